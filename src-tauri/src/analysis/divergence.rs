@@ -0,0 +1,353 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use super::saved::{AnalysisKind, AnalysisSpec};
+use super::{all_tests, ConditionDef};
+use crate::aoi::AoiSets;
+use crate::binning::{participant_curves, recording_curves, BinCounts, BinParams, RecordingCurve};
+use crate::stats::{self, Correction, ProportionScale, TTest, TfceParams};
+use crate::manifest::{self, WithManifest};
+use crate::{DbPool, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Bootstrapped divergence point analysis (Stone, Lago & Schad 2021)
+Resample participants, run a t test per bin between the two conditions,
+and take the first bin that starts a run of `sustain` significant bins.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivergenceParams {
+    #[serde(default = "default_n_boot")]
+    pub n_boot: usize,
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    /* minimum run of significant bins, in ms (rounded up to whole bins) */
+    #[serde(default = "default_sustain_ms")]
+    pub sustain_ms: f64,
+    #[serde(default = "default_ci_level")]
+    pub ci_level: f64,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /* scale the per-bin tests run on */
+    #[serde(default)]
+    pub scale: ProportionScale,
+    /* multiplicity correction reported per bin (onset detection stays on raw p) */
+    #[serde(default)]
+    pub correction: Correction,
+    /* TFCE on the per-bin t values, with a permutation null when set */
+    #[serde(default)]
+    pub tfce: Option<TfceParams>,
+    #[serde(default = "default_n_perm")]
+    pub n_perm: usize,
+}
+
+/* upper bounds on bootstrap resamples / permutations (user input; each one re-runs every bin test) */
+const MAX_BOOT: usize = 100_000;
+const MAX_PERM: usize = 100_000;
+
+fn default_n_boot() -> usize { 1000 }
+fn default_alpha() -> f64 { 0.05 }
+fn default_sustain_ms() -> f64 { 200.0 }
+fn default_ci_level() -> f64 { 0.95 }
+fn default_n_perm() -> usize { 1000 }
+
+impl Default for DivergenceParams {
+    fn default() -> Self {
+        DivergenceParams {
+            n_boot: default_n_boot(),
+            alpha: default_alpha(),
+            sustain_ms: default_sustain_ms(),
+            ci_level: default_ci_level(),
+            seed: None,
+            scale: ProportionScale::default(),
+            correction: Correction::default(),
+            tfce: None,
+            n_perm: default_n_perm(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DivergenceBin {
+    pub x_sec: f64,
+    pub mean_diff: f64,
+    pub t: f64,
+    pub p: f64,
+    pub p_adjusted: f64,
+    pub significant: bool,
+    pub tfce: Option<f64>,
+    /* family-wise p from the permutation distribution of max |TFCE| */
+    pub p_tfce: Option<f64>,
+}
+
+impl DivergenceParams {
+    pub fn check(&self) -> Result<(), String> {
+        if self.n_boot > MAX_BOOT {
+            return Err(format!("n_boot must be at most {MAX_BOOT} (got {})", self.n_boot));
+        }
+        if let Some(ref tfce) = self.tfce {
+            if self.n_perm > MAX_PERM {
+                return Err(format!("n_perm must be at most {MAX_PERM} (got {})", self.n_perm));
+            }
+            tfce.check()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DivergenceResult {
+    pub scale: ProportionScale,
+    pub correction: Correction,
+    pub condition_a: String,
+    pub condition_b: String,
+    /* true when exactly the same participants contribute to both conditions (paired t tests, else Welch) */
+    pub paired: bool,
+    pub n_participants: usize,
+    pub bin_ms: f64,
+    pub sustain_bins: usize,
+    pub seed: u64,
+    pub observed_onset_ms: Option<f64>,
+    pub observed_bins: Vec<DivergenceBin>,
+    pub n_boot: usize,
+    pub n_diverged: usize,
+    /* onsets from bootstrap samples that diverged at all */
+    pub onsets_ms: Vec<f64>,
+    pub mean_onset_ms: Option<f64>,
+    pub median_onset_ms: Option<f64>,
+    pub ci_low_ms: Option<f64>,
+    pub ci_high_ms: Option<f64>,
+}
+
+type Curves = BTreeMap<String, Vec<Option<f64>>>;
+type Draw<'a> = Vec<&'a Vec<Option<f64>>>;
+
+fn scaled_curves(curves: &BTreeMap<String, Vec<BinCounts>>, scale: ProportionScale) -> Curves {
+    curves
+        .iter()
+        .map(|(p, bins)| (p.clone(), bins.iter().map(|b| b.scaled(scale).map(|(v, _)| v)).collect()))
+        .collect()
+}
+
+/* Per-bin test of A − B for the given participant draws */
+fn bin_tests(a: &[&Vec<Option<f64>>], b: &[&Vec<Option<f64>>], paired: bool, num_bins: usize) -> Vec<Option<TTest>> {
+    (0..num_bins)
+        .map(|i| {
+            if paired {
+                let diffs: Vec<f64> = a
+                    .iter()
+                    .zip(b)
+                    .filter_map(|(x, y)| Some(x[i]? - y[i]?))
+                    .collect();
+                stats::one_sample_t(&diffs)
+            } else {
+                let xa: Vec<f64> = a.iter().filter_map(|x| x[i]).collect();
+                let xb: Vec<f64> = b.iter().filter_map(|y| y[i]).collect();
+                stats::welch_t(&xa, &xb)
+            }
+        })
+        .collect()
+}
+
+fn bin_t(tests: &[Option<TTest>]) -> Vec<f64> {
+    tests.iter().map(|t| t.map(|t| t.t).filter(|t| t.is_finite()).unwrap_or(0.0)).collect()
+}
+
+/* TFCE scores plus permutation p-values (sign flips when paired, label shuffles otherwise) */
+fn tfce_permutation(
+    a: &[&Vec<Option<f64>>],
+    b: &[&Vec<Option<f64>>],
+    paired: bool,
+    observed: &[Option<TTest>],
+    tfce: &TfceParams,
+    n_perm: usize,
+    rng: &mut StdRng,
+) -> (Vec<f64>, Vec<f64>) {
+    let num_bins = observed.len();
+    let t_obs = bin_t(observed);
+    let dh = stats::tfce_step(&t_obs, tfce.steps);
+    let scores = stats::tfce(&t_obs, dh, tfce);
+    let mut pooled: Draw = a.iter().chain(b).copied().collect();
+    let mut max_null: Vec<f64> = Vec::with_capacity(n_perm);
+    for _ in 0..n_perm {
+        let (pa, pb): (Draw, Draw) = if paired {
+            a.iter().zip(b).map(|(x, y)| if rng.gen_bool(0.5) { (*y, *x) } else { (*x, *y) }).unzip()
+        } else {
+            pooled.shuffle(rng);
+            (pooled[..a.len()].to_vec(), pooled[a.len()..].to_vec())
+        };
+        let t = bin_t(&bin_tests(&pa, &pb, paired, num_bins));
+        max_null.push(stats::tfce(&t, dh, tfce).iter().fold(0.0, |m, v| m.max(v.abs())));
+    }
+    let p = scores
+        .iter()
+        .map(|s| (1 + max_null.iter().filter(|m| **m >= s.abs()).count()) as f64 / (n_perm + 1) as f64)
+        .collect();
+    (scores, p)
+}
+
+fn first_sustained(tests: &[Option<TTest>], alpha: f64, sustain: usize) -> Option<usize> {
+    let sig: Vec<bool> = tests.iter().map(|t| t.is_some_and(|t| t.p < alpha)).collect();
+    (0..sig.len()).find(|&i| i + sustain <= sig.len() && sig[i..i + sustain].iter().all(|s| *s))
+}
+
+pub fn divergence_point(
+    names: (&str, &str),
+    a: &BTreeMap<String, Vec<BinCounts>>,
+    b: &BTreeMap<String, Vec<BinCounts>>,
+    bins: &BinParams,
+    params: &DivergenceParams,
+) -> DivergenceResult {
+    let (pa, pb) = (scaled_curves(a, params.scale), scaled_curves(b, params.scale));
+    // pair only on identical participant sets (as growth curves do), so nobody is silently dropped
+    let paired = pa.len() >= 2 && pa.keys().eq(pb.keys());
+    let bin_ms = bins.bin_ms;
+    let sustain = ((params.sustain_ms / bin_ms).ceil() as usize).max(1);
+    // keep generated seeds within 2^53 so they survive the round trip through JS numbers
+    let seed = params.seed.unwrap_or_else(|| rand::random::<u64>() >> 11);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let (ids_a, ids_b): (Vec<&String>, Vec<&String>) = (pa.keys().collect(), pb.keys().collect());
+
+    // Observed
+    let obs_a: Draw = ids_a.iter().map(|id| &pa[*id]).collect();
+    let obs_b: Draw = ids_b.iter().map(|id| &pb[*id]).collect();
+    let observed = bin_tests(&obs_a, &obs_b, paired, bins.num_bins);
+    let observed_onset = first_sustained(&observed, params.alpha, sustain);
+    let can_resample = !ids_a.is_empty() && !ids_b.is_empty();
+
+    // Bootstrap over participants (jointly when paired, per condition otherwise)
+    let mut onsets: Vec<f64> = Vec::new();
+    for _ in 0..if can_resample { params.n_boot } else { 0 } {
+        let (sa, sb): (Draw, Draw) = if paired {
+            (0..ids_a.len())
+                .map(|_| {
+                    let id = ids_a[rng.gen_range(0..ids_a.len())];
+                    (&pa[id], &pb[id])
+                })
+                .unzip()
+        } else {
+            (
+                (0..ids_a.len()).map(|_| &pa[ids_a[rng.gen_range(0..ids_a.len())]]).collect(),
+                (0..ids_b.len()).map(|_| &pb[ids_b[rng.gen_range(0..ids_b.len())]]).collect(),
+            )
+        };
+        let tests = bin_tests(&sa, &sb, paired, bins.num_bins);
+        if let Some(i) = first_sustained(&tests, params.alpha, sustain) {
+            onsets.push(i as f64 * bin_ms);
+        }
+    }
+
+    // Per-bin corrections
+    let x_sec = bins.x_sec();
+    let raw_p: Vec<f64> = observed.iter().map(|t| t.map(|t| t.p).unwrap_or(f64::NAN)).collect();
+    let adjusted = stats::adjust_p(&raw_p, params.correction);
+    // TFCE permutations run after the bootstrap so a seed reproduces the same onsets either way
+    let (tfce, p_tfce) = match &params.tfce {
+        Some(tp) if can_resample => {
+            let (s, p) = tfce_permutation(&obs_a, &obs_b, paired, &observed, tp, params.n_perm, &mut rng);
+            (s.into_iter().map(Some).collect(), p.into_iter().map(Some).collect())
+        }
+        _ => (vec![None; observed.len()], vec![None; observed.len()]),
+    };
+    let observed_bins = observed
+        .iter()
+        .enumerate()
+        .map(|(i, t)| DivergenceBin {
+            x_sec: x_sec[i],
+            mean_diff: t.map(|t| t.estimate).unwrap_or(f64::NAN),
+            t: t.map(|t| t.t).unwrap_or(f64::NAN),
+            p: raw_p[i],
+            p_adjusted: adjusted[i],
+            significant: t.is_some_and(|t| t.p < params.alpha),
+            tfce: tfce[i],
+            p_tfce: p_tfce[i],
+        })
+        .collect();
+
+    let mut sorted = onsets.clone();
+    sorted.sort_by(f64::total_cmp);
+    let tail = (1.0 - params.ci_level.clamp(0.0, 1.0)) / 2.0;
+    let summary = |q: f64| (!sorted.is_empty()).then(|| stats::quantile(&sorted, q));
+
+    DivergenceResult {
+        scale: params.scale,
+        correction: params.correction,
+        condition_a: names.0.to_string(),
+        condition_b: names.1.to_string(),
+        paired,
+        n_participants: if paired { ids_a.len() } else { ids_a.iter().chain(&ids_b).collect::<HashSet<_>>().len() },
+        bin_ms,
+        sustain_bins: sustain,
+        seed,
+        observed_onset_ms: observed_onset.map(|i| i as f64 * bin_ms),
+        observed_bins,
+        n_boot: if can_resample { params.n_boot } else { 0 },
+        n_diverged: onsets.len(),
+        mean_onset_ms: (!onsets.is_empty()).then(|| stats::mean(&onsets)),
+        median_onset_ms: summary(0.5),
+        ci_low_ms: summary(tail),
+        ci_high_ms: summary(1.0 - tail),
+        onsets_ms: onsets,
+    }
+}
+
+/* Divergence point between exactly two conditions */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_divergence_point(
+    conditions: Vec<ConditionDef>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    bins: Option<BinParams>,
+    options: Option<DivergenceParams>,
+    manifest: Option<bool>,
+    app: AppHandle,
+) -> Result<WithManifest<DivergenceResult>, String> {
+    if conditions.len() != 2 {
+        return Err("divergence analysis needs exactly two conditions".to_string());
+    }
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let sets = aoi.unwrap_or_default();
+    let params = bins.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    options.check()?;
+    let disabled_set = app.state::<DisabledStore>().0.read().unwrap().clone();
+
+    let curves = recording_curves(&conn, &disabled_set, &all_tests(&conditions), &participants, &cohort, &sets, &params)?;
+    let result = divergence_from_curves(&conditions, &curves, &params, &options)?;
+    let seed = result.seed;
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        aoi: sets,
+        bins: params.clone(),
+        divergence: DivergenceParams { seed: Some(seed), ..options.clone() },
+        ..AnalysisSpec::new(AnalysisKind::Divergence, conditions)
+    };
+    manifest::attach(&app, manifest, "get_divergence_point", spec, &disabled_set, Some(seed), result)
+}
+
+/* Pool recording curves for the two conditions and run the analysis */
+pub fn divergence_from_curves(
+    conditions: &[ConditionDef],
+    curves: &[RecordingCurve],
+    params: &BinParams,
+    options: &DivergenceParams,
+) -> Result<DivergenceResult, String> {
+    let [cond_a, cond_b] = conditions else {
+        return Err("divergence analysis needs exactly two conditions".to_string());
+    };
+    options.check()?;
+    let per = |c: &ConditionDef| {
+        let tests: HashSet<&str> = c.tests.iter().map(String::as_str).collect();
+        participant_curves(curves, &tests, params.num_bins)
+    };
+
+    Ok(divergence_point((&cond_a.name, &cond_b.name), &per(cond_a), &per(cond_b), params, options))
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use tauri::{AppHandle, Manager};
+
+use super::saved::{AnalysisKind, AnalysisSpec};
+use super::{all_tests, ConditionDef};
+use crate::aoi::AoiSets;
+use crate::binning::{participant_curves, recording_curves, BinCounts, BinParams, RecordingCurve};
+use crate::stats::{self, Correction, OlsFit, ProportionScale, TTest};
+use crate::manifest::{self, WithManifest};
+use crate::{DbPool, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Growth curve analysis (two-stage orthogonal polynomial regression)
+Stage 1: per participant × condition, OLS of P(blue | blue+red) on ot1..otK
+         (weighted least squares on the empirical-logit scale).
+Stage 2: coefficients summarised across participants; condition effects
+         against the first (reference) condition are paired differences when
+         both conditions have the same participants, Welch tests otherwise.
+────────────────────────────────────────────────────────────── */
+
+const MAX_DEGREE: usize = 4;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrowthOptions {
+    /* highest orthogonal time term (1..=4, default 4) */
+    #[serde(default)]
+    pub degree: Option<usize>,
+    #[serde(default)]
+    pub scale: ProportionScale,
+    /* applied within `coefficients` and within `interactions` */
+    #[serde(default)]
+    pub correction: Correction,
+}
+
+/* which t-test produced a coefficient row */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoefficientTest {
+    /* per-condition mean of participant coefficients against 0 */
+    OneSample,
+    /* condition − reference within participants (identical participant sets) */
+    Paired,
+    /* condition vs. reference as independent groups */
+    Welch,
+}
+
+impl CoefficientTest {
+    pub fn label(self) -> &'static str {
+        match self {
+            CoefficientTest::OneSample => "one-sample t",
+            CoefficientTest::Paired => "paired t",
+            CoefficientTest::Welch => "Welch t",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrowthCoefficient {
+    pub term: String,
+    pub condition: String,
+    pub estimate: f64,
+    pub se: f64,
+    pub t: f64,
+    pub df: f64,
+    pub p: f64,
+    pub p_adjusted: f64,
+    pub test: CoefficientTest,
+    /* participants entering the test (both groups for Welch), and their number */
+    pub n: usize,
+    pub participants: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantCoefficients {
+    pub participant: String,
+    pub condition: String,
+    pub beta: Vec<f64>,
+    pub se: Vec<f64>,
+    pub n_bins: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrowthConditionCurve {
+    pub condition: String,
+    pub n_participants: usize,
+    /* mean observed value per bin on `scale` (null when no participant looked at blue/red) */
+    pub observed: Vec<Option<f64>>,
+    pub fitted: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrowthCurveResult {
+    pub degree: usize,
+    pub scale: ProportionScale,
+    pub correction: Correction,
+    pub x_sec: Vec<f64>,
+    pub terms: Vec<String>,
+    /* orthogonal time terms (one row per term, one value per bin) */
+    pub time_terms: Vec<Vec<f64>>,
+    pub coefficients: Vec<GrowthCoefficient>,
+    /* condition × term effects relative to `reference` */
+    pub interactions: Vec<GrowthCoefficient>,
+    pub reference: Option<String>,
+    pub curves: Vec<GrowthConditionCurve>,
+    pub participants: Vec<ParticipantCoefficients>,
+}
+
+fn coefficient(term: &str, condition: &str, test: CoefficientTest, participants: Vec<String>, t: Option<TTest>) -> GrowthCoefficient {
+    let t = t.unwrap_or(TTest { estimate: f64::NAN, se: f64::NAN, t: f64::NAN, df: f64::NAN, p: f64::NAN });
+    GrowthCoefficient {
+        term: term.to_string(),
+        condition: condition.to_string(),
+        estimate: t.estimate,
+        se: t.se,
+        t: t.t,
+        df: t.df,
+        p: t.p,
+        p_adjusted: t.p,
+        test,
+        n: participants.len(),
+        participants,
+    }
+}
+
+fn adjust(coefs: &mut [GrowthCoefficient], method: Correction) {
+    let p: Vec<f64> = coefs.iter().map(|c| c.p).collect();
+    for (c, adj) in coefs.iter_mut().zip(stats::adjust_p(&p, method)) { c.p_adjusted = adj; }
+}
+
+/* Stage 1 for one participant curve; None when too few informative bins */
+fn fit_participant(bins: &[BinCounts], design: &[Vec<f64>], scale: ProportionScale) -> Option<(OlsFit, usize)> {
+    let mut x: Vec<Vec<f64>> = Vec::new();
+    let mut y: Vec<f64> = Vec::new();
+    let mut w: Vec<f64> = Vec::new();
+    for (b, row) in bins.iter().zip(design) {
+        let Some((v, wt)) = b.scaled(scale) else { continue; };
+        x.push(row.clone());
+        y.push(v);
+        w.push(wt);
+    }
+    let n = y.len();
+    stats::wls(&x, &y, &w).map(|fit| (fit, n))
+}
+
+pub fn growth_curve(
+    conditions: &[ConditionDef],
+    per_condition: &[BTreeMap<String, Vec<BinCounts>>],
+    params: &BinParams,
+    degree: usize,
+    options: &GrowthOptions,
+) -> GrowthCurveResult {
+    let scale = options.scale;
+    let x_sec = params.x_sec();
+    let time_terms = stats::orthogonal_poly(&x_sec, degree);
+    let design: Vec<Vec<f64>> = (0..x_sec.len())
+        .map(|i| std::iter::once(1.0).chain(time_terms.iter().map(|col| col[i])).collect())
+        .collect();
+    let terms: Vec<String> = std::iter::once("intercept".to_string())
+        .chain((1..=degree).map(|k| format!("ot{k}")))
+        .collect();
+
+    // Stage 1
+    let mut participants: Vec<ParticipantCoefficients> = Vec::new();
+    let mut betas: Vec<BTreeMap<String, Vec<f64>>> = Vec::new();
+    for (cond, curves) in conditions.iter().zip(per_condition) {
+        let mut fitted: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for (p, bins) in curves {
+            let Some((fit, n_bins)) = fit_participant(bins, &design, scale) else { continue; };
+            fitted.insert(p.clone(), fit.beta.clone());
+            participants.push(ParticipantCoefficients {
+                participant: p.clone(),
+                condition: cond.name.clone(),
+                beta: fit.beta,
+                se: fit.se,
+                n_bins,
+            });
+        }
+        betas.push(fitted);
+    }
+
+    // Stage 2: per-condition coefficient summaries + fitted curves
+    let mut coefficients: Vec<GrowthCoefficient> = Vec::new();
+    let mut curves: Vec<GrowthConditionCurve> = Vec::new();
+    for ((cond, fitted), raw) in conditions.iter().zip(&betas).zip(per_condition) {
+        let mut mean_beta = vec![f64::NAN; terms.len()];
+        for (j, term) in terms.iter().enumerate() {
+            let vals: Vec<f64> = fitted.values().map(|b| b[j]).collect();
+            if !vals.is_empty() { mean_beta[j] = stats::mean(&vals); }
+            let who = fitted.keys().cloned().collect();
+            coefficients.push(coefficient(term, &cond.name, CoefficientTest::OneSample, who, stats::one_sample_t(&vals)));
+        }
+        let observed = (0..x_sec.len())
+            .map(|i| {
+                let vals: Vec<f64> = raw.values().filter_map(|bins| bins[i].scaled(scale).map(|(v, _)| v)).collect();
+                (!vals.is_empty()).then(|| stats::mean(&vals))
+            })
+            .collect();
+        let fitted_curve = design
+            .iter()
+            .map(|row| row.iter().zip(&mean_beta).map(|(a, b)| a * b).sum())
+            .collect();
+        curves.push(GrowthConditionCurve {
+            condition: cond.name.clone(),
+            n_participants: fitted.len(),
+            observed,
+            fitted: fitted_curve,
+        });
+    }
+
+    // Condition interactions vs. reference: paired when both conditions have the same participants, Welch otherwise
+    let mut interactions: Vec<GrowthCoefficient> = Vec::new();
+    let reference = conditions.first().map(|c| c.name.clone());
+    if let Some(ref_betas) = betas.first() {
+        for (cond, fitted) in conditions.iter().zip(&betas).skip(1) {
+            let paired = fitted.len() >= 2 && fitted.keys().eq(ref_betas.keys());
+            for (j, term) in terms.iter().enumerate() {
+                let coef = if paired {
+                    let diffs: Vec<f64> = fitted.iter().map(|(p, b)| b[j] - ref_betas[p][j]).collect();
+                    coefficient(term, &cond.name, CoefficientTest::Paired, fitted.keys().cloned().collect(), stats::one_sample_t(&diffs))
+                } else {
+                    let a: Vec<f64> = fitted.values().map(|b| b[j]).collect();
+                    let r: Vec<f64> = ref_betas.values().map(|b| b[j]).collect();
+                    let who: BTreeSet<String> = fitted.keys().chain(ref_betas.keys()).cloned().collect();
+                    coefficient(term, &cond.name, CoefficientTest::Welch, who.into_iter().collect(), stats::welch_t(&a, &r))
+                };
+                interactions.push(coef);
+            }
+        }
+    }
+
+    adjust(&mut coefficients, options.correction);
+    adjust(&mut interactions, options.correction);
+
+    GrowthCurveResult {
+        degree,
+        scale,
+        correction: options.correction,
+        x_sec,
+        terms,
+        time_terms,
+        coefficients,
+        interactions,
+        reference,
+        curves,
+        participants,
+    }
+}
+
+/* Growth curve model on server-side binned curves */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fit_growth_curve(
+    conditions: Vec<ConditionDef>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    bins: Option<BinParams>,
+    options: Option<GrowthOptions>,
+    manifest: Option<bool>,
+    app: AppHandle,
+) -> Result<WithManifest<GrowthCurveResult>, String> {
+    if conditions.is_empty() {
+        return Err("at least one condition is required".to_string());
+    }
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let sets = aoi.unwrap_or_default();
+    let params = bins.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    let disabled_set = app.state::<DisabledStore>().0.read().unwrap().clone();
+
+    let curves = recording_curves(&conn, &disabled_set, &all_tests(&conditions), &participants, &cohort, &sets, &params)?;
+    let result = growth_from_curves(&conditions, &curves, &params, &options)?;
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        aoi: sets,
+        bins: params.clone(),
+        growth: options.clone(),
+        ..AnalysisSpec::new(AnalysisKind::Growth, conditions)
+    };
+    manifest::attach(&app, manifest, "fit_growth_curve", spec, &disabled_set, None, result)
+}
+
+/* Pool recording curves per condition and fit */
+pub fn growth_from_curves(
+    conditions: &[ConditionDef],
+    curves: &[RecordingCurve],
+    params: &BinParams,
+    options: &GrowthOptions,
+) -> Result<GrowthCurveResult, String> {
+    let degree = options.degree.unwrap_or(MAX_DEGREE).clamp(1, MAX_DEGREE);
+    if params.num_bins < degree + 2 {
+        return Err(format!("need at least {} bins for a degree-{degree} model", degree + 2));
+    }
+    let per_condition: Vec<_> = conditions
+        .iter()
+        .map(|c| {
+            let tests: HashSet<&str> = c.tests.iter().map(String::as_str).collect();
+            participant_curves(curves, &tests, params.num_bins)
+        })
+        .collect();
+
+    Ok(growth_curve(conditions, &per_condition, params, degree, options))
+}
@@ -0,0 +1,550 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tauri::{AppHandle, Manager};
+
+use super::saved::{AnalysisKind, AnalysisSpec};
+use super::ConditionDef;
+use crate::aoi::{self, AoiSets};
+use crate::binning::{recording_curves, RecordingCurve, WindowParams};
+use crate::stats::{self, Correction, ProportionScale};
+use crate::manifest::{self, WithManifest};
+use crate::{DbPool, DisabledStore, RowMap};
+
+/* ──────────────────────────────────────────────────────────────
+Linear mixed-effects model on trial-level window measures
+y = Xβ + Z_p b_p + Z_i b_i + ε  with crossed random intercepts for
+participant (b_p) and test_name (b_i). Fitted lme4-style: the deviance is
+profiled over θ = (σ_p/σ, σ_i/σ) and minimised with Nelder–Mead.
+Fixed effects are treatment-coded catalog fields; each factor gets a
+likelihood-ratio test (ML fits with and without its columns). Coefficient
+p-values are asymptotic Wald z tests: no Satterthwaite / Kenward–Roger
+denominator df, so they run liberal with few participants or items.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LmmOptions {
+    /* test_catalog columns used as categorical fixed effects */
+    #[serde(default = "default_fixed")]
+    pub fixed: Vec<String>,
+    /* report REML estimates (LRTs always compare ML fits) */
+    #[serde(default = "default_reml")]
+    pub reml: bool,
+    #[serde(default)]
+    pub scale: ProportionScale,
+    /* applied across the non-intercept fixed effects and across the LRTs */
+    #[serde(default)]
+    pub correction: Correction,
+}
+
+fn default_fixed() -> Vec<String> {
+    ["truth_value", "only_position", "morpheme"].iter().map(|s| s.to_string()).collect()
+}
+fn default_reml() -> bool { true }
+
+impl Default for LmmOptions {
+    fn default() -> Self {
+        LmmOptions {
+            fixed: default_fixed(),
+            reml: default_reml(),
+            scale: ProportionScale::default(),
+            correction: Correction::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LmmTrial {
+    pub test_name: String,
+    pub participant: String,
+    pub recording: String,
+    pub blue_n: i64,
+    pub red_n: i64,
+    pub value: f64,
+    pub weight: f64,
+    pub factors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FixedEffect {
+    pub term: String,
+    pub estimate: f64,
+    pub se: f64,
+    pub t: f64,
+    /* two-sided p of t against the standard normal (asymptotic Wald z) */
+    pub p: f64,
+    pub p_adjusted: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VarianceComponent {
+    pub group: String,
+    pub variance: f64,
+    pub sd: f64,
+    pub n_levels: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LikelihoodRatioTest {
+    pub factor: String,
+    pub df: usize,
+    pub chisq: f64,
+    pub p: f64,
+    pub p_adjusted: f64,
+    pub log_lik_full: f64,
+    pub log_lik_reduced: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LmmResult {
+    pub scale: ProportionScale,
+    pub reml: bool,
+    pub correction: Correction,
+    /* how fixed-effect p-values were computed ("wald_z": t against the standard normal, no df correction) */
+    pub fixed_effect_p: String,
+    pub n_obs: usize,
+    /* trials dropped for having no blue/red looks or a missing factor value */
+    pub n_dropped: usize,
+    /* factor → levels, reference level first */
+    pub levels: BTreeMap<String, Vec<String>>,
+    /* requested factors left out (fewer than two levels) */
+    pub dropped_factors: Vec<String>,
+    /* columns dropped as linear combinations of earlier ones */
+    pub aliased_terms: Vec<String>,
+    pub fixed_effects: Vec<FixedEffect>,
+    pub random_effects: Vec<VarianceComponent>,
+    /* log-likelihood (REML criterion / −2 when `reml`) */
+    pub log_lik: f64,
+    pub aic: f64,
+    pub bic: f64,
+    pub converged: bool,
+    pub lrt: Vec<LikelihoodRatioTest>,
+    pub trials: Vec<LmmTrial>,
+}
+
+/* Model frame: response, weights, fixed design rows and two crossed grouping factors */
+pub struct LmmData {
+    pub y: Vec<f64>,
+    pub w: Vec<f64>,
+    pub x: Vec<Vec<f64>>,
+    pub participant: Vec<usize>,
+    pub n_participants: usize,
+    pub item: Vec<usize>,
+    pub n_items: usize,
+}
+
+pub struct LmmFit {
+    pub beta: Vec<f64>,
+    pub cov_beta: Vec<f64>,
+    pub sigma2: f64,
+    pub theta: [f64; 2],
+    /* −2 log-likelihood (or REML criterion) */
+    pub deviance: f64,
+    pub converged: bool,
+}
+
+struct Evaluation {
+    deviance: f64,
+    beta: Vec<f64>,
+    sigma2: f64,
+    chol: Vec<f64>,
+}
+
+impl LmmData {
+    fn n_fixed(&self) -> usize {
+        self.x.first().map(Vec::len).unwrap_or(0)
+    }
+
+    fn with_columns(&self, keep: &[usize]) -> LmmData {
+        LmmData {
+            y: self.y.clone(),
+            w: self.w.clone(),
+            x: self.x.iter().map(|row| keep.iter().map(|&j| row[j]).collect()).collect(),
+            participant: self.participant.clone(),
+            n_participants: self.n_participants,
+            item: self.item.clone(),
+            n_items: self.n_items,
+        }
+    }
+
+    /* Penalised least squares for fixed θ; None when the system is singular */
+    fn evaluate(&self, theta: [f64; 2], reml: bool) -> Option<Evaluation> {
+        let (n, p) = (self.y.len(), self.n_fixed());
+        let q = self.n_participants + self.n_items;
+        let m = q + p;
+        if n <= p { return None; }
+        let mut a = vec![0.0; m * m];
+        let mut rhs = vec![0.0; m];
+        for i in 0..n {
+            let (y, w, x) = (self.y[i], self.w[i], &self.x[i]);
+            let z = [(self.participant[i], theta[0]), (self.n_participants + self.item[i], theta[1])];
+            for &(r, tr) in &z {
+                for &(c, tc) in &z { a[r * m + c] += w * tr * tc; }
+                for (j, xj) in x.iter().enumerate() {
+                    a[r * m + q + j] += w * tr * xj;
+                    a[(q + j) * m + r] += w * tr * xj;
+                }
+                rhs[r] += w * tr * y;
+            }
+            for (j, xj) in x.iter().enumerate() {
+                for (k, xk) in x.iter().enumerate() { a[(q + j) * m + q + k] += w * xj * xk; }
+                rhs[q + j] += w * xj * y;
+            }
+        }
+        for r in 0..q { a[r * m + r] += 1.0; }
+
+        let chol = stats::cholesky(&a, m)?;
+        let sol = stats::cholesky_solve(&chol, m, &rhs);
+        let (u, beta) = sol.split_at(q);
+        let mut r2: f64 = u.iter().map(|v| v * v).sum();
+        let mut log_w = 0.0;
+        for i in 0..n {
+            let fitted: f64 = self.x[i].iter().zip(beta).map(|(a, b)| a * b).sum::<f64>()
+                + theta[0] * u[self.participant[i]]
+                + theta[1] * u[self.n_participants + self.item[i]];
+            r2 += self.w[i] * (self.y[i] - fitted).powi(2);
+            log_w += self.w[i].ln();
+        }
+        let log_det = |range: std::ops::Range<usize>| -> f64 { range.map(|i| 2.0 * chol[i * m + i].ln()).sum() };
+        let dof = if reml { (n - p) as f64 } else { n as f64 };
+        let mut deviance = log_det(0..q) + dof * (1.0 + (2.0 * std::f64::consts::PI * r2 / dof).ln()) - log_w;
+        if reml { deviance += log_det(q..m); }
+        Some(Evaluation { deviance, beta: beta.to_vec(), sigma2: r2 / dof, chol })
+    }
+
+    pub fn fit(&self, reml: bool) -> Option<LmmFit> {
+        let objective = |t: &[f64]| {
+            self.evaluate([t[0].abs(), t[1].abs()], reml)
+                .map(|e| e.deviance)
+                .unwrap_or(f64::INFINITY)
+        };
+        let (best, _, converged) = stats::nelder_mead(objective, &[1.0, 1.0], 0.5, 1e-10, 1000);
+        let theta = [best[0].abs(), best[1].abs()];
+        let e = self.evaluate(theta, reml)?;
+
+        // Cov(β) = σ² · [A⁻¹]_ββ
+        let p = self.n_fixed();
+        let q = self.n_participants + self.n_items;
+        let m = q + p;
+        let mut cov_beta = vec![0.0; p * p];
+        let mut unit = vec![0.0; m];
+        for j in 0..p {
+            unit.iter_mut().for_each(|v| *v = 0.0);
+            unit[q + j] = 1.0;
+            let col = stats::cholesky_solve(&e.chol, m, &unit);
+            for k in 0..p { cov_beta[k * p + j] = e.sigma2 * col[q + k]; }
+        }
+        Some(LmmFit { beta: e.beta, cov_beta, sigma2: e.sigma2, theta, deviance: e.deviance, converged })
+    }
+}
+
+/* Greedy rank check: keep columns that are not linear combinations of the ones already kept */
+fn independent_columns(x: &[Vec<f64>]) -> Vec<usize> {
+    let p = x.first().map(Vec::len).unwrap_or(0);
+    let mut keep: Vec<usize> = Vec::new();
+    for j in 0..p {
+        let mut trial = keep.clone();
+        trial.push(j);
+        let k = trial.len();
+        let mut xtx = vec![0.0; k * k];
+        for row in x {
+            for (a, &ja) in trial.iter().enumerate() {
+                for (b, &jb) in trial.iter().enumerate() { xtx[a * k + b] += row[ja] * row[jb]; }
+            }
+        }
+        // scale to unit diagonal so the tolerance is relative
+        let diag: Vec<f64> = (0..k).map(|a| xtx[a * k + a].sqrt().max(1e-300)).collect();
+        for a in 0..k {
+            for b in 0..k { xtx[a * k + b] /= diag[a] * diag[b]; }
+        }
+        if stats::cholesky(&xtx, k).is_some_and(|l| l[(k - 1) * k + k - 1] > 1e-6) {
+            keep.push(j);
+        }
+    }
+    keep
+}
+
+/* Fit the model; `factor_columns` maps each factor to its design columns (for the LRTs) */
+pub fn lmm(data: &LmmData, terms: &[String], factor_columns: &[(String, Vec<usize>)], reml: bool) -> Option<(LmmFit, Vec<FixedEffect>, Vec<LikelihoodRatioTest>)> {
+    let fit = data.fit(reml)?;
+    let p = data.n_fixed();
+    let fixed_effects = terms
+        .iter()
+        .enumerate()
+        .map(|(j, term)| {
+            let se = fit.cov_beta[j * p + j].max(0.0).sqrt();
+            let t = fit.beta[j] / se;
+            let p = stats::z_two_sided_p(t);
+            FixedEffect { term: term.clone(), estimate: fit.beta[j], se, t, p, p_adjusted: p }
+        })
+        .collect();
+
+    let full_ml = if reml { data.fit(false)?.deviance } else { fit.deviance };
+    let lrt = factor_columns
+        .iter()
+        .filter(|(_, cols)| !cols.is_empty())
+        .filter_map(|(factor, cols)| {
+            let keep: Vec<usize> = (0..p).filter(|j| !cols.contains(j)).collect();
+            let reduced = data.with_columns(&keep).fit(false)?;
+            let chisq = (reduced.deviance - full_ml).max(0.0);
+            let p = stats::chi2_sf(chisq, cols.len() as f64);
+            Some(LikelihoodRatioTest {
+                factor: factor.clone(),
+                df: cols.len(),
+                chisq,
+                p,
+                p_adjusted: p,
+                log_lik_full: -full_ml / 2.0,
+                log_lik_reduced: -reduced.deviance / 2.0,
+            })
+        })
+        .collect();
+    Some((fit, fixed_effects, lrt))
+}
+
+/* Mixed model on one window per trial (recording) */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fit_lmm(
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    window: Option<WindowParams>,
+    options: Option<LmmOptions>,
+    manifest: Option<bool>,
+    app: AppHandle,
+) -> Result<WithManifest<LmmResult>, String> {
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let sets = aoi.unwrap_or_default();
+    let window = window.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    let disabled_set = app.state::<DisabledStore>().0.read().unwrap().clone();
+
+    let catalog = aoi::load_catalog(&conn)?;
+    let curves = recording_curves(&conn, &disabled_set, &tests, &participants, &cohort, &sets, &window.as_bins()?)?;
+    let result = lmm_from_curves(&catalog, &curves, &options)?;
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        aoi: sets,
+        window: window.clone(),
+        lmm: options.clone(),
+        ..AnalysisSpec::new(AnalysisKind::Lmm, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    manifest::attach(&app, manifest, "fit_lmm", spec, &disabled_set, None, result)
+}
+
+/* Trial measures, coding and fit for curves binned with one window bin */
+pub fn lmm_from_curves(catalog: &HashMap<String, RowMap>, curves: &[RecordingCurve], options: &LmmOptions) -> Result<LmmResult, String> {
+    // Trial-level measures
+    let mut trials: Vec<LmmTrial> = Vec::new();
+    let mut n_dropped = 0;
+    for c in curves {
+        let bin = c.bins.first().cloned().unwrap_or_default();
+        let row = catalog.get(&c.test_name);
+        let factors: Option<BTreeMap<String, String>> = options
+            .fixed
+            .iter()
+            .map(|f| {
+                let v = row.and_then(|r| aoi::pick(r, f)).map(str::trim).filter(|v| !v.is_empty())?;
+                Some((f.clone(), v.to_string()))
+            })
+            .collect();
+        let (Some((value, weight)), Some(factors)) = (bin.scaled(options.scale), factors) else {
+            n_dropped += 1;
+            continue;
+        };
+        trials.push(LmmTrial {
+            test_name: c.test_name.clone(),
+            participant: c.participant.clone(),
+            recording: c.recording.clone(),
+            blue_n: bin.blue,
+            red_n: bin.red,
+            value,
+            weight,
+            factors,
+        });
+    }
+    if trials.is_empty() {
+        return Err("no trials with blue/red looks in the window".to_string());
+    }
+
+    // Treatment coding (reference = first level in sort order)
+    let mut levels: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut dropped_factors: Vec<String> = Vec::new();
+    for f in &options.fixed {
+        let lv: BTreeSet<&str> = trials.iter().map(|t| t.factors[f].as_str()).collect();
+        if lv.len() < 2 {
+            dropped_factors.push(f.clone());
+        } else {
+            levels.insert(f.clone(), lv.into_iter().map(str::to_string).collect());
+        }
+    }
+    let mut all_terms: Vec<String> = vec!["intercept".to_string()];
+    let mut term_factor: Vec<Option<&str>> = vec![None];
+    for f in options.fixed.iter().filter(|f| levels.contains_key(*f)) {
+        for lv in &levels[f][1..] {
+            all_terms.push(format!("{f}={lv}"));
+            term_factor.push(Some(f.as_str()));
+        }
+    }
+    let full_x: Vec<Vec<f64>> = trials
+        .iter()
+        .map(|t| {
+            std::iter::once(1.0)
+                .chain(options.fixed.iter().filter(|f| levels.contains_key(*f)).flat_map(|f| {
+                    levels[f][1..].iter().map(move |lv| if t.factors[f] == *lv { 1.0 } else { 0.0 })
+                }))
+                .collect()
+        })
+        .collect();
+    let keep = independent_columns(&full_x);
+    let aliased_terms: Vec<String> = (0..all_terms.len()).filter(|j| !keep.contains(j)).map(|j| all_terms[j].clone()).collect();
+    let terms: Vec<String> = keep.iter().map(|&j| all_terms[j].clone()).collect();
+    let factor_columns: Vec<(String, Vec<usize>)> = levels
+        .keys()
+        .map(|f| {
+            let cols = keep.iter().enumerate().filter(|(_, &j)| term_factor[j] == Some(f.as_str())).map(|(k, _)| k).collect();
+            (f.clone(), cols)
+        })
+        .collect();
+
+    // Grouping factors
+    let index = |names: Vec<&str>| -> (Vec<usize>, usize) {
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        let idx = names.iter().map(|n| { let next = ids.len(); *ids.entry(n).or_insert(next) }).collect();
+        (idx, ids.len())
+    };
+    let (participant, n_participants) = index(trials.iter().map(|t| t.participant.as_str()).collect());
+    let (item, n_items) = index(trials.iter().map(|t| t.test_name.as_str()).collect());
+    let data = LmmData {
+        y: trials.iter().map(|t| t.value).collect(),
+        w: trials.iter().map(|t| t.weight).collect(),
+        x: full_x.iter().map(|row| keep.iter().map(|&j| row[j]).collect()).collect(),
+        participant,
+        n_participants,
+        item,
+        n_items,
+    };
+
+    let (fit, mut fixed_effects, mut lrt) = lmm(&data, &terms, &factor_columns, options.reml)
+        .ok_or_else(|| "mixed model could not be fitted (singular system)".to_string())?;
+    let effects: Vec<&mut FixedEffect> = fixed_effects.iter_mut().filter(|f| f.term != "intercept").collect();
+    let adjusted = stats::adjust_p(&effects.iter().map(|f| f.p).collect::<Vec<_>>(), options.correction);
+    for (f, adj) in effects.into_iter().zip(adjusted) { f.p_adjusted = adj; }
+    let adjusted = stats::adjust_p(&lrt.iter().map(|l| l.p).collect::<Vec<_>>(), options.correction);
+    for (l, adj) in lrt.iter_mut().zip(adjusted) { l.p_adjusted = adj; }
+    let component = |group: &str, variance: f64, n_levels: usize| VarianceComponent {
+        group: group.to_string(),
+        variance,
+        sd: variance.sqrt(),
+        n_levels,
+    };
+    let n = data.y.len() as f64;
+    let n_params = (terms.len() + 3) as f64;
+    let log_lik = -fit.deviance / 2.0;
+    Ok(LmmResult {
+        scale: options.scale,
+        reml: options.reml,
+        correction: options.correction,
+        fixed_effect_p: "wald_z".to_string(),
+        n_obs: data.y.len(),
+        n_dropped,
+        levels,
+        dropped_factors,
+        aliased_terms,
+        fixed_effects,
+        random_effects: vec![
+            component("participant", fit.sigma2 * fit.theta[0].powi(2), n_participants),
+            component("test_name", fit.sigma2 * fit.theta[1].powi(2), n_items),
+            component("residual", fit.sigma2, data.y.len()),
+        ],
+        log_lik,
+        aic: -2.0 * log_lik + 2.0 * n_params,
+        bic: -2.0 * log_lik + n_params * n.ln(),
+        converged: fit.converged,
+        lrt,
+        trials,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* fully crossed, one observation per participant × item, unit weights */
+    fn crossed(n_p: usize, n_i: usize, x: impl Fn(usize) -> Vec<f64>, y: impl Fn(usize, usize) -> f64) -> LmmData {
+        let cells: Vec<(usize, usize)> = (0..n_p).flat_map(|p| (0..n_i).map(move |i| (p, i))).collect();
+        LmmData {
+            y: cells.iter().map(|&(p, i)| y(p, i)).collect(),
+            w: vec![1.0; cells.len()],
+            x: cells.iter().map(|&(_, i)| x(i)).collect(),
+            participant: cells.iter().map(|c| c.0).collect(),
+            n_participants: n_p,
+            item: cells.iter().map(|c| c.1).collect(),
+            n_items: n_i,
+        }
+    }
+
+    fn close(got: f64, want: f64, rel: f64) -> bool {
+        (got - want).abs() <= rel * want.abs().max(1e-12)
+    }
+
+    const A: [f64; 6] = [-3.0, 1.5, 0.5, 2.0, -1.0, 0.0];
+    const B: [f64; 5] = [2.0, -1.0, 0.5, -2.5, 1.0];
+
+    fn noise(p: usize, i: usize) -> f64 {
+        0.4 * ((7 * p + 3 * i) as f64).sin()
+    }
+
+    /* balanced two-way crossed design: REML equals the ANOVA (expected mean squares) estimates */
+    #[test]
+    fn reml_matches_anova_estimates_when_balanced() {
+        let (n_p, n_i) = (A.len(), B.len());
+        let y = |p: usize, i: usize| 10.0 + A[p] + B[i] + noise(p, i);
+        let data = crossed(n_p, n_i, |_| vec![1.0], y);
+
+        let grand = data.y.iter().sum::<f64>() / data.y.len() as f64;
+        let p_mean: Vec<f64> = (0..n_p).map(|p| (0..n_i).map(|i| y(p, i)).sum::<f64>() / n_i as f64).collect();
+        let i_mean: Vec<f64> = (0..n_i).map(|i| (0..n_p).map(|p| y(p, i)).sum::<f64>() / n_p as f64).collect();
+        let ms_p = n_i as f64 * p_mean.iter().map(|m| (m - grand).powi(2)).sum::<f64>() / (n_p - 1) as f64;
+        let ms_i = n_p as f64 * i_mean.iter().map(|m| (m - grand).powi(2)).sum::<f64>() / (n_i - 1) as f64;
+        let ss_e: f64 = (0..n_p)
+            .flat_map(|p| (0..n_i).map(move |i| (p, i)))
+            .map(|(p, i)| (y(p, i) - p_mean[p] - i_mean[i] + grand).powi(2))
+            .sum();
+        let sigma2 = ss_e / ((n_p - 1) * (n_i - 1)) as f64;
+        let var_p = (ms_p - sigma2) / n_i as f64;
+        let var_i = (ms_i - sigma2) / n_p as f64;
+        assert!(var_p > 0.0 && var_i > 0.0);
+
+        let fit = data.fit(true).expect("fit");
+        assert!(fit.converged);
+        assert!(close(fit.sigma2, sigma2, 1e-4), "{} vs {sigma2}", fit.sigma2);
+        assert!(close(fit.sigma2 * fit.theta[0].powi(2), var_p, 1e-4));
+        assert!(close(fit.sigma2 * fit.theta[1].powi(2), var_i, 1e-4));
+        assert!(close(fit.beta[0], grand, 1e-9));
+        // Var(ȳ..) = σ²_p / P + σ²_i / I + σ² / (P·I)
+        let var_mean = var_p / n_p as f64 + var_i / n_i as f64 + sigma2 / (n_p * n_i) as f64;
+        assert!(close(fit.cov_beta[0], var_mean, 1e-3), "{} vs {var_mean}", fit.cov_beta[0]);
+    }
+
+    /* a balanced item-level factor is estimated by the difference of its level means */
+    #[test]
+    fn balanced_item_factor_is_a_mean_difference() {
+        let effect = |i: usize| if i >= 3 { 1.0 } else { 0.0 };
+        let b = [0.5, -0.5, 0.25, 0.3, -0.2, -0.1];
+        let y = |p: usize, i: usize| 2.0 + 1.5 * effect(i) + A[p] + b[i] + noise(p, i);
+        let data = crossed(A.len(), b.len(), |i| vec![1.0, effect(i)], y);
+        let mean = |level: f64| {
+            let v: Vec<f64> = data.y.iter().zip(&data.x).filter(|(_, x)| x[1] == level).map(|(y, _)| *y).collect();
+            v.iter().sum::<f64>() / v.len() as f64
+        };
+        let terms = ["intercept".to_string(), "group=b".to_string()];
+        let (fit, fixed, lrt) = lmm(&data, &terms, &[("group".to_string(), vec![1])], true).expect("fit");
+        assert!(close(fit.beta[1], mean(1.0) - mean(0.0), 1e-6));
+        assert!(close(fixed[1].p, stats::z_two_sided_p(fixed[1].t), 1e-12));
+        assert_eq!((lrt.len(), lrt[0].df), (1, 1));
+        assert!(lrt[0].chisq > 0.0 && lrt[0].log_lik_full >= lrt[0].log_lik_reduced);
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+pub mod divergence;
+pub mod growth;
+pub mod lmm;
+pub mod multiplicity;
+pub mod saved;
+
+/* A named condition = a set of tests pooled together (like a CompareGroup on the Advanced page) */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionDef {
+    pub name: String,
+    pub tests: Vec<String>,
+}
+
+/* Union of the tests of all conditions, de-duplicated, in first-seen order */
+pub fn all_tests(conditions: &[ConditionDef]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for t in conditions.iter().flat_map(|c| c.tests.iter()) {
+        if !out.contains(t) { out.push(t.clone()); }
+    }
+    out
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::stats::{self, Correction, TfceParams};
+
+/* ──────────────────────────────────────────────────────────────
+Multiplicity corrections for p-values / statistics computed elsewhere
+(e.g. the per-bin tests of the Advanced Compare page).
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdjustedPValues {
+    pub method: Correction,
+    /* null where the input was null */
+    pub p_adjusted: Vec<Option<f64>>,
+}
+
+#[tauri::command]
+pub async fn adjust_p_values(p_values: Vec<Option<f64>>, method: Correction) -> Result<AdjustedPValues, String> {
+    let p: Vec<f64> = p_values.iter().map(|v| v.unwrap_or(f64::NAN)).collect();
+    if let Some(bad) = p.iter().find(|v| !v.is_nan() && !(0.0..=1.0).contains(*v)) {
+        return Err(format!("p-value out of range: {bad}"));
+    }
+    let p_adjusted = stats::adjust_p(&p, method)
+        .into_iter()
+        .map(|v| (!v.is_nan()).then_some(v))
+        .collect();
+    Ok(AdjustedPValues { method, p_adjusted })
+}
+
+/* TFCE scores for a 1-D statistic (no null distribution: that needs the raw data) */
+#[tauri::command]
+pub async fn tfce_scores(statistics: Vec<Option<f64>>, options: Option<TfceParams>) -> Result<Vec<f64>, String> {
+    let options = options.unwrap_or_default();
+    options.check()?;
+    let stat: Vec<f64> = statistics.iter().map(|v| v.unwrap_or(0.0)).collect();
+    Ok(stats::tfce(&stat, stats::tfce_step(&stat, options.steps), &options))
+}
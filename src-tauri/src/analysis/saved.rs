@@ -0,0 +1,426 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
+
+use super::divergence::{divergence_from_curves, DivergenceParams, DivergenceResult};
+use super::growth::{growth_from_curves, GrowthCurveResult, GrowthOptions};
+use super::lmm::{lmm_from_curves, LmmOptions, LmmResult};
+use super::{all_tests, ConditionDef};
+use crate::aoi::{self, AoiSets};
+use crate::binning::{aoi_bins, recording_curves, AoiBinsResult, BinParams, RecordingCurve, WindowParams};
+use crate::export::{workbook, WorkbookContents};
+use crate::items::{item_stats, ItemOptions, ItemStatsOutput};
+use crate::manifest::{self, Manifest};
+use crate::measures::{window_measures, MeasureSpec, WindowMeasures};
+use crate::participant::{participant_summary, ParticipantSummaries};
+use crate::quality::{self, PrecisionOptions, PrecisionResult, QualityExclusion, QualityRules};
+use crate::transitions::{aoi_transitions, TransitionOptions, TransitionResult};
+use crate::{box_counts, gaze_stats, project, BoxStatsOptions, DbPool, DisabledSlice, DisabledStore, GazeStats};
+
+/* ──────────────────────────────────────────────────────────────
+Saved analysis definitions: selection (conditions/tests, participants,
+recordings), AOI sets, bin/window parameters, exclusions and the options
+of one analysis kind. Stored per DB in a versioned analyses.json next to
+disabled_slices.json (the DB itself is opened read-only).
+────────────────────────────────────────────────────────────── */
+
+/* bump when the stored layout changes */
+const ANALYSES_VERSION: u32 = 1;
+
+/* serialises read-modify-write of analyses.json */
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisKind {
+    Bins,
+    Growth,
+    Divergence,
+    Lmm,
+    /* the Excel export (export_workbook) */
+    Workbook,
+    /* per-test proportions (get_item_stats) */
+    Items,
+    /* trial × AOI window measures (get_window_measures) */
+    Measures,
+    /* AOI transition matrices (get_aoi_transitions) */
+    Transitions,
+    /* per-child summaries (get_participant_summary) */
+    Participants,
+    /* RMS sample-to-sample precision (get_precision) */
+    Precision,
+    /* Box shares of one test (get_box_stats) */
+    BoxStats,
+}
+
+impl AnalysisKind {
+    /* kinds built on binned recording curves; the others summarise whole slices */
+    pub fn is_curve(self) -> bool {
+        matches!(self, AnalysisKind::Bins | AnalysisKind::Growth | AnalysisKind::Divergence | AnalysisKind::Lmm)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExclusionProfile {
+    /* apply the DB's disabled slices when running */
+    #[serde(default = "default_true")]
+    pub use_disabled: bool,
+    /* extra slices excluded by this analysis only */
+    #[serde(default)]
+    pub slices: Vec<DisabledSlice>,
+    /* calibration / precision thresholds (see quality.rs) */
+    #[serde(default)]
+    pub quality: Option<QualityRules>,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for ExclusionProfile {
+    fn default() -> Self {
+        ExclusionProfile { use_disabled: true, slices: Vec::new(), quality: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisSpec {
+    pub kind: AnalysisKind,
+    /* selected tests, grouped into named conditions (bins/lmm use their union) */
+    pub conditions: Vec<ConditionDef>,
+    #[serde(default)]
+    pub participants: Vec<String>,
+    /* empty = every recording */
+    #[serde(default)]
+    pub recordings: Vec<String>,
+    /* restrict to one attached cohort */
+    #[serde(default)]
+    pub cohort: Option<String>,
+    #[serde(default)]
+    pub aoi: AoiSets,
+    #[serde(default)]
+    pub bins: BinParams,
+    #[serde(default)]
+    pub window: WindowParams,
+    #[serde(default)]
+    pub exclusions: ExclusionProfile,
+    #[serde(default)]
+    pub transforms: bool,
+    /* item and participant summaries */
+    #[serde(default)]
+    pub items: ItemOptions,
+    #[serde(default)]
+    pub measures: MeasureSpec,
+    #[serde(default)]
+    pub transitions: TransitionOptions,
+    #[serde(default)]
+    pub precision: PrecisionOptions,
+    #[serde(default)]
+    pub box_stats: BoxStatsOptions,
+    #[serde(default)]
+    pub growth: GrowthOptions,
+    #[serde(default)]
+    pub divergence: DivergenceParams,
+    #[serde(default)]
+    pub lmm: LmmOptions,
+}
+
+impl AnalysisSpec {
+    /* defaults for everything but the kind and the tests */
+    pub fn new(kind: AnalysisKind, conditions: Vec<ConditionDef>) -> Self {
+        AnalysisSpec {
+            kind,
+            conditions,
+            participants: Vec::new(),
+            recordings: Vec::new(),
+            cohort: None,
+            aoi: AoiSets::default(),
+            bins: BinParams::default(),
+            window: WindowParams::default(),
+            exclusions: ExclusionProfile::default(),
+            transforms: false,
+            items: ItemOptions::default(),
+            measures: MeasureSpec::default(),
+            transitions: TransitionOptions::default(),
+            precision: PrecisionOptions::default(),
+            box_stats: BoxStatsOptions::default(),
+            growth: GrowthOptions::default(),
+            divergence: DivergenceParams::default(),
+            lmm: LmmOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Analysis {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub created_ms: u64,
+    pub updated_ms: u64,
+    pub spec: AnalysisSpec,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AnalysesFile {
+    version: u32,
+    analyses: Vec<Analysis>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisSummary {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub kind: AnalysisKind,
+    pub updated_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "result", rename_all = "lowercase")]
+pub enum AnalysisOutput {
+    Bins(AoiBinsResult),
+    Growth(GrowthCurveResult),
+    Divergence(DivergenceResult),
+    Lmm(LmmResult),
+    Workbook(WorkbookContents),
+    Items(ItemStatsOutput),
+    Measures(WindowMeasures),
+    Transitions(TransitionResult),
+    Participants(ParticipantSummaries),
+    Precision(PrecisionResult),
+    BoxStats(GazeStats),
+}
+
+impl AnalysisOutput {
+    /* RNG seed actually used (divergence bootstrap) */
+    pub fn seed(&self) -> Option<u64> {
+        match self {
+            AnalysisOutput::Divergence(r) => Some(r.seed),
+            _ => None,
+        }
+    }
+
+    /* the bare result, as the matching command would return it */
+    pub fn result_value(&self) -> Result<Value, String> {
+        let mut v = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        Ok(v.get_mut("result").map(Value::take).unwrap_or(Value::Null))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisRun {
+    pub analysis_id: String,
+    pub name: String,
+    pub ran_at_ms: u64,
+    #[serde(flatten)]
+    pub output: AnalysisOutput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<Manifest>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn load(file: &Path) -> Result<Vec<Analysis>, String> {
+    let Ok(bytes) = fs::read(file) else { return Ok(Vec::new()); };
+    let stored: AnalysesFile = serde_json::from_slice(&bytes).map_err(|e| format!("analyses.json: {e}"))?;
+    if stored.version > ANALYSES_VERSION {
+        return Err(format!("analyses.json was written by a newer version (v{})", stored.version));
+    }
+    Ok(stored.analyses)
+}
+
+fn save(file: &Path, analyses: Vec<Analysis>) -> Result<(), String> {
+    if let Some(parent) = file.parent() { let _ = fs::create_dir_all(parent); }
+    let json = serde_json::to_vec_pretty(&AnalysesFile { version: ANALYSES_VERSION, analyses }).map_err(|e| e.to_string())?;
+    fs::write(file, json).map_err(|e| e.to_string())
+}
+
+/* Load analyses.json of the active DB, apply `f` and write it back */
+fn update<T>(app: &AppHandle, f: impl FnOnce(&mut Vec<Analysis>) -> Result<T, String>) -> Result<T, String> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    let file = project::data_file(app, "analyses.json")?;
+    let mut analyses = load(&file)?;
+    let out = f(&mut analyses)?;
+    save(&file, analyses)?;
+    Ok(out)
+}
+
+pub fn find(app: &AppHandle, id: &str) -> Result<Analysis, String> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    load(&project::data_file(app, "analyses.json")?)?
+        .into_iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("unknown analysis: {id}"))
+}
+
+fn new_id() -> String {
+    format!("{:012x}", rand::random::<u64>() >> 16)
+}
+
+fn check(spec: &AnalysisSpec) -> Result<(), String> {
+    // slice summaries take an empty selection as every test
+    if spec.kind.is_curve() && spec.conditions.iter().all(|c| c.tests.is_empty()) {
+        return Err("an analysis needs at least one test".to_string());
+    }
+    if spec.kind == AnalysisKind::Divergence && spec.conditions.len() != 2 {
+        return Err("divergence analysis needs exactly two conditions".to_string());
+    }
+    Ok(())
+}
+
+/* Slices of the spec's tests failing its quality rules (empty without rules) */
+pub fn quality_exclusions(app: &AppHandle, conn: &rusqlite::Connection, spec: &AnalysisSpec) -> Result<Vec<QualityExclusion>, String> {
+    match &spec.exclusions.quality {
+        Some(rules) => quality::rule_exclusions(app, conn, rules, &all_tests(&spec.conditions), &spec.participants, &spec.cohort),
+        None => Ok(Vec::new()),
+    }
+}
+
+/* Slices a spec excludes given the DB's current disabled slices and its quality exclusions */
+pub fn exclusions(spec: &AnalysisSpec, disabled: &HashSet<DisabledSlice>, quality: &[QualityExclusion]) -> HashSet<DisabledSlice> {
+    let mut excluded: HashSet<DisabledSlice> = if spec.exclusions.use_disabled { disabled.clone() } else { HashSet::new() };
+    excluded.extend(spec.exclusions.slices.iter().cloned());
+    excluded.extend(quality.iter().map(|q| q.slice.clone()));
+    excluded
+}
+
+/* Run a spec against `conn` with `excluded` slices left out (see `exclusions`) */
+pub fn run(app: &AppHandle, conn: &rusqlite::Connection, spec: &AnalysisSpec, excluded: &HashSet<DisabledSlice>) -> Result<AnalysisOutput, String> {
+    let tests = all_tests(&spec.conditions);
+    let curves = |params: &BinParams| -> Result<Vec<RecordingCurve>, String> {
+        let mut curves = recording_curves(conn, excluded, &tests, &spec.participants, &spec.cohort, &spec.aoi, params)?;
+        if !spec.recordings.is_empty() { curves.retain(|c| spec.recordings.contains(&c.recording)); }
+        Ok(curves)
+    };
+    Ok(match spec.kind {
+        AnalysisKind::Bins => AnalysisOutput::Bins(aoi_bins(curves(&spec.bins)?, &spec.bins, spec.transforms)),
+        AnalysisKind::Growth => AnalysisOutput::Growth(growth_from_curves(&spec.conditions, &curves(&spec.bins)?, &spec.bins, &spec.growth)?),
+        AnalysisKind::Divergence => {
+            AnalysisOutput::Divergence(divergence_from_curves(&spec.conditions, &curves(&spec.bins)?, &spec.bins, &spec.divergence)?)
+        }
+        AnalysisKind::Lmm => {
+            let catalog = aoi::load_catalog(conn)?;
+            AnalysisOutput::Lmm(lmm_from_curves(&catalog, &curves(&spec.window.as_bins()?)?, &spec.lmm)?)
+        }
+        AnalysisKind::Workbook => AnalysisOutput::Workbook(workbook(app, conn, &tests, &spec.participants, &spec.cohort, excluded)?.1),
+        AnalysisKind::Items => AnalysisOutput::Items(item_stats(app, conn, spec, excluded)?),
+        AnalysisKind::Measures => AnalysisOutput::Measures(window_measures(conn, excluded, &tests, &spec.participants, &spec.cohort, &spec.measures)?),
+        AnalysisKind::Transitions => {
+            AnalysisOutput::Transitions(aoi_transitions(conn, excluded, &tests, &spec.participants, &spec.cohort, &spec.transitions)?)
+        }
+        AnalysisKind::Participants => {
+            AnalysisOutput::Participants(ParticipantSummaries { participants: participant_summary(app, conn, spec, excluded)? })
+        }
+        AnalysisKind::Precision => AnalysisOutput::Precision(quality::precision(app, conn, &tests, &spec.participants, &spec.cohort, excluded, &spec.precision)?),
+        AnalysisKind::BoxStats => {
+            let [test] = tests.as_slice() else { return Err("box stats take exactly one test".to_string()); };
+            let counts = box_counts(conn, None, test, &spec.participants, &spec.cohort, &spec.box_stats, excluded)?;
+            AnalysisOutput::BoxStats(gaze_stats(counts, spec.transforms))
+        }
+    })
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn list_analyses(app: AppHandle) -> Result<Vec<AnalysisSummary>, String> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    let mut out: Vec<AnalysisSummary> = load(&project::data_file(&app, "analyses.json")?)?
+        .into_iter()
+        .map(|a| AnalysisSummary { id: a.id, name: a.name, description: a.description, kind: a.spec.kind, updated_ms: a.updated_ms })
+        .collect();
+    out.sort_by_key(|a| std::cmp::Reverse(a.updated_ms));
+    Ok(out)
+}
+
+#[tauri::command]
+pub async fn load_analysis(app: AppHandle, id: String) -> Result<Analysis, String> {
+    find(&app, &id)
+}
+
+/* Create (no `id`) or overwrite an analysis */
+#[tauri::command]
+pub async fn save_analysis(
+    app: AppHandle,
+    id: Option<String>,
+    name: String,
+    description: Option<String>,
+    spec: AnalysisSpec,
+) -> Result<Analysis, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() { return Err("missing param: name".to_string()); }
+    check(&spec)?;
+    update(&app, |analyses| {
+        let now = now_ms();
+        match id.filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let a = analyses.iter_mut().find(|a| a.id == id).ok_or_else(|| format!("unknown analysis: {id}"))?;
+                a.name = name;
+                a.description = description;
+                a.spec = spec;
+                a.updated_ms = now;
+                Ok(a.clone())
+            }
+            None => {
+                let a = Analysis { id: new_id(), name, description, created_ms: now, updated_ms: now, spec };
+                analyses.push(a.clone());
+                Ok(a)
+            }
+        }
+    })
+}
+
+#[tauri::command]
+pub async fn duplicate_analysis(app: AppHandle, id: String, name: Option<String>) -> Result<Analysis, String> {
+    update(&app, |analyses| {
+        let src = analyses.iter().find(|a| a.id == id).ok_or_else(|| format!("unknown analysis: {id}"))?;
+        let now = now_ms();
+        let copy = Analysis {
+            id: new_id(),
+            name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).unwrap_or_else(|| format!("{} (copy)", src.name)),
+            description: src.description.clone(),
+            created_ms: now,
+            updated_ms: now,
+            spec: src.spec.clone(),
+        };
+        analyses.push(copy.clone());
+        Ok(copy)
+    })
+}
+
+#[tauri::command]
+pub async fn delete_analysis(app: AppHandle, id: String) -> Result<(), String> {
+    update(&app, |analyses| {
+        let before = analyses.len();
+        analyses.retain(|a| a.id != id);
+        if analyses.len() == before { return Err(format!("unknown analysis: {id}")); }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn run_analysis(
+    app: AppHandle,
+    id: String,
+    manifest: Option<bool>,
+    pool: State<'_, DbPool>,
+    disabled: State<'_, DisabledStore>,
+) -> Result<AnalysisRun, String> {
+    let analysis = find(&app, &id)?;
+    let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let quality = quality_exclusions(&app, &conn, &analysis.spec)?;
+    let excluded = exclusions(&analysis.spec, &disabled.0.read().unwrap(), &quality);
+    let output = run(&app, &conn, &analysis.spec, &excluded)?;
+    let manifest = match manifest {
+        Some(true) => Some(manifest::build(&app, "run_analysis", analysis.spec.clone(), &excluded, output.seed(), &output.result_value()?)?),
+        _ => None,
+    };
+    Ok(AnalysisRun { analysis_id: analysis.id, name: analysis.name, ran_at_ms: now_ms(), output, manifest })
+}
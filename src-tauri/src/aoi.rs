@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::{dump_table, RowMap};
+
+/* ──────────────────────────────────────────────────────────────
+AOI codes ↔ Box names (mirrors AOI_CODE_TO_BOX in features/catalog/constants.ts)
+────────────────────────────────────────────────────────────── */
+
+pub const AOI_CODE_TO_BOX: [(&str, &str); 9] = [
+    ("S1", "Animal 1"),
+    ("O1A", "Object 1 for Animal 1"),
+    ("O2A", "Object 2 for Animal 1"),
+    ("S2", "Animal 2"),
+    ("O1B", "Object 1 for Animal 2"),
+    ("O2B", "Object 2 for Animal 2"),
+    ("S3", "Animal 3"),
+    ("O3A", "Object 1 for Animal 3"),
+    ("O3B", "Object 2 for Animal 3"),
+];
+
+pub const BASE_AOI_KEYS: [&str; 7] = [
+    "self_AOIs",
+    "correct_AOIs",
+    "potentially_correct_AOIs",
+    "incorrect_AOIs",
+    "correct_NULL",
+    "potentially_correct_NULL",
+    "incorrect_NULL",
+];
+
+/* "S1, O3A；S3" -> {"Animal 1", "Object 1 for Animal 3", "Animal 3"} (unknown codes dropped) */
+pub fn parse_aoi_list(s: &str) -> HashSet<String> {
+    s.replace('\u{3000}', " ")
+        .replace(['，', '；'], ",")
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|t| t.trim().to_uppercase())
+        .filter(|t| !t.is_empty())
+        .filter_map(|code| {
+            AOI_CODE_TO_BOX
+                .iter()
+                .find(|(c, _)| *c == code)
+                .map(|(_, b)| b.to_string())
+        })
+        .collect()
+}
+
+/* Tolerant column lookup (same spirit as `pick` in shared/services/testData.ts) */
+pub fn pick<'a>(row: &'a RowMap, key: &str) -> Option<&'a str> {
+    let candidates = [
+        key.to_string(),
+        key.replace('_', " "),
+        key.replace(' ', "_"),
+        key.to_lowercase(),
+        key.to_uppercase(),
+    ];
+    candidates
+        .iter()
+        .find_map(|k| row.get(k).and_then(|v| v.as_deref()))
+}
+
+pub fn boxes_for(row: &RowMap, keys: &[String]) -> HashSet<String> {
+    keys.iter()
+        .filter_map(|k| pick(row, k))
+        .flat_map(parse_aoi_list)
+        .collect()
+}
+
+/* Catalog keyed by test_name (first row wins when a test has several timelines) */
+pub fn load_catalog(conn: &rusqlite::Connection) -> Result<HashMap<String, RowMap>, String> {
+    let mut out: HashMap<String, RowMap> = HashMap::new();
+    for row in dump_table(conn, "test_catalog")? {
+        let Some(name) = pick(&row, "test_name").map(|s| s.trim().to_string()) else { continue; };
+        if name.is_empty() { continue; }
+        out.entry(name).or_insert(row);
+    }
+    Ok(out)
+}
+
+/* ──────────────────────────────────────────────────────────────
+AOI set selection (blue = target, red = competitors, invalid = dropped samples)
+Defaults match the Advanced Compare page.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AoiSets {
+    #[serde(default = "default_blue_keys")]
+    pub blue_keys: Vec<String>,
+    #[serde(default = "default_red_keys")]
+    pub red_keys: Vec<String>,
+    #[serde(default = "default_invalid")]
+    pub invalid: Vec<String>,
+}
+
+fn default_blue_keys() -> Vec<String> {
+    vec!["correct_AOIs".to_string()]
+}
+
+fn default_red_keys() -> Vec<String> {
+    BASE_AOI_KEYS
+        .iter()
+        .filter(|k| **k != "correct_AOIs")
+        .map(|k| k.to_string())
+        .collect()
+}
+
+fn default_invalid() -> Vec<String> {
+    vec!["missing".to_string()]
+}
+
+impl Default for AoiSets {
+    fn default() -> Self {
+        AoiSets { blue_keys: default_blue_keys(), red_keys: default_red_keys(), invalid: default_invalid() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AoiClass {
+    Blue,
+    Red,
+    Neither,
+    Invalid,
+}
+
+/* Resolved box sets for one test; red never overlaps blue */
+#[derive(Debug, Clone, Default)]
+pub struct TestAoi {
+    pub blue: HashSet<String>,
+    pub red: HashSet<String>,
+    pub invalid: HashSet<String>,
+}
+
+impl TestAoi {
+    pub fn resolve(row: Option<&RowMap>, sets: &AoiSets) -> Self {
+        let Some(row) = row else {
+            return TestAoi { invalid: sets.invalid.iter().cloned().collect(), ..Default::default() };
+        };
+        let blue = boxes_for(row, &sets.blue_keys);
+        let red_keys: Vec<String> = sets
+            .red_keys
+            .iter()
+            .filter(|k| !sets.blue_keys.contains(k))
+            .cloned()
+            .collect();
+        let red = boxes_for(row, &red_keys).into_iter().filter(|b| !blue.contains(b)).collect();
+        TestAoi { blue, red, invalid: sets.invalid.iter().cloned().collect() }
+    }
+
+    pub fn classify(&self, box_name: &str) -> AoiClass {
+        if self.invalid.contains(box_name) {
+            AoiClass::Invalid
+        } else if self.blue.contains(box_name) {
+            AoiClass::Blue
+        } else if self.red.contains(box_name) {
+            AoiClass::Red
+        } else {
+            AoiClass::Neither
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::{spawn, JoinHandle};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::resample::ResampleParams;
+use crate::smooth::SmoothParams;
+use crate::DisabledSlice;
+
+/* ──────────────────────────────────────────────────────────────
+Local HTTP/JSON API: an optional server on 127.0.0.1 so notebooks (Python,
+R) can run the same queries as the UI while the app is open. Handlers call
+the command functions directly, so they share the DbPool, DisabledStore
+and aggregate cache. Every /api route except /api/ping needs the token
+(`Authorization: Bearer <token>` or `X-Api-Token: <token>`).
+────────────────────────────────────────────────────────────── */
+
+const DEFAULT_PORT: u16 = 8765;
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiInfo {
+    pub url: String,
+    pub port: u16,
+    pub token: String,
+    pub endpoints: Vec<String>,
+}
+
+struct Running {
+    info: ApiInfo,
+    stop: oneshot::Sender<()>,
+    /* the accept loop; done once the listener is dropped */
+    task: JoinHandle<()>,
+}
+
+impl Running {
+    /* stop accepting and wait until the port is released */
+    async fn shut_down(self) {
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+#[derive(Default)]
+pub struct ApiState(Mutex<Option<Running>>);
+
+const ENDPOINTS: [&str; 9] = [
+    "GET  /api/ping",
+    "POST /api/gaze_data",
+    "POST /api/gaze_stream",
+    "POST /api/box_stats",
+    "GET  /api/search_tests",
+    "POST /api/search_slices",
+    "GET  /api/disabled_slices",
+    "PUT  /api/disabled_slices",
+    "POST /api/disabled_slices/toggle",
+];
+
+/* ─────────────────────────── Request bodies ─────────────────────────── */
+
+/* also the argument of get_gaze_stream */
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GazeQuery {
+    #[serde(alias = "testName")]
+    pub test_name: Option<String>,
+    pub participants: Vec<String>,
+    pub timeline: Option<String>,
+    pub recording: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub cohort: Option<String>,
+    pub resample: Option<ResampleParams>,
+    pub smoothing: Option<SmoothParams>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BoxStatsQuery {
+    #[serde(alias = "testName")]
+    test_name: Option<String>,
+    participants: Vec<String>,
+    timeline: Option<String>,
+    recording: Option<String>,
+    transforms: Option<bool>,
+    cohort: Option<String>,
+    resample: Option<ResampleParams>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SliceQuery {
+    #[serde(alias = "testName")]
+    test_name: Option<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetDisabled {
+    slices: Vec<DisabledSlice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToggleDisabled {
+    slice: DisabledSlice,
+    disabled: bool,
+}
+
+/* ─────────────────────────── HTTP ─────────────────────────── */
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct ApiError(u16, String);
+
+impl From<String> for ApiError {
+    fn from(e: String) -> Self { ApiError(400, e) }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        501 => "Not Implemented",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, ApiError> {
+    let mut buf: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") { break i; }
+        if buf.len() > MAX_HEAD_BYTES { return Err(ApiError(431, "request head too large".to_string())); }
+        let n = stream.read(&mut chunk).await.map_err(|e| ApiError(400, e.to_string()))?;
+        if n == 0 { return Err(ApiError(400, "connection closed mid-request".to_string())); }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut first = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (first.next(), first.next()) else {
+        return Err(ApiError(400, "malformed request line".to_string()));
+    };
+    let headers: HashMap<String, String> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+
+    // bodies are read by Content-Length only
+    match headers.get("transfer-encoding").map(|v| v.to_ascii_lowercase()) {
+        Some(te) if te.contains("chunked") => {
+            return Err(ApiError(411, "chunked request bodies are not supported; send Content-Length".to_string()));
+        }
+        Some(te) if te != "identity" => return Err(ApiError(501, format!("unsupported Transfer-Encoding: {te}"))),
+        _ => {}
+    }
+    let len: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if len > MAX_BODY_BYTES { return Err(ApiError(413, "request body too large".to_string())); }
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < len {
+        let n = stream.read(&mut chunk).await.map_err(|e| ApiError(400, e.to_string()))?;
+        if n == 0 { break; }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(len);
+
+    let path = target.split('?').next().unwrap_or_default().trim_end_matches('/').to_string();
+    Ok(Request { method: method.to_ascii_uppercase(), path, headers, body })
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &Value) {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reason(status),
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(&body).await;
+    let _ = stream.shutdown().await;
+}
+
+/* Token check without an early exit on the first differing byte */
+fn authorized(req: &Request, token: &str) -> bool {
+    let given = req
+        .headers
+        .get("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| req.headers.get("x-api-token").map(String::as_str))
+        .unwrap_or_default();
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/* JSON body as `T`; an empty body means all defaults */
+fn parse<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    let body = if body.iter().all(u8::is_ascii_whitespace) { b"{}".as_slice() } else { body };
+    serde_json::from_slice(body).map_err(|e| ApiError(400, format!("invalid JSON body: {e}")))
+}
+
+fn to_json<T: Serialize>(v: T) -> Result<Value, ApiError> {
+    serde_json::to_value(v).map_err(|e| ApiError(500, e.to_string()))
+}
+
+async fn route(app: &AppHandle, req: &Request) -> Result<Value, ApiError> {
+    match (req.method.as_str(), req.path.as_str()) {
+        ("POST" | "GET", "/api/gaze_data") => {
+            let q: GazeQuery = parse(&req.body)?;
+            let (pool, disabled) = (app.state(), app.state());
+            to_json(crate::get_gaze_data(q.test_name, None, q.participants, q.timeline, q.recording, q.limit, q.offset, q.cohort, q.resample, q.smoothing, pool, disabled).await?)
+        }
+        ("POST" | "GET", "/api/gaze_stream") => to_json(crate::get_gaze_stream(app.clone(), parse(&req.body)?).await?),
+        ("POST" | "GET", "/api/box_stats") => {
+            let q: BoxStatsQuery = parse(&req.body)?;
+            let (pool, disabled, aggregates) = (app.state(), app.state(), app.state());
+            to_json(crate::get_box_stats(q.test_name, None, q.participants, q.timeline, q.recording, q.transforms, q.cohort, q.resample, None, app.clone(), pool, disabled, aggregates).await?)
+        }
+        ("POST" | "GET", "/api/search_tests") => {
+            let q: SliceQuery = parse(&req.body)?;
+            to_json(crate::search_tests(q.cohort, app.state()).await?)
+        }
+        ("POST" | "GET", "/api/search_slices") => {
+            let q: SliceQuery = parse(&req.body)?;
+            to_json(crate::search_slices(q.test_name, None, q.participants, q.cohort, app.state()).await?)
+        }
+        ("GET", "/api/disabled_slices") => to_json(crate::get_disabled_slices(app.state()).await?),
+        ("PUT", "/api/disabled_slices") => {
+            let q: SetDisabled = parse(&req.body)?;
+            crate::set_disabled_slices(app.clone(), app.state(), q.slices).await?;
+            let _ = app.emit("disabled-slices-changed", ());
+            Ok(json!({ "ok": true }))
+        }
+        ("POST", "/api/disabled_slices/toggle") => {
+            let q: ToggleDisabled = parse(&req.body)?;
+            crate::toggle_disabled_slice(app.clone(), app.state(), q.slice, q.disabled).await?;
+            let _ = app.emit("disabled-slices-changed", ());
+            Ok(json!({ "ok": true }))
+        }
+        (_, path) if ENDPOINTS.iter().any(|e| e.split_whitespace().nth(1) == Some(path)) => Err(ApiError(405, format!("{} not allowed on {path}", req.method))),
+        (_, path) => Err(ApiError(404, format!("no such endpoint: {path}"))),
+    }
+}
+
+async fn handle(app: AppHandle, mut stream: TcpStream, token: String) {
+    let req = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(req)) => req,
+        Ok(Err(ApiError(status, msg))) => return respond(&mut stream, status, &json!({ "error": msg })).await,
+        Err(_) => return,
+    };
+    if req.path == "/api/ping" {
+        return respond(&mut stream, 200, &json!({ "ok": true, "app_version": env!("CARGO_PKG_VERSION") })).await;
+    }
+    if !authorized(&req, &token) {
+        return respond(&mut stream, 401, &json!({ "error": "missing or wrong API token" })).await;
+    }
+    match route(&app, &req).await {
+        Ok(v) => respond(&mut stream, 200, &v).await,
+        Err(ApiError(status, msg)) => respond(&mut stream, status, &json!({ "error": msg })).await,
+    }
+}
+
+async fn serve(app: AppHandle, listener: TcpListener, token: String, mut stop: oneshot::Receiver<()>) {
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue; };
+                spawn(handle(app.clone(), stream, token.clone()));
+            }
+        }
+    }
+}
+
+fn new_token() -> String {
+    format!("{:016x}{:016x}", rand::random::<u64>(), rand::random::<u64>())
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn get_api_server(state: State<'_, ApiState>) -> Result<Option<ApiInfo>, String> {
+    Ok(state.0.lock().unwrap().as_ref().map(|r| r.info.clone()))
+}
+
+/* Start (or restart) the server on 127.0.0.1:`port`; a token is generated when none is given */
+#[tauri::command]
+pub async fn start_api_server(
+    app: AppHandle,
+    port: Option<u16>,
+    token: Option<String>,
+    state: State<'_, ApiState>,
+) -> Result<ApiInfo, String> {
+    let prev = state.0.lock().unwrap().take();
+    if let Some(prev) = prev { prev.shut_down().await; }
+    let token = token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).unwrap_or_else(new_token);
+    let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(DEFAULT_PORT)))
+        .await
+        .map_err(|e| format!("cannot bind 127.0.0.1:{}: {e}", port.unwrap_or(DEFAULT_PORT)))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let info = ApiInfo {
+        url: format!("http://127.0.0.1:{port}/api"),
+        port,
+        token: token.clone(),
+        endpoints: ENDPOINTS.iter().map(|e| e.to_string()).collect(),
+    };
+    let (stop, stopped) = oneshot::channel();
+    let task = spawn(serve(app.clone(), listener, token, stopped));
+    *state.0.lock().unwrap() = Some(Running { info: info.clone(), stop, task });
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, ApiState>) -> Result<(), String> {
+    let running = state.0.lock().unwrap().take();
+    if let Some(running) = running { running.shut_down().await; }
+    Ok(())
+}
@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::aoi::{self, AoiClass, AoiSets, TestAoi};
+use crate::stats::{ProportionScale, ProportionTransforms};
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::ConditionDef;
+use crate::manifest::{self, WithManifest};
+use crate::resample::{self, ResampleParams};
+use crate::timestamp;
+use crate::{cohort, DbPool, DisabledSlice, DisabledStore, RowMap};
+
+/* ──────────────────────────────────────────────────────────────
+Server-side AOI binning (Rust port of buildBins in features/advanced/analysis.ts)
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinParams {
+    #[serde(default = "default_bin_ms")]
+    pub bin_ms: f64,
+    #[serde(default = "default_num_bins")]
+    pub num_bins: usize,
+    /* manual anchor: ms after the first sample of the recording */
+    #[serde(default)]
+    pub start_ms: f64,
+    /* anchor on the onset of this word from test_catalog.word_windows_json (falls back to start_ms) */
+    #[serde(default)]
+    pub anchor_word: Option<String>,
+    #[serde(default)]
+    pub shift_ms: f64,
+    /* map each recording onto a fixed time grid before binning */
+    #[serde(default)]
+    pub resample: Option<ResampleParams>,
+}
+
+fn default_bin_ms() -> f64 { 100.0 }
+fn default_num_bins() -> usize { 20 }
+
+/* upper bound on bins per recording (user input; every recording allocates them all) */
+const MAX_BINS: usize = 10_000;
+
+impl Default for BinParams {
+    fn default() -> Self {
+        BinParams {
+            bin_ms: default_bin_ms(),
+            num_bins: default_num_bins(),
+            start_ms: 0.0,
+            anchor_word: None,
+            shift_ms: 0.0,
+            resample: None,
+        }
+    }
+}
+
+impl BinParams {
+    pub fn check(&self) -> Result<(), String> {
+        if !(self.bin_ms.is_finite() && self.bin_ms > 0.0) {
+            return Err(format!("bin_ms must be positive (got {})", self.bin_ms));
+        }
+        if self.num_bins == 0 {
+            return Err("num_bins must be at least 1".to_string());
+        }
+        if self.num_bins > MAX_BINS {
+            return Err(format!("num_bins must be at most {MAX_BINS} (got {})", self.num_bins));
+        }
+        Ok(())
+    }
+
+    /* bin centres in seconds relative to the anchor */
+    pub fn x_sec(&self) -> Vec<f64> {
+        (0..self.num_bins).map(|i| (i as f64 + 0.5) * self.bin_ms / 1000.0).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BinCounts {
+    pub total: i64,
+    pub invalid: i64,
+    pub blue: i64,
+    pub red: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinSummary {
+    pub blue_pct: f64,
+    pub red_pct: f64,
+    pub valid_pct: f64,
+    pub blue_n: i64,
+    pub red_n: i64,
+    pub valid_n: i64,
+    /* blue out of blue+red on the elog / arcsine scales (only when requested) */
+    pub transforms: Option<ProportionTransforms>,
+}
+
+impl BinCounts {
+    pub fn add(&mut self, other: &BinCounts) {
+        self.total += other.total;
+        self.invalid += other.invalid;
+        self.blue += other.blue;
+        self.red += other.red;
+    }
+
+    /* (value, weight) of blue among blue+red on the requested scale; None when neither was looked at */
+    pub fn scaled(&self, scale: ProportionScale) -> Option<(f64, f64)> {
+        scale.apply(self.blue, self.blue + self.red)
+    }
+
+    pub fn summary(&self, transforms: bool) -> BinSummary {
+        let denom = (self.blue + self.red) as f64;
+        let valid = (self.total - self.invalid).max(0);
+        BinSummary {
+            blue_pct: if denom > 0.0 { self.blue as f64 / denom * 100.0 } else { 0.0 },
+            red_pct: if denom > 0.0 { self.red as f64 / denom * 100.0 } else { 0.0 },
+            valid_pct: if self.total > 0 { valid as f64 / self.total as f64 * 100.0 } else { 0.0 },
+            blue_n: self.blue,
+            red_n: self.red,
+            valid_n: valid,
+            transforms: if transforms { ProportionTransforms::new(self.blue, self.blue + self.red) } else { None },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GazeSample {
+    /* epoch µs (timestamp::parse) */
+    pub t_us: i64,
+    pub box_name: String,
+}
+
+impl GazeSample {
+    pub fn t_ms(&self) -> f64 {
+        timestamp::to_ms(self.t_us)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordingSamples {
+    /* attached cohort the rows came from (None without cohorts) */
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub samples: Vec<GazeSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingCurve {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub anchor_ms: f64,
+    pub bins: Vec<BinCounts>,
+}
+
+/* ─────────────────────────── Loading ─────────────────────────── */
+
+/* Word windows from test_catalog.word_windows_json: (word, start_sec, end_sec) */
+pub fn word_windows(row: &RowMap) -> Vec<(String, f64, f64)> {
+    let Some(raw) = aoi::pick(row, "word_windows_json") else { return vec![]; };
+    let Ok(serde_json::Value::Array(items)) = serde_json::from_str::<serde_json::Value>(raw) else { return vec![]; };
+    items
+        .iter()
+        .filter_map(|it| {
+            let word = it.get("chinese_word").or_else(|| it.get("w"))?.as_str()?.to_string();
+            let start = it.get("start_sec").or_else(|| it.get("start"))?.as_f64()?;
+            let end = it.get("end_sec").or_else(|| it.get("end"))?.as_f64()?;
+            Some((word, start, end))
+        })
+        .collect()
+}
+
+/* All (non-disabled) recordings for the given tests, samples in time order (rows with a
+   malformed "Exact time" are dropped) */
+pub fn load_recordings(
+    conn: &rusqlite::Connection,
+    disabled: &HashSet<DisabledSlice>,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+) -> Result<Vec<RecordingSamples>, String> {
+    if tests.is_empty() { return Ok(vec![]); }
+
+    // with cohorts attached, equally named slices of two cohorts stay separate recordings
+    let cohort_col = cohort::column_sql(conn);
+    let mut query = format!(
+        r#"
+        SELECT "Test Name", "Participant name", "Timeline name", "Recording name", "Exact time", Box, {cohort_col}
+        FROM   gaze_data
+        WHERE  "Test Name" IN ("#
+    );
+    query.push_str(&vec!["?"; tests.len()].join(","));
+    query.push(')');
+    if !participants.is_empty() {
+        query.push_str(" AND \"Participant name\" IN (");
+        query.push_str(&vec!["?"; participants.len()].join(","));
+        query.push(')');
+    }
+    query.push_str(cohort::filter_sql(conn, cohort)?);
+    query.push_str(&format!(r#" ORDER BY {cohort_col}, "Test Name", "Participant name", "Timeline name", "Recording name""#));
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
+    for t in tests { params.push(t); }
+    for p in participants { params.push(p); }
+    if let Some(c) = cohort { params.push(c); }
+
+    let mut rows = stmt.query(rusqlite::params_from_iter(params)).map_err(|e| e.to_string())?;
+    let mut out: Vec<RecordingSamples> = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let test_name: String = row.get(0).map_err(|e| e.to_string())?;
+        let participant: String = row.get(1).map_err(|e| e.to_string())?;
+        let timeline: Option<String> = row.get(2).map_err(|e| e.to_string())?;
+        let recording: String = row.get(3).map_err(|e| e.to_string())?;
+        let ts: Option<String> = row.get(4).map_err(|e| e.to_string())?;
+        let box_name: Option<String> = row.get(5).map_err(|e| e.to_string())?;
+        let from: Option<String> = row.get(6).map_err(|e| e.to_string())?;
+        let Some(t_us) = ts.as_deref().and_then(timestamp::parse) else { continue; };
+        let timeline = timeline.unwrap_or_default();
+
+        let same = out.last().is_some_and(|r| {
+            r.cohort == from && r.test_name == test_name && r.participant == participant && r.timeline == timeline && r.recording == recording
+        });
+        if !same {
+            let ds = DisabledSlice {
+                test_name: test_name.clone(),
+                recording_name: recording.clone(),
+                participant_name: participant.clone(),
+            };
+            if disabled.contains(&ds) { continue; }
+            out.push(RecordingSamples { cohort: from, test_name, participant, timeline, recording, samples: Vec::new() });
+        }
+        if let Some(r) = out.last_mut() {
+            r.samples.push(GazeSample { t_us, box_name: box_name.unwrap_or_else(|| "missing".to_string()) });
+        }
+    }
+    for r in out.iter_mut() {
+        r.samples.sort_by_key(|s| s.t_us);
+    }
+    Ok(out)
+}
+
+/* Absolute anchor (ms) for one recording */
+pub fn anchor_ms(rec: &RecordingSamples, catalog_row: Option<&RowMap>, params: &BinParams) -> f64 {
+    let base = rec.samples.first().map(GazeSample::t_ms).unwrap_or(0.0);
+    let word_start = params.anchor_word.as_ref().and_then(|w| {
+        catalog_row
+            .map(word_windows)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|(word, _, _)| word == w)
+            .map(|(_, start, _)| start * 1000.0)
+    });
+    base + word_start.unwrap_or(params.start_ms) + params.shift_ms
+}
+
+pub fn bin_samples(samples: &[GazeSample], anchor: f64, params: &BinParams, aoi: &TestAoi) -> Vec<BinCounts> {
+    let ms = params.bin_ms;
+    let mut bins = vec![BinCounts::default(); params.num_bins];
+    for s in samples {
+        let rel = s.t_ms() - anchor;
+        if rel < 0.0 { continue; }
+        let idx = (rel / ms).floor() as usize;
+        let Some(b) = bins.get_mut(idx) else { continue; };
+        b.total += 1;
+        match aoi.classify(&s.box_name) {
+            AoiClass::Invalid => b.invalid += 1,
+            AoiClass::Blue => b.blue += 1,
+            AoiClass::Red => b.red += 1,
+            AoiClass::Neither => {}
+        }
+    }
+    bins
+}
+
+/* Load + bin every recording of `tests` (catalog AOI sets resolved per test), optionally from one cohort */
+pub fn recording_curves(
+    conn: &rusqlite::Connection,
+    disabled: &HashSet<DisabledSlice>,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    sets: &AoiSets,
+    params: &BinParams,
+) -> Result<Vec<RecordingCurve>, String> {
+    params.check()?;
+    let catalog = aoi::load_catalog(conn)?;
+    let mut resolved: HashMap<&str, TestAoi> = HashMap::new();
+    let recs = load_recordings(conn, disabled, tests, participants, cohort)?;
+    Ok(recs
+        .iter()
+        .map(|rec| {
+            let row = catalog.get(&rec.test_name);
+            let aoi = resolved
+                .entry(rec.test_name.as_str())
+                .or_insert_with(|| TestAoi::resolve(row, sets));
+            let anchor = anchor_ms(rec, row, params);
+            RecordingCurve {
+                cohort: rec.cohort.clone(),
+                test_name: rec.test_name.clone(),
+                participant: rec.participant.clone(),
+                timeline: rec.timeline.clone(),
+                recording: rec.recording.clone(),
+                anchor_ms: anchor - rec.samples.first().map(GazeSample::t_ms).unwrap_or(anchor),
+                bins: bin_samples(&resample::samples(&rec.samples, params.resample.as_ref()), anchor, params, aoi),
+            }
+        })
+        .collect())
+}
+
+/* Pool a participant's recordings (restricted to `tests`) by summing bin counts */
+pub fn participant_curves(curves: &[RecordingCurve], tests: &HashSet<&str>, num_bins: usize) -> BTreeMap<String, Vec<BinCounts>> {
+    let mut out: BTreeMap<String, Vec<BinCounts>> = BTreeMap::new();
+    for c in curves.iter().filter(|c| tests.contains(c.test_name.as_str())) {
+        let acc = out
+            .entry(c.participant.clone())
+            .or_insert_with(|| vec![BinCounts::default(); num_bins]);
+        for (a, b) in acc.iter_mut().zip(&c.bins) { a.add(b); }
+    }
+    out
+}
+
+/* Single analysis window [start_ms, end_ms) relative to the anchor (trial-level measures) */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowParams {
+    #[serde(default)]
+    pub start_ms: f64,
+    #[serde(default = "default_window_end_ms")]
+    pub end_ms: f64,
+    #[serde(default)]
+    pub anchor_word: Option<String>,
+    #[serde(default)]
+    pub resample: Option<ResampleParams>,
+}
+
+fn default_window_end_ms() -> f64 { 2000.0 }
+
+impl Default for WindowParams {
+    fn default() -> Self {
+        WindowParams { start_ms: 0.0, end_ms: default_window_end_ms(), anchor_word: None, resample: None }
+    }
+}
+
+impl WindowParams {
+    /* the window as one bin */
+    pub fn as_bins(&self) -> Result<BinParams, String> {
+        let span = self.end_ms - self.start_ms;
+        if span.is_nan() || span <= 0.0 {
+            return Err("window end must be after start".to_string());
+        }
+        let bins = BinParams {
+            bin_ms: span,
+            num_bins: 1,
+            start_ms: 0.0,
+            anchor_word: self.anchor_word.clone(),
+            shift_ms: self.start_ms,
+            resample: self.resample.clone(),
+        };
+        bins.check()?;
+        Ok(bins)
+    }
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordingBins {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub anchor_ms: f64,
+    pub bins: Vec<BinSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AoiBinsResult {
+    pub x_sec: Vec<f64>,
+    pub recordings: Vec<RecordingBins>,
+}
+
+/* Per-recording blue/red bins for a set of tests (same numbers as buildBins on the client) */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_aoi_bins(
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    bins: Option<BinParams>,
+    transforms: Option<bool>,
+    manifest: Option<bool>,
+    app: AppHandle,
+) -> Result<WithManifest<AoiBinsResult>, String> {
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let sets = aoi.unwrap_or_default();
+    let params = bins.unwrap_or_default();
+    let with_transforms = transforms.unwrap_or(false);
+    let disabled_set = app.state::<DisabledStore>().0.read().unwrap().clone();
+
+    let curves = recording_curves(&conn, &disabled_set, &tests, &participants, &cohort, &sets, &params)?;
+    let result = aoi_bins(curves, &params, with_transforms);
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        aoi: sets,
+        bins: params.clone(),
+        transforms: with_transforms,
+        ..AnalysisSpec::new(AnalysisKind::Bins, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    manifest::attach(&app, manifest, "get_aoi_bins", spec, &disabled_set, None, result)
+}
+
+pub fn aoi_bins(curves: Vec<RecordingCurve>, params: &BinParams, with_transforms: bool) -> AoiBinsResult {
+    let recordings = curves
+        .into_iter()
+        .map(|c| RecordingBins {
+            cohort: c.cohort,
+            test_name: c.test_name,
+            participant: c.participant,
+            timeline: c.timeline,
+            recording: c.recording,
+            anchor_ms: c.anchor_ms,
+            bins: c.bins.iter().map(|b| b.summary(with_transforms)).collect(),
+        })
+        .collect();
+    AoiBinsResult { x_sec: params.x_sec(), recordings }
+}
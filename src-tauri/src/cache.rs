@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::path::BaseDirectory;
+use tauri::async_runtime::spawn_blocking;
+use tauri::{AppHandle, Manager, State};
+
+use crate::cohort::CohortState;
+use crate::project::{db_key, DbPath, Projects};
+use crate::timestamp;
+use crate::DisabledSlice;
+
+/* ──────────────────────────────────────────────────────────────
+Precomputed aggregates over gaze_data (one row per
+test × participant × timeline × recording: samples, Box counts, duration).
+The DB is opened immutable, so the layer lives on disk under AppData/cache,
+keyed by the DB file's SHA-256 and rebuilt whenever that changes.
+────────────────────────────────────────────────────────────── */
+
+/* bump when the aggregate layout changes */
+const CACHE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbFingerprint {
+    pub path: String,
+    pub size: u64,
+    pub modified_ms: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceAggregate {
+    pub test_name: String,
+    pub participant: Option<String>,
+    pub timeline: Option<String>,
+    pub recording: Option<String>,
+    pub samples: i64,
+    pub box_counts: BTreeMap<String, i64>,
+    /* last − first parsed "Exact time" (null when none parses) */
+    pub duration_ms: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Aggregates {
+    pub version: u32,
+    pub fingerprint: DbFingerprint,
+    pub built_at_ms: u64,
+    pub build_ms: u64,
+    pub slices: Vec<SliceAggregate>,
+}
+
+/* None until the background load/build finishes (callers fall back to SQL) */
+pub struct AggregateStore(pub Arc<RwLock<Option<Arc<Aggregates>>>>);
+
+fn nonempty(s: Option<&str>) -> Option<&str> {
+    s.filter(|s| !s.trim().is_empty())
+}
+
+impl Aggregates {
+    /* (test, participant, recording) for every slice with non-empty names (may repeat across timelines) */
+    pub fn triples(&self) -> impl Iterator<Item = (&str, &str, &str)> {
+        self.slices.iter().filter_map(|s| {
+            Some((nonempty(Some(&s.test_name))?, nonempty(s.participant.as_deref())?, nonempty(s.recording.as_deref())?))
+        })
+    }
+
+    /* Box counts with the same filters as the get_box_stats query */
+    pub fn box_counts(
+        &self,
+        test: &str,
+        participants: &[String],
+        timeline: Option<&str>,
+        recording: Option<&str>,
+        disabled: &HashSet<DisabledSlice>,
+    ) -> HashMap<String, i64> {
+        let mut out: HashMap<String, i64> = HashMap::new();
+        for s in self.slices.iter().filter(|s| s.test_name == test) {
+            let (part, rec) = (s.participant.as_deref(), s.recording.as_deref());
+            if !participants.is_empty() && !part.is_some_and(|p| participants.iter().any(|x| x == p)) { continue; }
+            if timeline.is_some() && s.timeline.as_deref() != timeline { continue; }
+            if recording.is_some() && rec != recording { continue; }
+            if let (Some(p), Some(r)) = (part, rec) {
+                let ds = DisabledSlice { test_name: test.to_string(), recording_name: r.to_string(), participant_name: p.to_string() };
+                if disabled.contains(&ds) { continue; }
+            }
+            for (b, c) in &s.box_counts { *out.entry(b.clone()).or_insert(0) += c; }
+        }
+        out
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 { break; }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/* Size + mtime, hashing the file only when they differ from `known` */
+pub fn fingerprint(path: &Path, known: Option<&DbFingerprint>) -> Result<DbFingerprint, String> {
+    let meta = fs::metadata(path).map_err(|e| e.to_string())?;
+    let modified_ms = meta
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let path_str = path.to_string_lossy().to_string();
+    if let Some(k) = known.filter(|k| k.path == path_str && k.size == meta.len() && k.modified_ms == modified_ms) {
+        return Ok(k.clone());
+    }
+    Ok(DbFingerprint { path: path_str, size: meta.len(), modified_ms, sha256: file_sha256(path)? })
+}
+
+/* One cache file per DB path */
+fn cache_file(app: &AppHandle, db_path: &Path) -> Result<PathBuf, String> {
+    app.path()
+        .resolve(format!("cache/aggregates-{}.json", db_key(db_path)), BaseDirectory::AppData)
+        .map_err(|e| e.to_string())
+}
+
+/* GROUP BY scan of gaze_data for counts, plus one pass parsing "Exact time" for durations
+   (MIN / MAX in SQL would compare the text) */
+pub fn build(conn: &rusqlite::Connection, fingerprint: DbFingerprint) -> Result<Aggregates, String> {
+    let started = Instant::now();
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT "Test Name", "Participant name", "Timeline name", "Recording name", Box, COUNT(*)
+               FROM gaze_data
+               WHERE "Test Name" IS NOT NULL
+               GROUP BY 1, 2, 3, 4, 5"#,
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                (row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, Option<String>>(3)?),
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    type Key = (String, Option<String>, Option<String>, Option<String>);
+    let mut acc: BTreeMap<Key, SliceAggregate> = BTreeMap::new();
+    for r in rows {
+        let (key, box_name, count) = r.map_err(|e| e.to_string())?;
+        let slice = acc.entry(key.clone()).or_insert_with(|| {
+            let (test_name, participant, timeline, recording) = key;
+            SliceAggregate { test_name, participant, timeline, recording, samples: 0, box_counts: BTreeMap::new(), duration_ms: None }
+        });
+        slice.samples += count;
+        if let Some(b) = box_name { *slice.box_counts.entry(b).or_insert(0) += count; }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT "Test Name", "Participant name", "Timeline name", "Recording name", CAST("Exact time" AS TEXT)
+               FROM gaze_data
+               WHERE "Test Name" IS NOT NULL"#,
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    let mut spans: HashMap<Key, (i64, i64)> = HashMap::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let Some(t) = row.get::<_, Option<String>>(4).map_err(|e| e.to_string())?.as_deref().and_then(timestamp::parse) else { continue; };
+        let key: Key = (
+            row.get(0).map_err(|e| e.to_string())?,
+            row.get(1).map_err(|e| e.to_string())?,
+            row.get(2).map_err(|e| e.to_string())?,
+            row.get(3).map_err(|e| e.to_string())?,
+        );
+        let (lo, hi) = spans.entry(key).or_insert((t, t));
+        (*lo, *hi) = ((*lo).min(t), (*hi).max(t));
+    }
+    for (key, (lo, hi)) in spans {
+        if let Some(s) = acc.get_mut(&key) { s.duration_ms = Some(timestamp::to_ms(hi - lo)); }
+    }
+    let slices = acc.into_values().collect();
+
+    Ok(Aggregates {
+        version: CACHE_VERSION,
+        fingerprint,
+        built_at_ms: now_ms(),
+        build_ms: started.elapsed().as_millis() as u64,
+        slices,
+    })
+}
+
+fn cohorts_attached(app: &AppHandle) -> bool {
+    app.state::<CohortState>().0.lock().unwrap().is_some()
+}
+
+/* Reuse the on-disk cache when version + DB hash match, otherwise rebuild and save.
+   Always reads the plain pool of `db_path`, never the cohort union */
+pub fn load_or_build(app: &AppHandle, db_path: &Path, force: bool) -> Result<Arc<Aggregates>, String> {
+    let file = cache_file(app, db_path)?;
+    let cached: Option<Aggregates> = fs::read(&file)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .filter(|a: &Aggregates| a.version == CACHE_VERSION);
+    let fp = fingerprint(db_path, cached.as_ref().map(|a| &a.fingerprint))?;
+
+    if let Some(mut agg) = cached.filter(|a| !force && a.fingerprint.sha256 == fp.sha256) {
+        if agg.fingerprint != fp {
+            // touched but unchanged: remember the new mtime so we skip hashing next time
+            agg.fingerprint = fp;
+            save(&file, &agg)?;
+        }
+        return Ok(Arc::new(agg));
+    }
+
+    let pool = app
+        .state::<Projects>()
+        .open
+        .lock()
+        .unwrap()
+        .get(db_path)
+        .cloned()
+        .ok_or_else(|| format!("database not open: {}", db_path.display()))?;
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let agg = build(&conn, fp)?;
+    save(&file, &agg)?;
+    Ok(Arc::new(agg))
+}
+
+/* Drop the current aggregates and load/build those of the active DB off the main thread
+   (nothing while cohorts are attached: queries then go to SQL) */
+pub fn refresh_in_background(app: AppHandle) {
+    let store = app.state::<AggregateStore>().0.clone();
+    *store.write().unwrap() = None;
+    if cohorts_attached(&app) { return; }
+    let path = app.state::<DbPath>().0.read().unwrap().clone();
+    spawn_blocking(move || {
+        let result = load_or_build(&app, &path, false);
+        // another DB may have been activated, or cohorts attached, while this one was building
+        if *app.state::<DbPath>().0.read().unwrap() != path || cohorts_attached(&app) { return; }
+        match result {
+            Ok(agg) => {
+                println!("Aggregate cache ready ({} slices).", agg.slices.len());
+                *store.write().unwrap() = Some(agg);
+            }
+            Err(e) => println!("Aggregate cache unavailable: {e}"),
+        }
+    });
+}
+
+fn save(file: &Path, agg: &Aggregates) -> Result<(), String> {
+    if let Some(parent) = file.parent() { let _ = fs::create_dir_all(parent); }
+    let json = serde_json::to_vec(agg).map_err(|e| e.to_string())?;
+    fs::write(file, json).map_err(|e| e.to_string())
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheStatus {
+    pub ready: bool,
+    pub fingerprint: Option<DbFingerprint>,
+    pub built_at_ms: Option<u64>,
+    pub build_ms: Option<u64>,
+    pub slices: usize,
+}
+
+fn status(agg: Option<&Aggregates>) -> CacheStatus {
+    CacheStatus {
+        ready: agg.is_some(),
+        fingerprint: agg.map(|a| a.fingerprint.clone()),
+        built_at_ms: agg.map(|a| a.built_at_ms),
+        build_ms: agg.map(|a| a.build_ms),
+        slices: agg.map(|a| a.slices.len()).unwrap_or(0),
+    }
+}
+
+#[tauri::command]
+pub async fn get_aggregate_cache_status(store: State<'_, AggregateStore>) -> Result<CacheStatus, String> {
+    let agg = store.0.read().unwrap().clone();
+    Ok(status(agg.as_deref()))
+}
+
+/* Re-check the DB hash (or force a rebuild) and swap in the result */
+#[tauri::command]
+pub async fn rebuild_aggregate_cache(
+    app: AppHandle,
+    force: Option<bool>,
+    db_path: State<'_, DbPath>,
+    store: State<'_, AggregateStore>,
+) -> Result<CacheStatus, String> {
+    if cohorts_attached(&app) {
+        return Err("aggregates describe the main database only; detach cohorts before rebuilding the cache".to_string());
+    }
+    let path = db_path.0.read().unwrap().clone();
+    let agg = load_or_build(&app, &path, force.unwrap_or(false))?;
+    if cohorts_attached(&app) { return Err("cohorts were attached during the rebuild".to_string()); }
+    *store.0.write().unwrap() = Some(agg.clone());
+    Ok(status(Some(&agg)))
+}
@@ -0,0 +1,301 @@
+use rusqlite::{OpenFlags, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use url::Url;
+
+use crate::project::{self, DbPath, Projects};
+use crate::{cache, table_exists, DbPool};
+
+/* ──────────────────────────────────────────────────────────────
+Cohort merging: additional study DBs are ATTACHed to every pooled
+connection and TEMP views named gaze_data / test_catalog / participants
+shadow the main tables with the UNION ALL of all cohorts plus a `cohort`
+column. Attachments belong to the active DB and are dropped on switch.
+────────────────────────────────────────────────────────────── */
+
+pub const UNIFIED_TABLES: [&str; 3] = ["gaze_data", "test_catalog", "participants"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortSpec {
+    pub path: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedCohort {
+    pub name: String,
+    /* schema alias used in ATTACH */
+    pub alias: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedTable {
+    pub table: String,
+    pub columns: Vec<String>,
+    /* cohort → columns it lacks (filled with NULL) */
+    pub missing_columns: BTreeMap<String, Vec<String>>,
+    /* cohorts without this table */
+    pub missing_in: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantCollision {
+    pub participant: String,
+    pub cohorts: Vec<String>,
+    /* tables the name was found in */
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CohortReport {
+    pub main: String,
+    pub cohorts: Vec<AttachedCohort>,
+    pub tables: Vec<UnifiedTable>,
+    pub collisions: Vec<ParticipantCollision>,
+}
+
+/* Current attachments (None = plain DB) */
+pub struct CohortState(pub Mutex<Option<CohortReport>>);
+
+fn quote_ident(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn quote_str(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn ro_uri(path: &Path) -> Result<String, String> {
+    let mut url = Url::from_file_path(path).map_err(|_| format!("bad DB path: {}", path.display()))?;
+    url.set_query(Some("mode=ro"));
+    Ok(url.to_string())
+}
+
+fn columns(conn: &rusqlite::Connection, schema: &str, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA {}.table_info({})", quote_ident(schema), quote_ident(table)))
+        .map_err(|e| e.to_string())?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .collect::<SqlResult<Vec<String>>>()
+        .map_err(|e| e.to_string());
+    cols
+}
+
+/* ATTACH + CREATE TEMP VIEW statements for `cohorts`, plus what each view unifies */
+fn setup_sql(conn: &rusqlite::Connection, main: &str, cohorts: &[AttachedCohort]) -> Result<(String, Vec<UnifiedTable>), String> {
+    let mut sql = String::new();
+    for c in cohorts {
+        let stmt = format!("ATTACH DATABASE {} AS {};\n", quote_str(&ro_uri(Path::new(&c.path))?), quote_ident(&c.alias));
+        conn.execute_batch(&stmt).map_err(|e| format!("attach {}: {e}", c.name))?;
+        sql.push_str(&stmt);
+    }
+
+    let schemas: Vec<(&str, &str)> = std::iter::once(("main", main))
+        .chain(cohorts.iter().map(|c| (c.alias.as_str(), c.name.as_str())))
+        .collect();
+    let mut tables = Vec::new();
+    for table in UNIFIED_TABLES {
+        let mut per: Vec<(&str, &str, Vec<String>)> = Vec::new();
+        let mut missing_in = Vec::new();
+        for (schema, label) in &schemas {
+            let cols = columns(conn, schema, table)?;
+            if cols.is_empty() { missing_in.push(label.to_string()); } else { per.push((schema, label, cols)); }
+        }
+        if per.is_empty() { continue; }
+        let mut all: Vec<String> = Vec::new();
+        for (_, _, cols) in &per {
+            for c in cols {
+                if !c.eq_ignore_ascii_case("cohort") && !all.contains(c) { all.push(c.clone()); }
+            }
+        }
+        let mut missing_columns = BTreeMap::new();
+        let selects: Vec<String> = per
+            .iter()
+            .map(|(schema, label, cols)| {
+                let lacking: Vec<String> = all.iter().filter(|c| !cols.contains(c)).cloned().collect();
+                let list: Vec<String> = all
+                    .iter()
+                    .map(|c| if cols.contains(c) { quote_ident(c) } else { format!("NULL AS {}", quote_ident(c)) })
+                    .collect();
+                if !lacking.is_empty() { missing_columns.insert(label.to_string(), lacking); }
+                // rowid kept so stored-order queries (ORDER BY rowid) work on the view
+                format!("SELECT {}, rowid AS rowid, {} AS cohort FROM {}.{}", list.join(", "), quote_str(label), quote_ident(schema), quote_ident(table))
+            })
+            .collect();
+        sql.push_str(&format!("CREATE TEMP VIEW {} AS {};\n", quote_ident(table), selects.join(" UNION ALL ")));
+        tables.push(UnifiedTable { table: table.to_string(), columns: all, missing_columns, missing_in });
+    }
+    Ok((sql, tables))
+}
+
+/* Participant names that occur in more than one cohort */
+fn collisions(conn: &rusqlite::Connection) -> Result<Vec<ParticipantCollision>, String> {
+    let mut seen: BTreeMap<String, (BTreeSet<String>, BTreeSet<String>)> = BTreeMap::new();
+    let sources = [("gaze_data", "Participant name"), ("participants", "participant")];
+    for (table, col) in sources {
+        if !table_exists(conn, table) || conn.prepare(&format!("SELECT {} FROM {table} LIMIT 0", quote_ident(col))).is_err() {
+            continue;
+        }
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT DISTINCT TRIM(CAST({c} AS TEXT)), cohort FROM {table} WHERE {c} IS NOT NULL AND TRIM(CAST({c} AS TEXT)) <> ''",
+                c = quote_ident(col)
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| e.to_string())?;
+        for r in rows {
+            let (p, cohort) = r.map_err(|e| e.to_string())?;
+            let entry = seen.entry(p).or_default();
+            entry.0.insert(cohort);
+            entry.1.insert(table.to_string());
+        }
+    }
+    Ok(seen
+        .into_iter()
+        .filter(|(_, (cohorts, _))| cohorts.len() > 1)
+        .map(|(participant, (cohorts, sources))| ParticipantCollision {
+            participant,
+            cohorts: cohorts.into_iter().collect(),
+            sources: sources.into_iter().collect(),
+        })
+        .collect())
+}
+
+/* gaze_data is the cohort union view (it has a cohort column) */
+pub fn is_attached(conn: &rusqlite::Connection) -> bool {
+    conn.prepare("SELECT cohort FROM gaze_data LIMIT 0").is_ok()
+}
+
+/* Select expression for a row's cohort: the column while cohorts are attached, else NULL */
+pub fn column_sql(conn: &rusqlite::Connection) -> &'static str {
+    if is_attached(conn) { "cohort" } else { "NULL" }
+}
+
+/* " AND cohort = ?" for an optional cohort filter (error when nothing is attached) */
+pub fn filter_sql(conn: &rusqlite::Connection, cohort: &Option<String>) -> Result<&'static str, String> {
+    match cohort {
+        None => Ok(""),
+        Some(_) if is_attached(conn) => Ok(" AND cohort = ?"),
+        Some(c) => Err(format!("cohort filter '{c}' given but no cohorts are attached")),
+    }
+}
+
+/* Stable paging order for gaze rows: per recording (and cohort while cohorts are attached, as
+   rowids restart in every DB), then "Exact time", rowid breaking ties */
+pub fn order_sql(conn: &rusqlite::Connection) -> &'static str {
+    if is_attached(conn) {
+        r#"cohort, "Participant name", "Timeline name", "Recording name", "Exact time", rowid"#
+    } else {
+        r#""Participant name", "Timeline name", "Recording name", "Exact time", rowid"#
+    }
+}
+
+/* Participant names in one cohort's gaze_data, for filtering tables the views do not unify */
+pub fn participants(conn: &rusqlite::Connection, cohort: &str) -> Result<BTreeSet<String>, String> {
+    let mut stmt = conn
+        .prepare(r#"SELECT DISTINCT TRIM("Participant name") FROM gaze_data WHERE "Participant name" IS NOT NULL AND cohort = ?1"#)
+        .map_err(|e| e.to_string())?;
+    let names = stmt.query_map([cohort], |row| row.get::<_, String>(0)).map_err(|e| e.to_string())?;
+    names.collect::<SqlResult<BTreeSet<String>>>().map_err(|e| e.to_string())
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn get_cohorts(state: State<'_, CohortState>) -> Result<Option<CohortReport>, String> {
+    Ok(state.0.lock().unwrap().clone())
+}
+
+/* Attach `cohorts` to the active DB (replacing any previous attachments) */
+#[tauri::command]
+pub async fn attach_cohorts(
+    app: AppHandle,
+    cohorts: Vec<CohortSpec>,
+    main_name: Option<String>,
+    pool: State<'_, DbPool>,
+    db_path: State<'_, DbPath>,
+    state: State<'_, CohortState>,
+) -> Result<CohortReport, String> {
+    if cohorts.is_empty() {
+        return Err("no cohorts given".to_string());
+    }
+    let main_path = db_path.0.read().unwrap().clone();
+    let main = main_name
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| main_path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "main".to_string());
+
+    let mut names: BTreeSet<String> = BTreeSet::from([main.clone()]);
+    let mut attached = Vec::new();
+    for (i, spec) in cohorts.iter().enumerate() {
+        let name = spec.name.trim().to_string();
+        if name.is_empty() || !names.insert(name.clone()) {
+            return Err(format!("cohort names must be unique and non-empty: '{}'", spec.name));
+        }
+        let path = PathBuf::from(spec.path.trim());
+        let path = std::fs::canonicalize(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        if path == main_path {
+            return Err(format!("cohort '{name}' is the active database"));
+        }
+        attached.push(AttachedCohort { name, alias: format!("cohort_{}", i + 1), path: path.to_string_lossy().to_string() });
+    }
+
+    // Probe on a throwaway connection first so a bad file never reaches the pool
+    let probe = rusqlite::Connection::open_with_flags(
+        ro_uri(&main_path)?,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+    )
+    .map_err(|e| e.to_string())?;
+    let (sql, tables) = setup_sql(&probe, &main, &attached)?;
+    for c in &attached {
+        if columns(&probe, &c.alias, "gaze_data")?.is_empty() {
+            return Err(format!("cohort '{}' has no gaze_data table", c.name));
+        }
+    }
+    probe.execute_batch(&sql[sql.find("CREATE TEMP VIEW").unwrap_or(sql.len())..]).map_err(|e| e.to_string())?;
+    let collisions = collisions(&probe)?;
+
+    let immutable = project::is_bundled(&app, &main_path);
+    let merged = project::open_pool_with(&main_path, immutable, Some(sql))?;
+    pool.0.swap(Arc::new(merged));
+    // aggregates describe the main DB only: serve from SQL while cohorts are attached
+    *app.state::<cache::AggregateStore>().0.write().unwrap() = None;
+
+    let report = CohortReport { main, cohorts: attached, tables, collisions };
+    *state.0.lock().unwrap() = Some(report.clone());
+    let _ = app.emit("database-changed", main_path.to_string_lossy().to_string());
+    Ok(report)
+}
+
+/* Drop all attachments and go back to the plain active DB */
+#[tauri::command]
+pub async fn detach_cohorts(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    db_path: State<'_, DbPath>,
+    projects: State<'_, Projects>,
+    state: State<'_, CohortState>,
+) -> Result<(), String> {
+    if state.0.lock().unwrap().take().is_none() { return Ok(()); }
+    let path = db_path.0.read().unwrap().clone();
+    let plain = projects
+        .open
+        .lock()
+        .unwrap()
+        .get(&path)
+        .cloned()
+        .ok_or_else(|| format!("database not open: {}", path.display()))?;
+    pool.0.swap(plain);
+    cache::refresh_in_background(app.clone());
+    let _ = app.emit("database-changed", path.to_string_lossy().to_string());
+    Ok(())
+}
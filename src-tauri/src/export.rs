@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::ConditionDef;
+use crate::cache::AggregateStore;
+use crate::manifest::{self, WithManifest};
+use crate::xlsx::{Cell, Workbook};
+use crate::{box_counts_sql, project, query_slices, DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Excel export: a Summary sheet (AOI box shares per test × participant,
+as get_box_stats computes them), a By test pivot, one sheet per test
+with the per-recording rows of search_slices, and the disabled slices.
+Sheet names are truncated to Excel's 31 characters; the cells always
+carry the full test / participant names.
+────────────────────────────────────────────────────────────── */
+
+/* What was written, independent of where (a manifest re-run rebuilds this without a file) */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkbookContents {
+    pub sheets: Vec<String>,
+    pub tests: usize,
+    pub participants: usize,
+    /* SHA-256 of the .xlsx bytes (the writer is deterministic) */
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkbookExport {
+    pub path: String,
+    #[serde(flatten)]
+    pub contents: WorkbookContents,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/* Box share columns: box names ordered by overall sample count */
+fn box_columns<'a>(counts: impl Iterator<Item = &'a HashMap<String, i64>>) -> Vec<String> {
+    let mut totals: BTreeMap<&str, i64> = BTreeMap::new();
+    for c in counts {
+        for (b, n) in c { *totals.entry(b.as_str()).or_default() += n; }
+    }
+    let mut boxes: Vec<(&str, i64)> = totals.into_iter().collect();
+    boxes.sort_by_key(|(b, n)| (std::cmp::Reverse(*n), *b));
+    boxes.into_iter().map(|(b, _)| b.to_string()).collect()
+}
+
+fn share_cells(counts: &HashMap<String, i64>, boxes: &[String]) -> Vec<Cell> {
+    let total: i64 = counts.values().sum();
+    let mut row = vec![Cell::Int(total)];
+    row.extend(boxes.iter().map(|b| {
+        let n = counts.get(b).copied().unwrap_or(0);
+        if total > 0 { Cell::Pct(n as f64 / total as f64 * 100.0) } else { Cell::Empty }
+    }));
+    row
+}
+
+/* The .xlsx bytes with `disabled` slices left out of the shares (and listed). Empty `tests` = every test */
+pub fn workbook(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    disabled: &HashSet<DisabledSlice>,
+) -> Result<(Vec<u8>, WorkbookContents), String> {
+    let cached = app.state::<AggregateStore>().0.read().unwrap().clone().filter(|_| cohort.is_none());
+
+    let mut slices = query_slices(conn, &None, participants, cohort)?;
+    if !tests.is_empty() { slices.retain(|s| tests.contains(&s.test_name)); }
+    // participants per test, in name order
+    let mut by_test: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for s in &slices {
+        by_test.entry(s.test_name.clone()).or_default().insert(s.participant_name.clone());
+    }
+
+    let counts = |test: &String, who: &[String]| -> Result<HashMap<String, i64>, String> {
+        match &cached {
+            Some(agg) => Ok(agg.box_counts(test, who, None, None, disabled)),
+            None => box_counts_sql(conn, test, who, &None, &None, cohort, disabled),
+        }
+    };
+    let mut cells: Vec<(String, String, HashMap<String, i64>)> = Vec::new();
+    let mut pivot: Vec<(String, usize, HashMap<String, i64>)> = Vec::new();
+    for (test, people) in &by_test {
+        let mut all: HashMap<String, i64> = HashMap::new();
+        for p in people {
+            let c = counts(test, std::slice::from_ref(p))?;
+            for (b, n) in &c { *all.entry(b.clone()).or_default() += n; }
+            cells.push((test.clone(), p.clone(), c));
+        }
+        pivot.push((test.clone(), people.len(), all));
+    }
+    let boxes = box_columns(pivot.iter().map(|(_, _, c)| c));
+    let pct_heads: Vec<String> = boxes.iter().map(|b| format!("{b} %")).collect();
+    let heads = |lead: &[&'static str]| -> Vec<String> {
+        lead.iter().map(|s| s.to_string()).chain(std::iter::once("Samples".to_string())).chain(pct_heads.iter().cloned()).collect()
+    };
+
+    let mut book = Workbook::default();
+    let head = heads(&["Test", "Participant"]);
+    let summary = book.add_sheet("Summary", &head.iter().map(String::as_str).collect::<Vec<_>>());
+    for (test, p, c) in &cells {
+        let mut row = vec![Cell::from(test.as_str()), Cell::from(p.as_str())];
+        row.extend(share_cells(c, &boxes));
+        summary.push(row);
+    }
+
+    let head = heads(&["Test", "Participants"]);
+    let per_test = book.add_sheet("By test", &head.iter().map(String::as_str).collect::<Vec<_>>());
+    for (test, n, c) in &pivot {
+        let mut row = vec![Cell::from(test.as_str()), Cell::Int(*n as i64)];
+        row.extend(share_cells(c, &boxes));
+        per_test.push(row);
+    }
+
+    for test in by_test.keys() {
+        let sheet = book.add_sheet(
+            test,
+            &["Test", "Participant", "Recording", "Group", "Image", "Sentence", "Pair duration (s)", "MP4 duration (s)", "PNG duration (s)", "Disabled"],
+        );
+        for s in slices.iter().filter(|s| &s.test_name == test) {
+            let slice = DisabledSlice {
+                test_name: s.test_name.clone(),
+                recording_name: s.recording_name.clone(),
+                participant_name: s.participant_name.clone(),
+            };
+            sheet.push(vec![
+                Cell::from(s.test_name.as_str()),
+                Cell::from(s.participant_name.as_str()),
+                Cell::from(s.recording_name.as_str()),
+                Cell::from(s.group.clone()),
+                Cell::from(s.image_name.clone()),
+                Cell::from(s.sentence.clone()),
+                Cell::from(s.pair_duration_seconds),
+                Cell::from(s.mp4_duration_seconds),
+                Cell::from(s.png_duration_seconds),
+                Cell::from(if disabled.contains(&slice) { "yes" } else { "" }),
+            ]);
+        }
+    }
+
+    let mut off: Vec<&DisabledSlice> = disabled.iter().collect();
+    off.sort_by(|a, b| (&a.test_name, &a.participant_name, &a.recording_name).cmp(&(&b.test_name, &b.participant_name, &b.recording_name)));
+    let sheet = book.add_sheet("Disabled slices", &["Test", "Participant", "Recording"]);
+    for s in off {
+        sheet.push(vec![
+            Cell::from(s.test_name.as_str()),
+            Cell::from(s.participant_name.as_str()),
+            Cell::from(s.recording_name.as_str()),
+        ]);
+    }
+
+    let bytes = book.to_bytes();
+    let contents = WorkbookContents {
+        sheets: book.sheet_names(),
+        tests: by_test.len(),
+        participants: cells.iter().map(|(_, p, _)| p.as_str()).collect::<BTreeSet<_>>().len(),
+        sha256: format!("{:x}", Sha256::digest(&bytes)),
+    };
+    Ok((bytes, contents))
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Write the workbook to `path` (default: exports/ next to disabled_slices.json). Empty `tests` = every test */
+#[tauri::command]
+pub async fn export_workbook(
+    app: AppHandle,
+    path: Option<String>,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<WorkbookExport>, String> {
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let (bytes, contents) = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        workbook(&app, &conn, &tests, &participants, &cohort, &disabled)?
+    };
+
+    let path = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => project::data_file(&app, &format!("exports/eye-tracking-{}.xlsx", now_ms()))?,
+    };
+    if let Some(parent) = path.parent() { let _ = fs::create_dir_all(parent); }
+    fs::write(&path, bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    // the manifest covers the contents, not the file name
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        ..AnalysisSpec::new(AnalysisKind::Workbook, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    let out = manifest::attach(&app, manifest, "export_workbook", spec, &disabled, None, contents)?;
+    Ok(WithManifest { result: WorkbookExport { path: path.to_string_lossy().to_string(), contents: out.result }, manifest: out.manifest })
+}
@@ -0,0 +1,253 @@
+use rusqlite::{OpenFlags, Result as SqlResult};
+use rusqlite::types::Value as SqlValue;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::cache;
+use crate::project::{is_bundled, DbPath};
+use crate::{table_exists, DbPool};
+
+/* ──────────────────────────────────────────────────────────────
+Index diagnostics: existing indexes, EXPLAIN QUERY PLAN for the built-in
+gaze_data queries, and the composite indexes they want. Missing indexes
+are only created on a writable project DB (never the bundled resource).
+────────────────────────────────────────────────────────────── */
+
+/* (name, table, columns) */
+pub const RECOMMENDED: [(&str, &str, &[&str]); 3] = [
+    ("idx_gaze_test_participant_recording", "gaze_data", &["Test Name", "Participant name", "Recording name"]),
+    ("idx_gaze_participant_test_recording", "gaze_data", &["Participant name", "Test Name", "Recording name"]),
+    ("idx_test_group_test_recording_participant", "test_group", &["test_name", "Recording name", "Participant name"]),
+];
+
+/* Representative shapes of the queries built in lib.rs / binning.rs (one participant, no disabled slices) */
+const BUILTIN_QUERIES: [(&str, &str); 9] = [
+    (
+        "get_gaze_data",
+        r#"SELECT "Gaze point X", "Gaze point Y", Box, "Presented Media name", "Timeline name",
+                  "Participant name", "Recording name", "Exact time", "Test Name"
+           FROM gaze_data
+           WHERE "Test Name" = ?1 AND "Participant name" IN (?) AND "Timeline name" = ? AND "Recording name" = ?"#,
+    ),
+    (
+        "get_timeline_recordings",
+        r#"SELECT DISTINCT "Timeline name", "Recording name" FROM gaze_data
+           WHERE "Test Name" = ?1 AND "Participant name" IN (?)
+           ORDER BY "Timeline name", "Recording name""#,
+    ),
+    (
+        "get_all_participant_sessions",
+        r#"SELECT DISTINCT "Participant name", "Test Name", "Timeline name", "Recording name" FROM gaze_data
+           WHERE "Test Name" IN (?) AND "Participant name" IN (?)
+           ORDER BY "Participant name", "Test Name", "Timeline name", "Recording name""#,
+    ),
+    (
+        "get_box_stats",
+        r#"SELECT Box, COUNT(*) AS count FROM gaze_data
+           WHERE "Test Name" = ?1 AND "Participant name" IN (?) GROUP BY Box"#,
+    ),
+    (
+        "get_participants_for_test",
+        r#"SELECT DISTINCT "Participant name", "Recording name" FROM gaze_data WHERE "Test Name"=?1"#,
+    ),
+    (
+        "get_tests_for_participant",
+        r#"SELECT DISTINCT "Test Name", "Recording name" FROM gaze_data WHERE "Participant name"=?1"#,
+    ),
+    (
+        "list_gaze_slices",
+        r#"SELECT DISTINCT "Test Name", "Recording name", "Participant name" FROM gaze_data
+           WHERE 1=1 AND "Test Name" = ? AND "Participant name" IN (?) ORDER BY 1,2,3"#,
+    ),
+    (
+        "list_gaze_slices (participants only)",
+        r#"SELECT DISTINCT "Test Name", "Recording name", "Participant name" FROM gaze_data
+           WHERE 1=1 AND "Participant name" IN (?) ORDER BY 1,2,3"#,
+    ),
+    (
+        "server-side binning (load_recordings)",
+        r#"SELECT "Test Name", "Participant name", "Timeline name", "Recording name", "Exact time", Box
+           FROM gaze_data
+           WHERE "Test Name" IN (?) AND "Participant name" IN (?)
+           ORDER BY "Test Name", "Participant name", "Timeline name", "Recording name""#,
+    ),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexInfo {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub sql: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryPlan {
+    pub query: String,
+    pub sql: String,
+    pub plan: Vec<String>,
+    /* tables read without any index */
+    pub full_scans: Vec<String>,
+    pub indexes_used: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecommendedIndex {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    /* existing index whose leading columns match */
+    pub covered_by: Option<String>,
+    pub created: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexDiagnostics {
+    pub db_path: String,
+    pub writable: bool,
+    pub note: Option<String>,
+    pub indexes: Vec<IndexInfo>,
+    pub queries: Vec<QueryPlan>,
+    pub recommended: Vec<RecommendedIndex>,
+}
+
+pub fn list_indexes(conn: &rusqlite::Connection) -> Result<Vec<IndexInfo>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name, tbl_name, sql FROM sqlite_master WHERE type = 'index' ORDER BY tbl_name, name")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<SqlResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for (name, table, sql) in rows {
+        let escaped = name.replace('"', "\"\"");
+        let mut info = conn
+            .prepare(&format!("PRAGMA index_info(\"{escaped}\")"))
+            .map_err(|e| e.to_string())?;
+        let columns = info
+            .query_map([], |row| row.get::<_, Option<String>>(2))
+            .map_err(|e| e.to_string())?
+            .collect::<SqlResult<Vec<_>>>()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|c| c.unwrap_or_else(|| "<expr>".to_string()))
+            .collect();
+        let unique = sql.as_deref().is_some_and(|s| s.to_uppercase().starts_with("CREATE UNIQUE"))
+            || name.starts_with("sqlite_autoindex_");
+        out.push(IndexInfo { name, table, columns, unique, sql });
+    }
+    Ok(out)
+}
+
+/* EXPLAIN QUERY PLAN with every placeholder bound to NULL */
+pub fn explain(conn: &rusqlite::Connection, query: &str, sql: &str) -> QueryPlan {
+    let run = || -> Result<Vec<String>, String> {
+        let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}")).map_err(|e| e.to_string())?;
+        let nulls = vec![SqlValue::Null; stmt.parameter_count()];
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(nulls), |row| row.get::<_, String>(3))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<SqlResult<Vec<String>>>().map_err(|e| e.to_string())
+    };
+    let (plan, error) = match run() {
+        Ok(p) => (p, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    let mut full_scans = Vec::new();
+    let mut indexes_used = Vec::new();
+    for detail in &plan {
+        if let Some(rest) = detail.strip_prefix("SCAN ") {
+            if !detail.contains(" USING ") {
+                let table = rest.trim_start_matches("TABLE ").split_whitespace().next().unwrap_or("");
+                full_scans.push(table.to_string());
+            }
+        }
+        if let Some(pos) = detail.find(" INDEX ") {
+            // "(col=?)" follows automatic indexes, which have no name
+            if let Some(name) = detail[pos + 7..].split_whitespace().next().filter(|n| !n.starts_with('(')) {
+                indexes_used.push(name.to_string());
+            }
+        }
+    }
+    QueryPlan { query: query.to_string(), sql: sql.to_string(), plan, full_scans, indexes_used, error }
+}
+
+fn covered_by(existing: &[IndexInfo], table: &str, columns: &[&str]) -> Option<String> {
+    existing
+        .iter()
+        .filter(|i| i.table.eq_ignore_ascii_case(table) && i.columns.len() >= columns.len())
+        .find(|i| i.columns.iter().zip(columns).all(|(a, b)| a.eq_ignore_ascii_case(b)))
+        .map(|i| i.name.clone())
+}
+
+fn diagnostics(conn: &rusqlite::Connection, created: &[String], db_path: &Path, writable: bool, note: Option<String>) -> Result<IndexDiagnostics, String> {
+    let indexes = list_indexes(conn)?;
+    let queries = BUILTIN_QUERIES.iter().map(|(q, sql)| explain(conn, q, sql)).collect();
+    let recommended = RECOMMENDED
+        .iter()
+        .filter(|(_, table, _)| table_exists(conn, table))
+        .map(|(name, table, cols)| RecommendedIndex {
+            name: name.to_string(),
+            table: table.to_string(),
+            columns: cols.iter().map(|c| c.to_string()).collect(),
+            covered_by: covered_by(&indexes, table, cols),
+            created: created.iter().any(|c| c == name),
+        })
+        .collect();
+    Ok(IndexDiagnostics {
+        db_path: db_path.to_string_lossy().to_string(),
+        writable,
+        note,
+        indexes,
+        queries,
+        recommended,
+    })
+}
+
+/* Diagnose the open DB; with `create`, add the missing recommended indexes (writable project DBs only) */
+#[tauri::command]
+pub async fn diagnose_indexes(
+    app: AppHandle,
+    create: Option<bool>,
+    pool: State<'_, DbPool>,
+    db_path: State<'_, DbPath>,
+) -> Result<IndexDiagnostics, String> {
+    let path = db_path.0.read().unwrap().clone();
+    let bundled = is_bundled(&app, &path);
+    let read_only_file = fs::metadata(&path).map(|m| m.permissions().readonly()).unwrap_or(true);
+    let writable = !bundled && !read_only_file;
+    let mut note = bundled.then(|| "bundled database is read-only; indexes can only be created on a project DB".to_string());
+    if !bundled && read_only_file {
+        note = Some("database file is read-only".to_string());
+    }
+
+    if create.unwrap_or(false) && writable {
+        let conn = rusqlite::Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_WRITE).map_err(|e| e.to_string())?;
+        let existing = list_indexes(&conn)?;
+        let mut created = Vec::new();
+        for (name, table, cols) in RECOMMENDED {
+            if !table_exists(&conn, table) || covered_by(&existing, table, cols).is_some() { continue; }
+            let cols_sql = cols.iter().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", ");
+            conn.execute_batch(&format!("CREATE INDEX IF NOT EXISTS \"{name}\" ON {table} ({cols_sql});"))
+                .map_err(|e| e.to_string())?;
+            created.push(name.to_string());
+        }
+        if !created.is_empty() {
+            conn.execute_batch("ANALYZE;").map_err(|e| e.to_string())?;
+            note = Some(format!("created {} index(es)", created.len()));
+            // the file changed, so its aggregate cache is stale
+            cache::refresh_in_background(app.clone());
+        }
+        diagnostics(&conn, &created, &path, writable, note)
+    } else {
+        let conn = pool.0.get().map_err(|e| e.to_string())?;
+        diagnostics(&conn, &[], &path, writable, note)
+    }
+}
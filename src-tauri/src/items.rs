@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::{all_tests, ConditionDef};
+use crate::aoi::{load_catalog, pick, AoiClass, AoiSets, TestAoi};
+use crate::binning::load_recordings;
+use crate::cache::AggregateStore;
+use crate::manifest::{self, WithManifest};
+use crate::resample::{self, ResampleParams};
+use crate::{cohort, stats, DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Item-level (per-test) AOI proportions. get_box_stats pools samples, so a
+long recording weighs more; here every participant gets one proportion
+per test (their Box count / their samples) and the item value is the mean
+over participants, with SD / SE across them. The target share is
+blue / (blue + red) per participant under the given AOI sets.
+────────────────────────────────────────────────────────────── */
+
+/* catalog columns attached to each row (the Compare-by factors) */
+const FACTORS: [&str; 6] = ["group", "truth_value", "only_position", "morpheme", "series", "case_no"];
+
+/* (test, participant) → Box → samples */
+pub type ParticipantCounts = BTreeMap<(String, String), HashMap<String, i64>>;
+
+/* Box counts per (test, participant), disabled slices left out; from the aggregate cache when possible,
+   grid points instead of raw samples with `resample` */
+pub fn participant_box_counts(
+    app: &AppHandle,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    disabled: &HashSet<DisabledSlice>,
+    resample: Option<&ResampleParams>,
+) -> Result<ParticipantCounts, String> {
+    let mut out = ParticipantCounts::new();
+    if let Some(params) = resample {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        let all_tests;
+        let tests = if tests.is_empty() {
+            let mut stmt = conn.prepare(r#"SELECT DISTINCT "Test Name" FROM gaze_data WHERE "Test Name" IS NOT NULL"#).map_err(|e| e.to_string())?;
+            all_tests = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(|e| e.to_string())?
+                .collect::<rusqlite::Result<Vec<String>>>()
+                .map_err(|e| e.to_string())?;
+            &all_tests
+        } else {
+            tests
+        };
+        for rec in load_recordings(&conn, disabled, tests, participants, cohort)? {
+            if rec.participant.trim().is_empty() { continue; }
+            let counts = out.entry((rec.test_name.clone(), rec.participant.clone())).or_default();
+            for s in resample::samples(&rec.samples, Some(params)).iter() { *counts.entry(s.box_name.clone()).or_default() += 1; }
+        }
+        return Ok(out);
+    }
+
+    let keep = |test: &str, participant: &str, recording: &str| {
+        (tests.is_empty() || tests.iter().any(|t| t == test))
+            && (participants.is_empty() || participants.iter().any(|p| p == participant))
+            && !disabled.contains(&DisabledSlice {
+                test_name: test.to_string(),
+                recording_name: recording.to_string(),
+                participant_name: participant.to_string(),
+            })
+    };
+
+    let cached = app.state::<AggregateStore>().0.read().unwrap().clone().filter(|_| cohort.is_none());
+    if let Some(agg) = cached {
+        for s in &agg.slices {
+            let (Some(p), Some(r)) = (s.participant.as_deref(), s.recording.as_deref()) else { continue; };
+            if p.trim().is_empty() || !keep(&s.test_name, p, r) { continue; }
+            let counts = out.entry((s.test_name.clone(), p.to_string())).or_default();
+            for (b, n) in &s.box_counts { *counts.entry(b.clone()).or_default() += n; }
+        }
+        return Ok(out);
+    }
+
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, cohort)?;
+    let mut query = String::from(
+        r#"SELECT "Test Name", "Participant name", "Recording name", Box, COUNT(*)
+           FROM gaze_data
+           WHERE "Participant name" IS NOT NULL AND TRIM("Participant name") <> ''"#,
+    );
+    let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
+    if !tests.is_empty() {
+        query.push_str(&format!(" AND \"Test Name\" IN ({})", vec!["?"; tests.len()].join(",")));
+        params.extend(tests.iter().map(|t| t as &dyn rusqlite::ToSql));
+    }
+    if !participants.is_empty() {
+        query.push_str(&format!(" AND \"Participant name\" IN ({})", vec!["?"; participants.len()].join(",")));
+        params.extend(participants.iter().map(|p| p as &dyn rusqlite::ToSql));
+    }
+    query.push_str(cohort_sql);
+    if let Some(ref c) = cohort { params.push(c); }
+    query.push_str(r#" GROUP BY "Test Name", "Participant name", "Recording name", Box"#);
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(params)).map_err(|e| e.to_string())?;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let test: String = row.get(0).map_err(|e| e.to_string())?;
+        let participant: String = row.get(1).map_err(|e| e.to_string())?;
+        let recording: Option<String> = row.get(2).map_err(|e| e.to_string())?;
+        if !keep(&test, &participant, recording.as_deref().unwrap_or("")) { continue; }
+        let b: Option<String> = row.get(3).map_err(|e| e.to_string())?;
+        let n: i64 = row.get(4).map_err(|e| e.to_string())?;
+        *out.entry((test, participant)).or_default().entry(b.unwrap_or_default()).or_default() += n;
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProportionSummary {
+    pub mean: f64,
+    /* across participants; None with fewer than two */
+    pub sd: Option<f64>,
+    pub se: Option<f64>,
+    /* participants contributing a proportion */
+    pub n: usize,
+}
+
+impl ProportionSummary {
+    pub fn of(v: &[f64]) -> Option<Self> {
+        if v.is_empty() { return None; }
+        let sd = (v.len() > 1).then(|| stats::sd(v));
+        Some(ProportionSummary { mean: stats::mean(v), sd, se: sd.map(|s| s / (v.len() as f64).sqrt()), n: v.len() })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemStats {
+    pub test_name: String,
+    pub factors: BTreeMap<String, Option<String>>,
+    pub n_participants: usize,
+    pub n_samples: i64,
+    /* per Box: mean over participants of (Box samples / all samples) */
+    pub boxes: BTreeMap<String, ProportionSummary>,
+    /* blue / (blue + red), participants without blue or red samples left out */
+    pub target: Option<ProportionSummary>,
+    /* (target mean − mean of the other items in its condition) / their SD */
+    pub condition_z: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemOptions {
+    /* catalog factor items are compared within (default group) */
+    #[serde(default)]
+    pub condition_by: Option<String>,
+    /* count grid points instead of raw samples */
+    #[serde(default)]
+    pub resample: Option<ResampleParams>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemStatsOutput {
+    pub condition_by: String,
+    pub items: Vec<ItemStats>,
+}
+
+/* Item rows for the spec's tests (empty = all) with `excluded` slices left out */
+pub fn item_stats(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    spec: &AnalysisSpec,
+    excluded: &HashSet<DisabledSlice>,
+) -> Result<ItemStatsOutput, String> {
+    let options = &spec.items;
+    let counts = participant_box_counts(app, &all_tests(&spec.conditions), &spec.participants, &spec.cohort, excluded, options.resample.as_ref())?;
+    let catalog = load_catalog(conn)?;
+    let sets = &spec.aoi;
+    let condition_by = options.condition_by.clone().filter(|c| !c.trim().is_empty()).unwrap_or_else(|| "group".to_string());
+
+    let mut by_test: BTreeMap<&str, Vec<&HashMap<String, i64>>> = BTreeMap::new();
+    for ((test, _), c) in &counts { by_test.entry(test.as_str()).or_default().push(c); }
+
+    let mut items: Vec<ItemStats> = Vec::new();
+    for (test, people) in by_test {
+        let row = catalog.get(test);
+        let resolved = TestAoi::resolve(row, sets);
+        let box_names: HashSet<&String> = people.iter().flat_map(|c| c.keys()).collect();
+        let mut shares: BTreeMap<String, Vec<f64>> = box_names.iter().map(|b| (b.to_string(), Vec::new())).collect();
+        let mut target: Vec<f64> = Vec::new();
+        let mut n_samples = 0;
+        for c in &people {
+            let total: i64 = c.values().sum();
+            n_samples += total;
+            if total == 0 { continue; }
+            for (b, v) in shares.iter_mut() {
+                v.push(c.get(b).copied().unwrap_or(0) as f64 / total as f64);
+            }
+            let (mut blue, mut red) = (0, 0);
+            for (b, n) in c.iter() {
+                match resolved.classify(b) {
+                    AoiClass::Blue => blue += n,
+                    AoiClass::Red => red += n,
+                    _ => {}
+                }
+            }
+            if blue + red > 0 { target.push(blue as f64 / (blue + red) as f64); }
+        }
+        items.push(ItemStats {
+            test_name: test.to_string(),
+            factors: FACTORS
+                .iter()
+                .map(|f| (f.to_string(), row.and_then(|r| pick(r, f)).map(|v| v.trim().to_string())))
+                .collect(),
+            n_participants: people.len(),
+            n_samples,
+            boxes: shares.into_iter().filter_map(|(b, v)| ProportionSummary::of(&v).map(|s| (b, s))).collect(),
+            target: ProportionSummary::of(&target),
+            condition_z: None,
+        });
+    }
+
+    // leave-one-out z of the target mean within each condition
+    let condition_of = |test: &str| catalog.get(test).and_then(|r| pick(r, &condition_by)).map(|v| v.trim().to_string());
+    let means: Vec<(Option<String>, Option<f64>)> =
+        items.iter().map(|i| (condition_of(&i.test_name), i.target.as_ref().map(|t| t.mean))).collect();
+    for (k, item) in items.iter_mut().enumerate() {
+        let (Some(cond), Some(m)) = &means[k] else { continue; };
+        let others: Vec<f64> = means
+            .iter()
+            .enumerate()
+            .filter(|(j, (c, _))| *j != k && c.as_ref() == Some(cond))
+            .filter_map(|(_, (_, v))| *v)
+            .collect();
+        let sd = if others.len() > 1 { stats::sd(&others) } else { 0.0 };
+        if sd > 0.0 { item.condition_z = Some((m - stats::mean(&others)) / sd); }
+    }
+
+    Ok(ItemStatsOutput { condition_by, items })
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* One row per test (empty `tests` = all), items compared within `options.condition_by` */
+#[tauri::command]
+pub async fn get_item_stats(
+    app: AppHandle,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    options: Option<ItemOptions>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<ItemStatsOutput>, String> {
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let spec = AnalysisSpec {
+        participants,
+        cohort,
+        aoi: aoi.unwrap_or_default(),
+        items: options.unwrap_or_default(),
+        ..AnalysisSpec::new(AnalysisKind::Items, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    let result = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        item_stats(&app, &conn, &spec, &disabled)?
+    };
+    manifest::attach(&app, manifest, "get_item_stats", || spec, &disabled, None, result)
+}
@@ -1,19 +1,41 @@
 use base64::{engine::general_purpose, Engine as _};
-use r2d2::Pool;
-use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{OpenFlags, OptionalExtension, Result as SqlResult};
+use rusqlite::{OptionalExtension, Result as SqlResult};
 use rusqlite::types::Value as SqlValue;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock, Mutex};
-use std::time::Duration;
 use tauri::path::BaseDirectory;
 use tauri::{AppHandle, Manager, State};
-use tauri::async_runtime::spawn;
+use tauri::async_runtime::{spawn, spawn_blocking};
 // use tokio::time::{sleep, Duration as TokioDuration};
-use url::Url;
+
+mod analysis;
+mod api;
+mod aoi;
+mod binning;
+mod cache;
+mod cohort;
+mod export;
+mod indexes;
+mod items;
+mod manifest;
+mod measures;
+mod participant;
+mod project;
+mod quality;
+mod report;
+mod resample;
+mod smooth;
+mod sentence;
+mod stats;
+mod synth;
+mod timestamp;
+mod transitions;
+mod validate;
+mod vocab;
+mod xlsx;
 
 /* ──────────────────────────────────────────────────────────────
 Data types
@@ -28,7 +50,11 @@ pub struct GazeData {
     timeline: String,
     participant: String,
     recording: String,
+    /* "Exact time" as stored */
     timestamp: String,
+    /* parsed epoch µs (None when malformed) and ms since the recording's first sample */
+    time_us: Option<i64>,
+    rel_ms: Option<f64>,
     test_name: String,
 }
 
@@ -36,6 +62,9 @@ pub struct GazeData {
 pub struct GazeStats {
     box_percentages: HashMap<String, f64>,
     total_points: i64,
+    box_counts: HashMap<String, i64>,
+    /* per-box elog / arcsine of count out of total_points (only when requested) */
+    transforms: Option<HashMap<String, stats::ProportionTransforms>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -66,7 +95,7 @@ pub struct StaticData {
     pub tests_by_participant: HashMap<String, Vec<String>>,
 }
 
-pub struct DbPool(Arc<Pool<SqliteConnectionManager>>);
+pub struct DbPool(project::SwappablePool);
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct DisabledSlice {
@@ -81,6 +110,8 @@ pub struct DisabledStore(pub Arc<RwLock<HashSet<DisabledSlice>>>);
 struct SetupState {
     frontend_task: bool,
     backend_task: bool,
+    /* user chose to continue despite fatal DB problems */
+    acknowledged: bool,
 }
 
 #[tauri::command]
@@ -93,19 +124,27 @@ async fn set_complete(
     match task.as_str() {
         "frontend" => state_lock.frontend_task = true,
         "backend" => state_lock.backend_task = true,
+        "acknowledge" => state_lock.acknowledged = true,
         _ => return Ok(()),
     }
-    if state_lock.frontend_task && state_lock.backend_task {
+    // fatal problems from the startup health check keep the splash (which lists them) open
+    let fatal = app
+        .state::<validate::HealthStore>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|r| !r.ok);
+    if state_lock.frontend_task && state_lock.backend_task && (!fatal || state_lock.acknowledged) {
         if let Some(splash) = app.get_webview_window("splashscreen") { let _ = splash.close(); }
         if let Some(main) = app.get_webview_window("main") { let _ = main.show(); }
     }
     Ok(())
 }
 
+/* Disabled slices are stored per database (see project::data_file) */
 fn disabled_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .resolve("disabled_slices.json", BaseDirectory::AppData)
-        .map_err(|e| e.to_string())
+    project::data_file(app, "disabled_slices.json")
 }
 
 fn load_disabled_from_disk(app: &AppHandle) -> HashSet<DisabledSlice> {
@@ -160,7 +199,11 @@ Helpers
 
 fn table_exists(conn: &rusqlite::Connection, name: &str) -> bool {
     let mut stmt =
-        match conn.prepare("SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1 LIMIT 1") {
+        // cohort views (see cohort.rs) shadow the main tables as TEMP views
+        match conn.prepare(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1
+             UNION ALL SELECT 1 FROM sqlite_temp_master WHERE type='view' AND name=?1 LIMIT 1",
+        ) {
             Ok(s) => s,
             Err(_) => return false,
         };
@@ -242,47 +285,70 @@ Commands
 
 /* 1) Bootstrap: fetch small tables. (Skip huge test_group) */
 #[tauri::command]
-async fn get_static_data(pool: State<'_, DbPool>, disabled: State<'_, DisabledStore>) -> Result<StaticData, String> {
+async fn get_static_data(
+    cohort: Option<String>,
+    pool: State<'_, DbPool>,
+    disabled: State<'_, DisabledStore>,
+    aggregates: State<'_, cache::AggregateStore>,
+) -> Result<StaticData, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
 
     // Full dumps — do NOT fetch test_group
-    let test_catalog = dump_table(&conn, "test_catalog")?;
-    let recordings   = dump_table(&conn, "recordings")?;
+    let mut test_catalog = dump_table(&conn, "test_catalog")?;
+    if let Some(ref c) = cohort { test_catalog.retain(|row| row.get("cohort").and_then(|v| v.as_deref()) == Some(c.as_str())); }
+    let mut recordings = dump_table(&conn, "recordings")?;
+    // recordings is not unified across cohorts: keep the rows of the cohort's participants
+    if let Some(ref c) = cohort {
+        let names = cohort::participants(&conn, c)?;
+        recordings.retain(|row| row.get("Participant").and_then(|v| v.as_deref()).is_some_and(|p| names.contains(p.trim())));
+    }
     let test_group: Vec<RowMap> = Vec::new();
 
     // Participants from recordings
-    let participants = distinct_nonempty(&conn, "recordings", "Participant")?;
+    let mut participants = distinct_nonempty(&conn, "recordings", "Participant")?;
 
     // Test names from test_catalog
-    let test_names = distinct_nonempty(&conn, "test_catalog", "test_name")?;
+    let mut test_names = distinct_nonempty(&conn, "test_catalog", "test_name")?;
 
-    // Build maps (test -> participants, participant -> tests) from gaze_data, honoring disabled triples
+    // Build maps (test -> participants, participant -> tests) from gaze_data, honoring disabled triples.
+    // Served from the aggregate cache once it is ready (and no cohort filter applies); otherwise scan gaze_data.
     let mut by_test: HashMap<String, BTreeSet<String>> = HashMap::new();
     let mut by_part: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let cached = aggregates.0.read().unwrap().clone().filter(|_| cohort.is_none());
+    let triples: Vec<(String, String, String)> = match cached {
+        Some(agg) => agg.triples().map(|(t, p, r)| (t.to_string(), p.to_string(), r.to_string())).collect(),
+        None => {
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT DISTINCT "Test Name", "Participant name", "Recording name"
+                   FROM gaze_data
+                   WHERE "Test Name" IS NOT NULL AND TRIM("Test Name") <> ''
+                     AND "Participant name" IS NOT NULL AND TRIM("Participant name") <> ''
+                     AND "Recording name" IS NOT NULL AND TRIM("Recording name") <> ''{cohort_sql}"#,
+            )).map_err(|e| e.to_string())?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(cohort.iter()), |row| {
+                let t: String = row.get(0)?;
+                let p: String = row.get(1)?;
+                let r: String = row.get(2)?;
+                Ok((t, p, r))
+            }).map_err(|e| e.to_string())?;
+            rows.collect::<SqlResult<Vec<_>>>().map_err(|e| e.to_string())?
+        }
+    };
     {
-        let mut stmt = conn.prepare(
-            r#"SELECT DISTINCT "Test Name", "Participant name", "Recording name"
-               FROM gaze_data
-               WHERE "Test Name" IS NOT NULL AND TRIM("Test Name") <> ''
-                 AND "Participant name" IS NOT NULL AND TRIM("Participant name") <> ''
-                 AND "Recording name" IS NOT NULL AND TRIM("Recording name") <> ''"#,
-        ).map_err(|e| e.to_string())?;
-        let rows = stmt.query_map([], |row| {
-            let t: String = row.get(0)?;
-            let p: String = row.get(1)?;
-            let r: String = row.get(2)?;
-            Ok((t, p, r))
-        }).map_err(|e| e.to_string())?;
-
         let disabled_set = disabled.0.read().unwrap();
-        for r in rows {
-            let (t, p, rname) = r.map_err(|e| e.to_string())?;
+        for (t, p, rname) in triples {
             let ds = DisabledSlice { test_name: t.clone(), recording_name: rname.clone(), participant_name: p.clone() };
             if disabled_set.contains(&ds) { continue; }
             by_test.entry(t.clone()).or_default().insert(p.clone());
             by_part.entry(p).or_default().insert(t);
         }
     }
+    if cohort.is_some() {
+        participants.retain(|p| by_part.contains_key(p));
+        let catalog_names: HashSet<&str> = test_catalog.iter().filter_map(|r| r.get("test_name")?.as_deref()).collect();
+        test_names.retain(|t| catalog_names.contains(t.as_str()));
+    }
     let participants_by_test: HashMap<String, Vec<String>> = by_test
         .into_iter()
         .map(|(k, v)| (k, v.into_iter().collect()))
@@ -295,8 +361,9 @@ async fn get_static_data(pool: State<'_, DbPool>, disabled: State<'_, DisabledSt
     Ok(StaticData { test_catalog, test_group, recordings, participants, test_names, participants_by_test, tests_by_participant })
 }
 
-/* 2) Heavy data: filtered gaze stream (with optional limit/offset)
-     Accept BOTH `test_name` and `testName` from JS. */
+/* 2) Heavy data: filtered gaze stream, paged in SQL by limit/offset over a stable per-recording
+     time order; the returned rows (grid points when resampled) are sorted by parsed time, malformed
+     times last. Accept BOTH `test_name` and `testName` from JS. */
 #[tauri::command]
 async fn get_gaze_data(
     test_name: Option<String>,
@@ -306,14 +373,24 @@ async fn get_gaze_data(
     recording: Option<String>,
     limit: Option<i64>,
     offset: Option<i64>,
+    cohort: Option<String>,
+    resample: Option<resample::ResampleParams>,
+    smoothing: Option<smooth::SmoothParams>,
     pool: State<'_, DbPool>,
     disabled: State<'_, DisabledStore>,
 ) -> Result<Vec<GazeData>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
 
     let lim_guard: i64 = limit.unwrap_or(0);
-    let off_guard: i64 = offset.unwrap_or(0);
+    let off_guard: i64 = offset.unwrap_or(0).max(0);
+    let paged = lim_guard > 0;
+    // resampling / smoothing see PAGE_MARGIN rows on each side of the page so its edges match the full stream
+    let margin = if paged && (resample.is_some() || smoothing.is_some()) { PAGE_MARGIN } else { 0 };
+    let window_start = (off_guard - margin).max(0);
+    let window_len = lim_guard + (off_guard - window_start) + margin;
+    let lead = (off_guard - window_start) as usize;
 
     let mut query = String::from(
         r#"
@@ -332,6 +409,7 @@ async fn get_gaze_data(
     }
     if timeline.is_some() { query.push_str(" AND \"Timeline name\" = ?"); }
     if recording.is_some() { query.push_str(" AND \"Recording name\" = ?"); }
+    query.push_str(cohort_sql);
 
     // Exclude disabled slices
     let disabled_set = disabled.0.read().unwrap();
@@ -353,13 +431,8 @@ async fn get_gaze_data(
         }
         query.push(')');
     }
-
-    query.push_str(" ORDER BY \"Exact time\"");
-
-    if lim_guard > 0 {
-        query.push_str(" LIMIT ?");
-        if off_guard > 0 { query.push_str(" OFFSET ?"); }
-    }
+    query.push_str(&format!(" ORDER BY {}", cohort::order_sql(&conn)));
+    if paged { query.push_str(" LIMIT ? OFFSET ?"); }
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
 
@@ -367,20 +440,22 @@ async fn get_gaze_data(
     for p in &participants { params.push(p); }
     if let Some(ref tl) = timeline { params.push(tl); }
     if let Some(ref rc) = recording { params.push(rc); }
+    if let Some(ref c) = cohort { params.push(c); }
     // disabled params
     for ds in &disabled_filters {
         params.push(&ds.test_name);
         params.push(&ds.recording_name);
         params.push(&ds.participant_name);
     }
-    // no validity filters (temporarily disabled)
-    if lim_guard > 0 {
-        params.push(&lim_guard);
-        if off_guard > 0 { params.push(&off_guard); }
+    if paged {
+        params.push(&window_len);
+        params.push(&window_start);
     }
+    // no validity filters (temporarily disabled)
 
     let rows = stmt
         .query_map(rusqlite::params_from_iter(params), |row| {
+            let timestamp: String = row.get(7)?;
             Ok(GazeData {
                 gaze_x: row.get::<_, Option<f64>>(0)?,
                 gaze_y: row.get::<_, Option<f64>>(1)?,
@@ -389,13 +464,219 @@ async fn get_gaze_data(
                 timeline: row.get(4)?,
                 participant: row.get(5)?,
                 recording: row.get(6)?,
-                timestamp: row.get(7)?,
+                time_us: timestamp::parse(&timestamp),
+                rel_ms: None,
+                timestamp,
                 test_name: row.get(8)?,
             })
         })
         .map_err(|e| e.to_string())?;
 
-    Ok(rows.collect::<rusqlite::Result<Vec<GazeData>>>().map_err(|e| e.to_string())?)
+    let mut rows = rows.collect::<rusqlite::Result<Vec<GazeData>>>().map_err(|e| e.to_string())?;
+    drop(stmt);
+    let starts = if paged { recording_starts_sql(&conn, &test, &rows, &cohort)? } else { recording_starts(&rows) };
+    let lead = lead.min(rows.len());
+    let page_end = (lead + lim_guard.max(0) as usize).min(rows.len());
+    let spans = (paged && resample.is_some()).then(|| page_spans(&rows, lead, page_end));
+    if let Some(ref params) = resample { rows = resample_gaze(rows, params, &starts); }
+    if let Some(ref params) = smoothing { smooth_gaze(&mut rows, params); }
+    match spans {
+        Some(spans) => rows.retain(|r| {
+            let span = r.time_us.and_then(|t| Some((t, spans.get(&group_key(r))?)));
+            span.is_some_and(|(t, (lo, hi))| *lo <= t && t < *hi)
+        }),
+        None if paged => {
+            rows.truncate(page_end);
+            rows.drain(..lead.min(rows.len()));
+        }
+        None => {}
+    }
+    set_relative_times(&mut rows, &starts);
+    // stable: ties keep the SQL order
+    rows.sort_by_key(|r| (r.time_us.is_none(), r.time_us));
+    Ok(rows)
+}
+
+/* rows on each side of a page that resampling / smoothing see but do not return */
+const PAGE_MARGIN: i64 = 64;
+
+type GroupKey = (String, String, String);
+
+fn group_key(r: &GazeData) -> GroupKey {
+    (r.participant.clone(), r.timeline.clone(), r.recording.clone())
+}
+
+/* First parsed time of each (participant, timeline, recording) among `rows` */
+fn recording_starts(rows: &[GazeData]) -> HashMap<GroupKey, i64> {
+    let mut starts: HashMap<GroupKey, i64> = HashMap::new();
+    for r in rows {
+        let Some(t) = r.time_us else { continue; };
+        let start = starts.entry(group_key(r)).or_insert(t);
+        *start = (*start).min(t);
+    }
+    starts
+}
+
+/* Same for the recordings a page touches, over all their rows (only "Exact time" is read) */
+fn recording_starts_sql(
+    conn: &rusqlite::Connection,
+    test: &str,
+    rows: &[GazeData],
+    cohort: &Option<String>,
+) -> Result<HashMap<GroupKey, i64>, String> {
+    let cohort_sql = cohort::filter_sql(conn, cohort)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"SELECT CAST("Exact time" AS TEXT) FROM gaze_data
+               WHERE "Test Name" = ? AND "Participant name" = ? AND "Timeline name" = ? AND "Recording name" = ?{cohort_sql}"#
+        ))
+        .map_err(|e| e.to_string())?;
+    let keys: HashSet<GroupKey> = rows.iter().map(group_key).collect();
+    let mut starts = HashMap::new();
+    for key in keys {
+        let (p, tl, rec) = &key;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&test, p, tl, rec];
+        if let Some(ref c) = cohort { params.push(c); }
+        let times = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| row.get::<_, Option<String>>(0))
+            .map_err(|e| e.to_string())?;
+        let mut start: Option<i64> = None;
+        for t in times {
+            let Some(t) = t.map_err(|e| e.to_string())?.as_deref().and_then(timestamp::parse) else { continue; };
+            start = Some(start.map_or(t, |s| s.min(t)));
+        }
+        if let Some(s) = start { starts.insert(key, s); }
+    }
+    Ok(starts)
+}
+
+/* Time span [from, to) each recording's page rows cover: up to the recording's next row after
+   the page (so consecutive pages tile the grid), else just past its last page row */
+fn page_spans(rows: &[GazeData], lead: usize, page_end: usize) -> HashMap<GroupKey, (i64, i64)> {
+    let mut spans: HashMap<GroupKey, (i64, i64)> = HashMap::new();
+    for r in &rows[lead..page_end] {
+        let Some(t) = r.time_us else { continue; };
+        let span = spans.entry(group_key(r)).or_insert((t, t + 1));
+        *span = (span.0.min(t), span.1.max(t + 1));
+    }
+    let mut next: HashMap<GroupKey, i64> = HashMap::new();
+    for r in &rows[page_end..] {
+        let Some(t) = r.time_us else { continue; };
+        let key = group_key(r);
+        let Some(&(_, to)) = spans.get(&key) else { continue; };
+        if t >= to {
+            let n = next.entry(key).or_insert(t);
+            *n = (*n).min(t);
+        }
+    }
+    for (key, to) in next {
+        if let Some(span) = spans.get_mut(&key) { span.1 = to; }
+    }
+    spans
+}
+
+/* rel_ms from the first sample of each (participant, timeline, recording) */
+fn set_relative_times(rows: &mut [GazeData], starts: &HashMap<GroupKey, i64>) {
+    for r in rows.iter_mut() {
+        r.rel_ms = r.time_us.zip(starts.get(&group_key(r))).map(|(t, s)| timestamp::to_ms(t - s));
+    }
+}
+
+/* Gaze stream on a fixed grid per (participant, timeline, recording), anchored at the recording's
+   start: Box / media from the chosen sample, coordinates interpolated; rows with a malformed
+   "Exact time" are dropped */
+fn resample_gaze(rows: Vec<GazeData>, params: &resample::ResampleParams, starts: &HashMap<GroupKey, i64>) -> Vec<GazeData> {
+    let mut groups: BTreeMap<GroupKey, Vec<(i64, GazeData)>> = BTreeMap::new();
+    for r in rows {
+        let Some(t) = r.time_us else { continue; };
+        groups.entry(group_key(&r)).or_default().push((t, r));
+    }
+    let mut out: Vec<GazeData> = Vec::new();
+    for (key, mut g) in groups {
+        g.sort_by_key(|(t, _)| *t);
+        let times: Vec<i64> = g.iter().map(|(t, _)| *t).collect();
+        let xs: Vec<Option<f64>> = g.iter().map(|(_, r)| r.gaze_x).collect();
+        let ys: Vec<Option<f64>> = g.iter().map(|(_, r)| r.gaze_y).collect();
+        let anchor = starts.get(&key).copied().unwrap_or(times[0]);
+        for p in resample::grid_from(&times, anchor, params) {
+            let near = &g[p.source.unwrap_or(p.prev)].1;
+            out.push(GazeData {
+                gaze_x: resample::interpolate(&xs, &p),
+                gaze_y: resample::interpolate(&ys, &p),
+                box_name: p.source.map(|i| g[i].1.box_name.clone()).unwrap_or_else(|| "missing".to_string()),
+                media_name: near.media_name.clone(),
+                timeline: near.timeline.clone(),
+                participant: near.participant.clone(),
+                recording: near.recording.clone(),
+                timestamp: timestamp::format(p.t_us),
+                time_us: Some(p.t_us),
+                rel_ms: None,
+                test_name: near.test_name.clone(),
+            });
+        }
+    }
+    out.sort_by_key(|r| r.time_us);
+    out
+}
+
+/* Smooth gaze_x / gaze_y per (participant, timeline, recording) in place; order and Box are kept,
+   rows with a malformed "Exact time" are left as they are */
+fn smooth_gaze(rows: &mut [GazeData], params: &smooth::SmoothParams) {
+    let mut groups = BTreeMap::<_, Vec<(i64, usize)>>::new();
+    for (i, r) in rows.iter().enumerate() {
+        let Some(t) = r.time_us else { continue; };
+        groups.entry((&r.participant, &r.timeline, &r.recording)).or_default().push((t, i));
+    }
+    let groups: Vec<Vec<(i64, usize)>> = groups.into_values().collect();
+    for mut g in groups {
+        g.sort_by_key(|(t, _)| *t);
+        let times: Vec<i64> = g.iter().map(|(t, _)| *t).collect();
+        let mut xs: Vec<Option<f64>> = g.iter().map(|(_, i)| rows[*i].gaze_x).collect();
+        let mut ys: Vec<Option<f64>> = g.iter().map(|(_, i)| rows[*i].gaze_y).collect();
+        smooth::series(&times, &mut xs, params);
+        smooth::series(&times, &mut ys, params);
+        for ((_, i), (x, y)) in g.iter().zip(xs.into_iter().zip(ys)) {
+            rows[*i].gaze_x = x;
+            rows[*i].gaze_y = y;
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GazeMeta {
+    /* parameters as applied (smoothing windows normalised), in pipeline order: resample, then smoothing */
+    resample: Option<resample::ResampleParams>,
+    smoothing: Option<smooth::SmoothParams>,
+    recordings: usize,
+    rows: usize,
+    app_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GazeStream {
+    meta: GazeMeta,
+    rows: Vec<GazeData>,
+}
+
+/* get_gaze_data plus the processing that produced it, for saving next to derived results */
+#[tauri::command]
+async fn get_gaze_stream(app: AppHandle, query: api::GazeQuery) -> Result<GazeStream, String> {
+    let q = query;
+    let smoothing = q.smoothing.as_ref().map(smooth::SmoothParams::normalized);
+    let rows = get_gaze_data(
+        q.test_name, None, q.participants, q.timeline, q.recording, q.limit, q.offset, q.cohort,
+        q.resample.clone(), smoothing.clone(), app.state(), app.state(),
+    )
+    .await?;
+    let recordings = rows.iter().map(|r| (&r.participant, &r.timeline, &r.recording)).collect::<HashSet<_>>().len();
+    let meta = GazeMeta {
+        resample: q.resample,
+        smoothing,
+        recordings,
+        rows: rows.len(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    Ok(GazeStream { meta, rows })
 }
 
 /* 3) Distinct (timeline, recording) for a test + optional participants */
@@ -404,10 +685,12 @@ async fn get_timeline_recordings(
     test_name: Option<String>,
     testName: Option<String>,
     participants: Vec<String>,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
     disabled: State<'_, DisabledStore>,
 ) -> Result<Vec<TimelineRecording>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
 
     let mut query = String::from(
@@ -423,6 +706,7 @@ async fn get_timeline_recordings(
         query.push_str(&vec!["?"; participants.len()].join(","));
         query.push(')');
     }
+    query.push_str(cohort_sql);
     // Exclude disabled (per test/participants)
     let disabled_set = disabled.0.read().unwrap();
     let mut disabled_filters: Vec<&DisabledSlice> = disabled_set
@@ -445,6 +729,7 @@ async fn get_timeline_recordings(
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
     let mut params: Vec<&dyn rusqlite::ToSql> = vec![&test];
     for p in &participants { params.push(p); }
+    if let Some(ref c) = cohort { params.push(c); }
     for ds in &disabled_filters {
         params.push(&ds.test_name);
         params.push(&ds.recording_name);
@@ -465,10 +750,12 @@ async fn get_timeline_recordings(
 async fn get_all_participant_sessions(
     tests: Vec<String>,
     participants: Vec<String>,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
     disabled: State<'_, DisabledStore>,
 ) -> Result<Vec<ParticipantSession>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     
     if tests.is_empty() || participants.is_empty() {
         return Ok(vec![]);
@@ -489,6 +776,7 @@ async fn get_all_participant_sessions(
     // Add participant placeholders
     query.push_str(&vec!["?"; participants.len()].join(","));
     query.push(')');
+    query.push_str(cohort_sql);
 
     // Exclude disabled sessions
     let disabled_set = disabled.0.read().unwrap();
@@ -515,6 +803,7 @@ async fn get_all_participant_sessions(
     for t in &tests { params.push(t); }
     // Add participant parameters
     for p in &participants { params.push(p); }
+    if let Some(ref c) = cohort { params.push(c); }
     // Add disabled filter parameters
     for ds in &disabled_filters {
         params.push(&ds.test_name);
@@ -536,20 +825,17 @@ async fn get_all_participant_sessions(
     Ok(rows.collect::<SqlResult<Vec<ParticipantSession>>>().map_err(|e| e.to_string())?)
 }
 
-/* 4) Box share stats for filtered slice */
-#[tauri::command]
-async fn get_box_stats(
-    test_name: Option<String>,
-    testName: Option<String>,
-    participants: Vec<String>,
-    timeline: Option<String>,
-    recording: Option<String>,
-    pool: State<'_, DbPool>,
-    disabled: State<'_, DisabledStore>,
-) -> Result<GazeStats, String> {
-    let conn = pool.0.get().map_err(|e| e.to_string())?;
-    let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
-
+/* GROUP BY Box over gaze_data for one slice (fallback while the aggregate cache builds) */
+fn box_counts_sql(
+    conn: &rusqlite::Connection,
+    test: &String,
+    participants: &[String],
+    timeline: &Option<String>,
+    recording: &Option<String>,
+    cohort: &Option<String>,
+    disabled_set: &HashSet<DisabledSlice>,
+) -> Result<HashMap<String, i64>, String> {
+    let cohort_sql = cohort::filter_sql(conn, cohort)?;
     let mut query = String::from(
         r#"
         SELECT Box, COUNT(*) AS count
@@ -565,12 +851,12 @@ async fn get_box_stats(
     }
     if timeline.is_some() { query.push_str(" AND \"Timeline name\" = ?"); }
     if recording.is_some() { query.push_str(" AND \"Recording name\" = ?"); }
+    query.push_str(cohort_sql);
 
     // Exclude disabled
-    let disabled_set = disabled.0.read().unwrap();
     let mut disabled_filters: Vec<&DisabledSlice> = disabled_set
         .iter()
-        .filter(|ds| ds.test_name == test)
+        .filter(|ds| &ds.test_name == test)
         .collect();
     if !participants.is_empty() {
         disabled_filters.retain(|ds| participants.contains(&ds.participant_name));
@@ -591,10 +877,11 @@ async fn get_box_stats(
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
 
-    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&test];
-    for p in &participants { params.push(p); }
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![test];
+    for p in participants { params.push(p); }
     if let Some(ref tl) = timeline { params.push(tl); }
     if let Some(ref rc) = recording { params.push(rc); }
+    if let Some(ref c) = cohort { params.push(c); }
     for ds in &disabled_filters {
         params.push(&ds.test_name);
         params.push(&ds.recording_name);
@@ -602,9 +889,6 @@ async fn get_box_stats(
     }
     // no validity filters (temporarily disabled)
 
-    let mut box_counts = HashMap::new();
-    let mut total_points = 0i64;
-
     let rows = stmt
         .query_map(rusqlite::params_from_iter(params), |row| {
             let box_name: String = row.get(0)?;
@@ -612,17 +896,104 @@ async fn get_box_stats(
             Ok((box_name, count))
         })
         .map_err(|e| e.to_string())?;
+    rows.collect::<SqlResult<HashMap<String, i64>>>().map_err(|e| e.to_string())
+}
 
-    for row in rows {
-        let (box_name, count) = row.map_err(|e| e.to_string())?;
-        total_points += count;
-        box_counts.insert(box_name, count as f64);
-    }
-    for count in box_counts.values_mut() {
-        *count = (*count / total_points as f64) * 100.0;
-    }
+/* Slice selection of get_box_stats beyond test / participants / cohort */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoxStatsOptions {
+    #[serde(default)]
+    pub timeline: Option<String>,
+    #[serde(default)]
+    pub recording: Option<String>,
+    /* count grid points instead of raw samples */
+    #[serde(default)]
+    pub resample: Option<resample::ResampleParams>,
+}
 
-    Ok(GazeStats { box_percentages: box_counts, total_points })
+/* Samples per Box for one test (the aggregate cache when given and applicable, else SQL) */
+pub(crate) fn box_counts(
+    conn: &rusqlite::Connection,
+    cached: Option<&cache::Aggregates>,
+    test: &str,
+    participants: &[String],
+    cohort: &Option<String>,
+    options: &BoxStatsOptions,
+    disabled: &HashSet<DisabledSlice>,
+) -> Result<HashMap<String, i64>, String> {
+    let BoxStatsOptions { timeline, recording, resample } = options;
+    Ok(match (cached.filter(|_| cohort.is_none() && resample.is_none()), resample) {
+        (Some(agg), _) => agg.box_counts(test, participants, timeline.as_deref(), recording.as_deref(), disabled),
+        (None, Some(params)) => {
+            // count grid points instead of raw samples
+            let recs = binning::load_recordings(conn, disabled, &[test.to_string()], participants, cohort)?;
+            let mut counts: HashMap<String, i64> = HashMap::new();
+            for rec in &recs {
+                if timeline.as_ref().is_some_and(|t| t != &rec.timeline) || recording.as_ref().is_some_and(|r| r != &rec.recording) { continue; }
+                for s in resample::samples(&rec.samples, Some(params)).iter() {
+                    *counts.entry(s.box_name.clone()).or_insert(0) += 1;
+                }
+            }
+            counts
+        }
+        (None, None) => box_counts_sql(conn, &test.to_string(), participants, timeline, recording, cohort, disabled)?,
+    })
+}
+
+pub(crate) fn gaze_stats(raw_counts: HashMap<String, i64>, transforms: bool) -> GazeStats {
+    let total_points: i64 = raw_counts.values().sum();
+    let box_counts: HashMap<String, f64> = raw_counts
+        .iter()
+        .map(|(b, c)| (b.clone(), (*c as f64 / total_points as f64) * 100.0))
+        .collect();
+    let transforms = transforms.then(|| {
+        raw_counts
+            .iter()
+            .filter_map(|(b, c)| stats::ProportionTransforms::new(*c, total_points).map(|t| (b.clone(), t)))
+            .collect()
+    });
+    GazeStats { box_percentages: box_counts, total_points, box_counts: raw_counts, transforms }
+}
+
+/* 4) Box share stats for filtered slice */
+#[tauri::command]
+async fn get_box_stats(
+    test_name: Option<String>,
+    testName: Option<String>,
+    participants: Vec<String>,
+    timeline: Option<String>,
+    recording: Option<String>,
+    transforms: Option<bool>,
+    cohort: Option<String>,
+    resample: Option<resample::ResampleParams>,
+    manifest: Option<bool>,
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    disabled: State<'_, DisabledStore>,
+    aggregates: State<'_, cache::AggregateStore>,
+) -> Result<manifest::WithManifest<GazeStats>, String> {
+    let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
+    let disabled_set = disabled.0.read().unwrap().clone();
+    let options = BoxStatsOptions { timeline, recording, resample };
+    let transforms = transforms.unwrap_or(false);
+
+    let cached = aggregates.0.read().unwrap().clone();
+    let raw_counts = {
+        let conn = pool.0.get().map_err(|e| e.to_string())?;
+        box_counts(&conn, cached.as_deref(), &test, &participants, &cohort, &options, &disabled_set)?
+    };
+    let result = gaze_stats(raw_counts, transforms);
+    let spec = || analysis::saved::AnalysisSpec {
+        participants,
+        cohort,
+        transforms,
+        box_stats: options,
+        ..analysis::saved::AnalysisSpec::new(
+            analysis::saved::AnalysisKind::BoxStats,
+            vec![analysis::ConditionDef { name: "tests".to_string(), tests: vec![test] }],
+        )
+    };
+    manifest::attach(&app, manifest, "get_box_stats", spec, &disabled_set, None, result)
 }
 
 /* 5) Lookup helpers for UI filtering */
@@ -630,16 +1001,20 @@ async fn get_box_stats(
 async fn get_participants_for_test(
     test_name: Option<String>,
     testName: Option<String>,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
     disabled: State<'_, DisabledStore>,
 ) -> Result<Vec<String>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
     let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let mut stmt = conn
-        .prepare(r#"SELECT DISTINCT "Participant name", "Recording name" FROM gaze_data WHERE "Test Name"=?1"#)
+        .prepare(&format!(r#"SELECT DISTINCT "Participant name", "Recording name" FROM gaze_data WHERE "Test Name"=?1{cohort_sql}"#))
         .map_err(|e| e.to_string())?;
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&test];
+    if let Some(ref c) = cohort { params.push(c); }
     let rows = stmt
-        .query_map([&test], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .query_map(rusqlite::params_from_iter(params), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
         .map_err(|e| e.to_string())?;
 
     let disabled_set = disabled.0.read().unwrap();
@@ -657,15 +1032,19 @@ async fn get_participants_for_test(
 #[tauri::command]
 async fn get_tests_for_participant(
     participant: String,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
     disabled: State<'_, DisabledStore>,
 ) -> Result<Vec<String>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let mut stmt = conn
-        .prepare(r#"SELECT DISTINCT "Test Name", "Recording name" FROM gaze_data WHERE "Participant name"=?1"#)
+        .prepare(&format!(r#"SELECT DISTINCT "Test Name", "Recording name" FROM gaze_data WHERE "Participant name"=?1{cohort_sql}"#))
         .map_err(|e| e.to_string())?;
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&participant];
+    if let Some(ref c) = cohort { params.push(c); }
     let rows = stmt
-        .query_map([&participant], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .query_map(rusqlite::params_from_iter(params), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
         .map_err(|e| e.to_string())?;
 
     let disabled_set = disabled.0.read().unwrap();
@@ -684,14 +1063,24 @@ async fn get_tests_for_participant(
       Falls back to legacy table name if needed. */
 #[tauri::command]
 async fn get_participants(
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
 ) -> Result<Vec<RowMap>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    cohort::filter_sql(&conn, &cohort)?;
     if table_exists(&conn, "participants") {
-        return dump_table(&conn, "participants");
+        let mut rows = dump_table(&conn, "participants")?;
+        if let Some(ref c) = cohort { rows.retain(|row| row.get("cohort").and_then(|v| v.as_deref()) == Some(c.as_str())); }
+        return Ok(rows);
     }
     if table_exists(&conn, "participant_qac") {
-        return dump_table(&conn, "participant_qac");
+        let mut rows = dump_table(&conn, "participant_qac")?;
+        // the legacy table is not unified across cohorts: match on the cohort's participant names
+        if let Some(ref c) = cohort {
+            let names = cohort::participants(&conn, c)?;
+            rows.retain(|row| row.get("participant").and_then(|v| v.as_deref()).is_some_and(|p| names.contains(p.trim())));
+        }
+        return Ok(rows);
     }
     Ok(vec![])
 }
@@ -702,9 +1091,11 @@ async fn list_gaze_slices(
     test_name: Option<String>,
     testName: Option<String>,
     participants: Vec<String>,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
 ) -> Result<Vec<DisabledSlice>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let test = test_name.or(testName);
 
     let mut query = String::from(
@@ -718,12 +1109,14 @@ async fn list_gaze_slices(
         query.push_str(&vec!["?"; participants.len()].join(","));
         query.push(')');
     }
+    query.push_str(cohort_sql);
     query.push_str(" ORDER BY 1,2,3");
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
     let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
     if let Some(ref t) = test { params.push(t); }
     for p in &participants { params.push(p); }
+    if let Some(ref c) = cohort { params.push(c); }
 
     let rows = stmt
         .query_map(rusqlite::params_from_iter(params), |row| {
@@ -741,15 +1134,23 @@ async fn list_gaze_slices(
 /* 8) Search tests: enrich with test_catalog fields and aggregated pair (mp4+png) duration */
 #[tauri::command]
 async fn search_tests(
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
 ) -> Result<Vec<SearchTestRow>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
+    // test_group is not unified across cohorts: a cohort keeps its catalog tests and its participants' media rows
+    let (names, grp_filter) = match cohort {
+        Some(_) => (
+            format!("SELECT DISTINCT test_name FROM test_catalog WHERE 1=1{cohort_sql}"),
+            format!(r#"WHERE "Participant name" IN (SELECT "Participant name" FROM gaze_data WHERE 1=1{cohort_sql})"#),
+        ),
+        None => ("SELECT DISTINCT test_name FROM test_group\n        UNION\n        SELECT DISTINCT test_name FROM test_catalog".to_string(), String::new()),
+    };
 
-    let sql = r#"
+    let sql = format!(r#"
     WITH names AS (
-        SELECT DISTINCT test_name FROM test_group
-        UNION
-        SELECT DISTINCT test_name FROM test_catalog
+        {names}
     ),
     tc AS (
         SELECT test_name,
@@ -757,6 +1158,7 @@ async fn search_tests(
                MIN(NULLIF("Image name", '')) AS image_name,
                MIN(NULLIF(sentence, '')) AS sentence
         FROM test_catalog
+        WHERE 1=1{cohort_sql}
         GROUP BY test_name
     ),
     grp AS (
@@ -772,6 +1174,7 @@ async fn search_tests(
                    SUM(CASE WHEN LOWER("Presented Media name") LIKE '%.png' THEN 1 ELSE 0 END) > 0
                ) THEN 1 ELSE 0 END AS has_both
         FROM test_group
+        {grp_filter}
         GROUP BY test_name, rec, part
     ),
     agg AS (
@@ -795,11 +1198,13 @@ async fn search_tests(
     LEFT JOIN tc  ON tc.test_name  = n.test_name
     LEFT JOIN agg ON agg.test_name = n.test_name
     ORDER BY n.test_name
-    "#;
+    "#);
 
-    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    // names, tc and grp each bind the cohort once
+    let params: Vec<&String> = match &cohort { Some(c) => vec![c; 3], None => Vec::new() };
     let rows = stmt
-        .query_map([], |row| {
+        .query_map(rusqlite::params_from_iter(params), |row| {
             Ok(SearchTestRow {
                 test_name: row.get(0)?,
                 group: row.get::<_, Option<String>>(1)?,
@@ -816,17 +1221,16 @@ async fn search_tests(
     Ok(rows.collect::<SqlResult<Vec<SearchTestRow>>>().map_err(|e| e.to_string())?)
 }
 
-#[tauri::command]
-async fn search_slices(
-    test_name: Option<String>,
-    testName: Option<String>,
-    participants: Vec<String>,
-    pool: State<'_, DbPool>,
+/* Test × recording × participant rows with catalog info and mp4/png durations */
+fn query_slices(
+    conn: &rusqlite::Connection,
+    test: &Option<String>,
+    participants: &[String],
+    cohort: &Option<String>,
 ) -> Result<Vec<SearchSliceRow>, String> {
-    let conn = pool.0.get().map_err(|e| e.to_string())?;
-    let test = test_name.or(testName);
+    let cohort_sql = cohort::filter_sql(conn, cohort)?;
 
-    let mut query = String::from(
+    let mut query = format!(
         r#"
         WITH triples AS (
             SELECT DISTINCT "Test Name"   AS test_name,
@@ -835,7 +1239,7 @@ async fn search_slices(
             FROM gaze_data
             WHERE "Test Name" IS NOT NULL AND TRIM("Test Name") <> ''
               AND "Recording name" IS NOT NULL AND TRIM("Recording name") <> ''
-              AND "Participant name" IS NOT NULL AND TRIM("Participant name") <> ''
+              AND "Participant name" IS NOT NULL AND TRIM("Participant name") <> ''{cohort_sql}
         ),
         tc AS (
             SELECT test_name,
@@ -882,8 +1286,10 @@ async fn search_slices(
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
     let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
+    // the cohort filter sits in the triples CTE, ahead of the outer filters
+    if let Some(ref c) = cohort { params.push(c); }
     if let Some(ref t) = test { params.push(t); }
-    for p in &participants { params.push(p); }
+    for p in participants { params.push(p); }
 
     let rows = stmt
         .query_map(rusqlite::params_from_iter(params), |row| {
@@ -901,7 +1307,19 @@ async fn search_slices(
         })
         .map_err(|e| e.to_string())?;
 
-    Ok(rows.collect::<SqlResult<Vec<SearchSliceRow>>>().map_err(|e| e.to_string())?)
+    rows.collect::<SqlResult<Vec<SearchSliceRow>>>().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn search_slices(
+    test_name: Option<String>,
+    testName: Option<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<SearchSliceRow>, String> {
+    let conn = pool.0.get().map_err(|e| e.to_string())?;
+    query_slices(&conn, &test_name.or(testName), &participants, &cohort)
 }
 
 #[tauri::command]
@@ -946,44 +1364,51 @@ async fn get_test_image(
     test_name: Option<String>,
     testName: Option<String>,
     timeline: Option<String>,
+    cohort: Option<String>,
     pool: State<'_, DbPool>,
 ) -> Result<Option<String>, String> {
     let conn = pool.0.get().map_err(|e| e.to_string())?;
+    let cohort_sql = cohort::filter_sql(&conn, &cohort)?;
     let test = test_name.or(testName).ok_or_else(|| "missing param: test_name/testName".to_string())?;
 
-    let try_sqls: [&str; 3] = [
+    let try_sqls: [String; 3] = [
         // exact + timeline
-        r#"
+        format!(r#"
         SELECT image_path
         FROM   test_catalog
         WHERE  image_path IS NOT NULL AND TRIM(image_path) <> '' 
                AND test_name = ?1 AND timeline = ?2
+               {cohort_sql}
         LIMIT  1
-        "#,
+        "#),
         // exact by test_name
-        r#"
+        format!(r#"
         SELECT image_path
         FROM   test_catalog
         WHERE  image_path IS NOT NULL AND TRIM(image_path) <> '' 
                AND test_name = ?1
+               {cohort_sql}
         LIMIT  1
-        "#,
+        "#),
         // prefix fallback
-        r#"
+        format!(r#"
         SELECT image_path
         FROM   test_catalog
         WHERE  image_path IS NOT NULL AND TRIM(image_path) <> '' 
                AND ?1 LIKE test_name || '%'
+               {cohort_sql}
         LIMIT  1
-        "#,
+        "#),
     ];
 
     let mut image_path: Option<String> = None;
 
     if let Some(ref tl) = timeline {
-        if let Ok(mut stmt) = conn.prepare(try_sqls[0]) {
+        if let Ok(mut stmt) = conn.prepare(&try_sqls[0]) {
+            let mut params: Vec<&dyn rusqlite::ToSql> = vec![&test, tl];
+            if let Some(ref c) = cohort { params.push(c); }
             image_path = stmt
-                .query_row(rusqlite::params![&test, tl], |row| {
+                .query_row(rusqlite::params_from_iter(params), |row| {
                     row.get::<_, Option<String>>("image_path")
                 })
                 .optional()
@@ -993,18 +1418,20 @@ async fn get_test_image(
     }
 
     if image_path.is_none() {
-        let mut stmt = conn.prepare(try_sqls[1]).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(&try_sqls[1]).map_err(|e| e.to_string())?;
+        let params: Vec<&String> = std::iter::once(&test).chain(cohort.as_ref()).collect();
         image_path = stmt
-            .query_row([&test], |row| row.get::<_, Option<String>>("image_path"))
+            .query_row(rusqlite::params_from_iter(params), |row| row.get::<_, Option<String>>("image_path"))
             .optional()
             .map_err(|e| e.to_string())?
             .flatten();
     }
 
     if image_path.is_none() {
-        let mut stmt = conn.prepare(try_sqls[2]).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(&try_sqls[2]).map_err(|e| e.to_string())?;
+        let params: Vec<&String> = std::iter::once(&test).chain(cohort.as_ref()).collect();
         image_path = stmt
-            .query_row([&test], |row| row.get::<_, Option<String>>("image_path"))
+            .query_row(rusqlite::params_from_iter(params), |row| row.get::<_, Option<String>>("image_path"))
             .optional()
             .map_err(|e| e.to_string())?
             .flatten();
@@ -1033,7 +1460,7 @@ App bootstrap
 pub fn run() {
     tauri::Builder::default()
         // splash tracking state
-        .manage(Mutex::new(SetupState { frontend_task: false, backend_task: false }))
+        .manage(Mutex::new(SetupState { frontend_task: false, backend_task: false, acknowledged: false }))
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
             println!("Setting up the application...");
@@ -1053,38 +1480,40 @@ pub fn run() {
             }
 
             // Open read-only, immutable
-            let mut url = Url::from_file_path(&resource_path).map_err(|_| {
-                println!("Bad DB path for URL: {:?}", &resource_path);
-                "bad DB path"
+            let pool = project::open_pool(&resource_path, true).map_err(|e| {
+                println!("Failed to create DB pool: {e}");
+                e
             })?;
-            url.set_query(Some("mode=ro&immutable=1"));
-
-            let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI;
-
-            let manager = SqliteConnectionManager::file(url.as_str())
-                .with_flags(flags)
-                .with_init(|c| {
-                    c.execute_batch("PRAGMA query_only=ON;")?;
-                    Ok(())
-                });
 
-            let pool = r2d2::Pool::builder()
-                .max_size(4)
-                .connection_timeout(Duration::from_secs(10))
-                .build(manager)
-                .map_err(|e| {
-                    println!("Failed to create DB pool: {e}");
-                    e
-                })?;
-
-            app.manage(DbPool(Arc::new(pool)));
+            // Bundled DB is the initial (and fallback) active database
+            let bundled = fs::canonicalize(&resource_path).unwrap_or(resource_path);
+            let pool = Arc::new(pool);
+            app.manage(DbPool(project::SwappablePool::new(pool.clone())));
+            app.manage(project::DbPath(RwLock::new(bundled.clone())));
+            app.manage(project::Projects {
+                open: Mutex::new([(bundled.clone(), pool)].into_iter().collect()),
+                bundled,
+            });
             let handle = app.handle();
             let disabled_set = load_disabled_from_disk(&handle);
             app.manage(DisabledStore(Arc::new(RwLock::new(disabled_set))));
+            app.manage(cohort::CohortState(Mutex::new(None)));
             println!("Setup completed (read-only). Disabled slices loaded.");
-            // Mark backend ready for splashscreen (non-blocking)
+
+            // Aggregate cache: load (or rebuild when the DB hash changed) off the main thread
+            app.manage(cache::AggregateStore(Arc::new(RwLock::new(None))));
+            cache::refresh_in_background(app.handle().clone());
+            // Health check of the bundled DB, then mark backend ready for splashscreen (non-blocking)
+            app.manage(validate::HealthStore(Mutex::new(None)));
+            app.manage(api::ApiState::default());
             let handle = app.handle().clone();
             spawn(async move {
+                let h = handle.clone();
+                match spawn_blocking(move || validate::check_active(&h)).await {
+                    Ok(Ok(report)) => println!("DB health: {} fatal, {} warning(s).", report.fatal, report.warnings),
+                    Ok(Err(e)) => println!("DB health check failed: {e}"),
+                    Err(e) => println!("DB health check failed: {e}"),
+                }
                 let _ = set_complete(handle.clone(), handle.state::<Mutex<SetupState>>(), "backend".to_string()).await;
             });
             Ok(())
@@ -1098,6 +1527,7 @@ pub fn run() {
             get_timeline_recordings,
             get_all_participant_sessions,
             get_gaze_data,
+            get_gaze_stream,
             get_box_stats,
             get_participants_for_test,
             get_tests_for_participant,
@@ -1113,6 +1543,64 @@ pub fn run() {
             get_disabled_slices,
             set_disabled_slices,
             toggle_disabled_slice,
+            // server-side binning + analyses
+            binning::get_aoi_bins,
+            analysis::growth::fit_growth_curve,
+            analysis::divergence::get_divergence_point,
+            analysis::lmm::fit_lmm,
+            analysis::multiplicity::adjust_p_values,
+            analysis::multiplicity::tfce_scores,
+            // aggregate cache
+            cache::get_aggregate_cache_status,
+            cache::rebuild_aggregate_cache,
+            // index diagnostics
+            indexes::diagnose_indexes,
+            // study databases
+            project::list_databases,
+            project::open_database,
+            project::switch_database,
+            project::close_database,
+            // cross-database cohorts
+            cohort::attach_cohorts,
+            cohort::detach_cohorts,
+            cohort::get_cohorts,
+            // schema validation / health
+            validate::validate_database,
+            validate::get_database_health,
+            // saved analyses
+            analysis::saved::list_analyses,
+            analysis::saved::load_analysis,
+            analysis::saved::save_analysis,
+            analysis::saved::duplicate_analysis,
+            analysis::saved::delete_analysis,
+            analysis::saved::run_analysis,
+            // item- and participant-level summaries
+            items::get_item_stats,
+            participant::get_participant_summary,
+            // window measures
+            measures::get_window_measures,
+            // AOI transitions
+            transitions::get_aoi_transitions,
+            // calibration / precision quality
+            quality::import_calibration,
+            quality::get_calibration,
+            quality::get_precision,
+            quality::preview_quality_exclusions,
+            // reproducibility manifests
+            manifest::build_manifest,
+            manifest::rerun_manifest,
+            // reports and exports
+            report::generate_report,
+            export::export_workbook,
+            // local HTTP API
+            api::start_api_server,
+            api::stop_api_server,
+            api::get_api_server,
+            // synthetic data
+            synth::generate_synthetic_db,
+            // catalog derivation from sentences
+            sentence::parse_sentence,
+            sentence::derive_test_catalog,
             // splashscreen control
             set_complete,
         ])
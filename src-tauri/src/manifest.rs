@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{self, AnalysisOutput, AnalysisSpec, ExclusionProfile};
+use crate::aoi::{AoiSets, AOI_CODE_TO_BOX};
+use crate::cache::{self, AggregateStore, DbFingerprint};
+use crate::cohort::{AttachedCohort, CohortState};
+use crate::project::DbPath;
+use crate::{DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Reproducibility manifests: which DB (hash), app version, exclusions,
+AOI definitions, seed and parameters produced a result, plus a hash of
+the result itself. A manifest re-runs through the saved-analysis runner
+and the new output is diffed against the original. adjust_p_values and
+tfce_scores get none: they only transform numbers the caller passes in,
+so there is no DB, exclusion or AOI state to record.
+────────────────────────────────────────────────────────────── */
+
+const MANIFEST_VERSION: u32 = 1;
+/* numbers closer than this (relative) count as equal when diffing */
+const REL_TOLERANCE: f64 = 1e-9;
+const MAX_DIFFS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExclusionSet {
+    /* SHA-256 of the sorted slices as JSON */
+    pub sha256: String,
+    pub slices: Vec<DisabledSlice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub manifest_version: u32,
+    pub app_version: String,
+    pub created_at_ms: u64,
+    /* command that produced the result */
+    pub command: String,
+    pub db: DbFingerprint,
+    #[serde(default)]
+    pub cohorts: Vec<AttachedCohort>,
+    pub exclusions: ExclusionSet,
+    pub aoi: AoiSets,
+    /* AOI code → Box name table in effect */
+    pub aoi_codes: BTreeMap<String, String>,
+    pub seed: Option<u64>,
+    /* full parameters, replayable through the saved-analysis runner */
+    pub spec: AnalysisSpec,
+    /* SHA-256 of the result as canonical (key-sorted) JSON */
+    pub result_sha256: String,
+}
+
+/* A command result with its manifest alongside (only when asked for) */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithManifest<T> {
+    #[serde(flatten)]
+    pub result: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<Manifest>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn sha256_json(v: &Value) -> String {
+    format!("{:x}", Sha256::digest(serde_json::to_vec(v).unwrap_or_default()))
+}
+
+/* Going through Value sorts object keys, so the hash does not depend on field order */
+pub fn result_sha256<T: Serialize>(result: &T) -> Result<String, String> {
+    Ok(sha256_json(&serde_json::to_value(result).map_err(|e| e.to_string())?))
+}
+
+pub fn exclusion_set(excluded: &HashSet<DisabledSlice>) -> ExclusionSet {
+    let mut slices: Vec<DisabledSlice> = excluded.iter().cloned().collect();
+    slices.sort_by(|a, b| {
+        (&a.test_name, &a.recording_name, &a.participant_name).cmp(&(&b.test_name, &b.recording_name, &b.participant_name))
+    });
+    let sha256 = sha256_json(&serde_json::to_value(&slices).unwrap_or_default());
+    ExclusionSet { sha256, slices }
+}
+
+/* Fingerprint of the active DB, reusing the aggregate cache's hash when size + mtime still match */
+pub fn db_fingerprint(app: &AppHandle) -> Result<DbFingerprint, String> {
+    let path = app.state::<DbPath>().0.read().unwrap().clone();
+    let known = app.state::<AggregateStore>().0.read().unwrap().clone();
+    cache::fingerprint(&path, known.as_ref().map(|a| &a.fingerprint))
+}
+
+pub fn build<T: Serialize>(
+    app: &AppHandle,
+    command: &str,
+    spec: AnalysisSpec,
+    excluded: &HashSet<DisabledSlice>,
+    seed: Option<u64>,
+    result: &T,
+) -> Result<Manifest, String> {
+    let cohorts = app.state::<CohortState>().0.lock().unwrap().as_ref().map(|r| r.cohorts.clone()).unwrap_or_default();
+    Ok(Manifest {
+        manifest_version: MANIFEST_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at_ms: now_ms(),
+        command: command.to_string(),
+        db: db_fingerprint(app)?,
+        cohorts,
+        exclusions: exclusion_set(excluded),
+        aoi: spec.aoi.clone(),
+        aoi_codes: AOI_CODE_TO_BOX.iter().map(|(c, b)| (c.to_string(), b.to_string())).collect(),
+        seed,
+        spec,
+        result_sha256: result_sha256(result)?,
+    })
+}
+
+/* Wrap `result`, building the manifest only when `wanted` */
+pub fn attach<T: Serialize>(
+    app: &AppHandle,
+    wanted: Option<bool>,
+    command: &str,
+    spec: impl FnOnce() -> AnalysisSpec,
+    excluded: &HashSet<DisabledSlice>,
+    seed: Option<u64>,
+    result: T,
+) -> Result<WithManifest<T>, String> {
+    let manifest = match wanted {
+        Some(true) => Some(build(app, command, spec(), excluded, seed, &result)?),
+        _ => None,
+    };
+    Ok(WithManifest { result, manifest })
+}
+
+/* ─────────────────────────── Diffing ─────────────────────────── */
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValueDiff {
+    /* JSON pointer into the result */
+    pub path: String,
+    pub original: Value,
+    pub rerun: Value,
+}
+
+fn diff(path: &str, a: &Value, b: &Value, out: &mut Vec<ValueDiff>, count: &mut usize, max_abs: &mut f64) {
+    let mut push = |out: &mut Vec<ValueDiff>| {
+        *count += 1;
+        if out.len() < MAX_DIFFS {
+            out.push(ValueDiff { path: path.to_string(), original: a.clone(), rerun: b.clone() });
+        }
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(f64::NAN), y.as_f64().unwrap_or(f64::NAN));
+            let d = (x - y).abs();
+            if d > REL_TOLERANCE * x.abs().max(y.abs()).max(1.0) {
+                *max_abs = max_abs.max(d);
+                push(out);
+            }
+        }
+        (Value::Array(xs), Value::Array(ys)) if xs.len() == ys.len() => {
+            for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                diff(&format!("{path}/{i}"), x, y, out, count, max_abs);
+            }
+        }
+        (Value::Object(xs), Value::Object(ys)) => {
+            let keys: std::collections::BTreeSet<&String> = xs.keys().chain(ys.keys()).collect();
+            for k in keys {
+                let p = format!("{path}/{}", k.replace('~', "~0").replace('/', "~1"));
+                match (xs.get(k), ys.get(k)) {
+                    (Some(x), Some(y)) => diff(&p, x, y, out, count, max_abs),
+                    (x, y) => {
+                        *count += 1;
+                        if out.len() < MAX_DIFFS {
+                            out.push(ValueDiff { path: p, original: x.cloned().unwrap_or(Value::Null), rerun: y.cloned().unwrap_or(Value::Null) });
+                        }
+                    }
+                }
+            }
+        }
+        _ if a == b => {}
+        _ => push(out),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestChecks {
+    pub db_matches: bool,
+    pub current_db_sha256: String,
+    pub app_version_matches: bool,
+    pub current_app_version: String,
+    pub cohorts_match: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RerunReport {
+    pub checks: ManifestChecks,
+    /* re-run result hash equals manifest.result_sha256 */
+    pub identical: bool,
+    pub result_sha256: String,
+    pub expected_sha256: String,
+    /* None when no original output was supplied */
+    pub n_differences: Option<usize>,
+    pub max_abs_difference: Option<f64>,
+    pub differences: Vec<ValueDiff>,
+    pub output: AnalysisOutput,
+}
+
+/* The bare result of what a command returned (drops the manifest, an export's file path and the run_analysis envelope) */
+fn original_result(original: Value) -> Value {
+    let mut v = match original {
+        Value::Object(mut m) if m.contains_key("kind") && m.contains_key("result") => m.remove("result").unwrap_or(Value::Null),
+        v => v,
+    };
+    if let Value::Object(m) = &mut v {
+        m.remove("manifest");
+        m.remove("path");
+    }
+    v
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Manifest for a result computed elsewhere (e.g. a client-side export) */
+#[tauri::command]
+pub async fn build_manifest(
+    app: AppHandle,
+    command: String,
+    spec: AnalysisSpec,
+    seed: Option<u64>,
+    result: Value,
+) -> Result<Manifest, String> {
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let quality = saved::quality_exclusions(&app, &conn, &spec)?;
+    let excluded = saved::exclusions(&spec, &disabled, &quality);
+    build(&app, &command, spec, &excluded, seed, &result)
+}
+
+/* Re-run the analysis a manifest describes (with its exact exclusions) and diff against `original` */
+#[tauri::command]
+pub async fn rerun_manifest(app: AppHandle, manifest: Manifest, original: Option<Value>) -> Result<RerunReport, String> {
+    if manifest.manifest_version > MANIFEST_VERSION {
+        return Err(format!("manifest v{} is newer than this app supports", manifest.manifest_version));
+    }
+    let mut spec = manifest.spec.clone();
+    spec.exclusions = ExclusionProfile { use_disabled: false, slices: manifest.exclusions.slices.clone(), quality: None };
+    if let Some(seed) = manifest.seed { spec.divergence.seed = Some(seed); }
+
+    let current = db_fingerprint(&app)?;
+    let current_cohorts = app.state::<CohortState>().0.lock().unwrap().as_ref().map(|r| r.cohorts.clone()).unwrap_or_default();
+    let paths = |c: &[AttachedCohort]| c.iter().map(|c| c.path.clone()).collect::<Vec<_>>();
+    let checks = ManifestChecks {
+        db_matches: current.sha256 == manifest.db.sha256,
+        current_db_sha256: current.sha256,
+        app_version_matches: manifest.app_version == env!("CARGO_PKG_VERSION"),
+        current_app_version: env!("CARGO_PKG_VERSION").to_string(),
+        cohorts_match: paths(&current_cohorts) == paths(&manifest.cohorts),
+    };
+
+    let excluded: HashSet<DisabledSlice> = spec.exclusions.slices.iter().cloned().collect();
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let output = saved::run(&app, &conn, &spec, &excluded)?;
+    let rerun = output.result_value()?;
+    let result_sha256 = sha256_json(&rerun);
+
+    let (mut differences, mut n, mut max_abs) = (Vec::new(), 0usize, 0.0f64);
+    let compared = original.map(|o| diff("", &original_result(o), &rerun, &mut differences, &mut n, &mut max_abs)).is_some();
+    Ok(RerunReport {
+        checks,
+        identical: result_sha256 == manifest.result_sha256,
+        result_sha256,
+        expected_sha256: manifest.result_sha256,
+        n_differences: compared.then_some(n),
+        max_abs_difference: (compared && n > 0).then_some(max_abs),
+        differences,
+        output,
+    })
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::ConditionDef;
+use crate::aoi::{self, BASE_AOI_KEYS};
+use crate::binning::{anchor_ms, load_recordings, word_windows, GazeSample, WindowParams};
+use crate::manifest::{self, WithManifest};
+use crate::resample;
+use crate::{DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Classic window measures per trial (recording) × AOI category (catalog
+AOI column): time to first fixation, first fixation duration, total
+dwell, number of visits and the share of the window. gaze_data holds
+raw samples, so a "visit" is a run of consecutive samples on the
+category (short invalid gaps bridged) and a fixation is a visit lasting
+at least min_fixation_ms. Each valid sample counts for the recording's
+median sample interval.
+────────────────────────────────────────────────────────────── */
+
+/* catalog columns attached to each row (as in items.rs) */
+const FACTORS: [&str; 6] = ["group", "truth_value", "only_position", "morpheme", "series", "case_no"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeasureWindow {
+    /* [start_ms, end_ms) after trial onset, or after the anchor word's onset; with
+       `resample`, the grid step becomes the sample duration */
+    #[serde(flatten)]
+    pub window: WindowParams,
+    /* use this word's spoken span from word_windows_json instead (start_ms / end_ms ignored) */
+    #[serde(default)]
+    pub word_window: Option<String>,
+    /* shifts both edges of the word span (e.g. ~200 ms for saccade latency) */
+    #[serde(default)]
+    pub word_shift_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasureOptions {
+    #[serde(default = "default_min_fixation_ms")]
+    pub min_fixation_ms: f64,
+    /* invalid (e.g. missing) stretches up to this long do not end a visit */
+    #[serde(default = "default_max_gap_ms")]
+    pub max_gap_ms: f64,
+    #[serde(default = "default_invalid")]
+    pub invalid: Vec<String>,
+}
+
+fn default_min_fixation_ms() -> f64 { 60.0 }
+fn default_max_gap_ms() -> f64 { 75.0 }
+fn default_invalid() -> Vec<String> { vec!["missing".to_string()] }
+
+impl Default for MeasureOptions {
+    fn default() -> Self {
+        MeasureOptions {
+            min_fixation_ms: default_min_fixation_ms(),
+            max_gap_ms: default_max_gap_ms(),
+            invalid: default_invalid(),
+        }
+    }
+}
+
+/* Window, categories and options together (as stored in an analysis spec) */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeasureSpec {
+    #[serde(default)]
+    pub window: MeasureWindow,
+    /* catalog AOI columns; empty = all */
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub options: MeasureOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrialMeasure {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub category: String,
+    pub factors: BTreeMap<String, Option<String>>,
+    /* window start, ms after the first sample of the recording */
+    pub window_start_ms: f64,
+    pub window_ms: f64,
+    pub valid_ms: f64,
+    /* from window start; None when the category is never fixated */
+    pub time_to_first_fixation_ms: Option<f64>,
+    pub first_fixation_ms: Option<f64>,
+    pub dwell_ms: f64,
+    pub visits: usize,
+    /* dwell / window, dwell / valid time */
+    pub window_prop: f64,
+    pub valid_prop: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WindowMeasures {
+    pub rows: Vec<TrialMeasure>,
+    /* recordings left out because their test has no such word window */
+    pub skipped: Vec<String>,
+}
+
+fn median_interval(samples: &[GazeSample]) -> f64 {
+    let mut d: Vec<f64> = samples.windows(2).map(|w| w[1].t_ms() - w[0].t_ms()).filter(|d| *d > 0.0).collect();
+    if d.is_empty() { return 0.0; }
+    d.sort_by(f64::total_cmp);
+    d[d.len() / 2]
+}
+
+/* Visits (start, end) of `boxes` over `samples` (already clipped to the window) */
+fn visits(samples: &[GazeSample], boxes: &HashSet<String>, invalid: &HashSet<&str>, dt: f64, max_gap_ms: f64) -> Vec<(f64, f64)> {
+    let mut out = Vec::new();
+    let mut run: Option<(f64, f64)> = None;
+    for s in samples {
+        if invalid.contains(s.box_name.as_str()) { continue; }
+        if !boxes.contains(&s.box_name) {
+            if let Some((a, b)) = run.take() { out.push((a, b + dt)); }
+            continue;
+        }
+        let t = s.t_ms();
+        run = match run {
+            Some((a, b)) if t - b <= dt + max_gap_ms => Some((a, t)),
+            Some((a, b)) => {
+                out.push((a, b + dt));
+                Some((t, t))
+            }
+            None => Some((t, t)),
+        };
+    }
+    if let Some((a, b)) = run { out.push((a, b + dt)); }
+    out
+}
+
+/* Trial-level table for `tests` with `excluded` slices left out */
+pub fn window_measures(
+    conn: &rusqlite::Connection,
+    excluded: &HashSet<DisabledSlice>,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    spec: &MeasureSpec,
+) -> Result<WindowMeasures, String> {
+    let MeasureSpec { window, options, .. } = spec;
+    let categories: Vec<String> = if spec.categories.is_empty() {
+        BASE_AOI_KEYS.iter().map(|k| k.to_string()).collect()
+    } else {
+        spec.categories.clone()
+    };
+    let invalid: HashSet<&str> = options.invalid.iter().map(String::as_str).collect();
+
+    let catalog = aoi::load_catalog(conn)?;
+    let recs = load_recordings(conn, excluded, tests, participants, cohort)?;
+
+    // the fixed window only matters (and is only checked) without a word window
+    let fixed = match window.word_window {
+        None => Some(window.window.as_bins()?),
+        Some(_) => None,
+    };
+    let mut rows = Vec::new();
+    let mut skipped = Vec::new();
+    for rec in &recs {
+        let row = catalog.get(&rec.test_name);
+        let Some(base) = rec.samples.first().map(GazeSample::t_ms) else { continue; };
+        let (start, end) = match &window.word_window {
+            Some(w) => {
+                let span = row.map(word_windows).unwrap_or_default().into_iter().find(|(word, _, _)| word == w);
+                let Some((_, a, b)) = span else {
+                    skipped.push(format!("{} / {} / {}", rec.test_name, rec.participant, rec.recording));
+                    continue;
+                };
+                (base + a * 1000.0 + window.word_shift_ms, base + b * 1000.0 + window.word_shift_ms)
+            }
+            None => {
+                let Some(bins) = fixed.as_ref() else { continue; };
+                let start = anchor_ms(rec, row, bins);
+                (start, start + bins.bin_ms)
+            }
+        };
+        let samples = resample::samples(&rec.samples, window.window.resample.as_ref());
+        let dt = median_interval(&samples);
+        let inside: Vec<GazeSample> = samples.iter().filter(|s| (start..end).contains(&s.t_ms())).cloned().collect();
+        let valid_ms = inside.iter().filter(|s| !invalid.contains(s.box_name.as_str())).count() as f64 * dt;
+        let window_ms = (end - start).max(0.0);
+        let factors: BTreeMap<String, Option<String>> = FACTORS
+            .iter()
+            .map(|f| (f.to_string(), row.and_then(|r| aoi::pick(r, f)).map(|v| v.trim().to_string())))
+            .collect();
+
+        for category in &categories {
+            let boxes: HashSet<String> = row.map(|r| aoi::boxes_for(r, std::slice::from_ref(category))).unwrap_or_default();
+            let dwell_ms = inside.iter().filter(|s| boxes.contains(&s.box_name)).count() as f64 * dt;
+            let runs = visits(&inside, &boxes, &invalid, dt, options.max_gap_ms);
+            let first = runs.iter().find(|(a, b)| b - a >= options.min_fixation_ms);
+            rows.push(TrialMeasure {
+                cohort: rec.cohort.clone(),
+                test_name: rec.test_name.clone(),
+                participant: rec.participant.clone(),
+                timeline: rec.timeline.clone(),
+                recording: rec.recording.clone(),
+                category: category.clone(),
+                factors: factors.clone(),
+                window_start_ms: start - base,
+                window_ms,
+                valid_ms,
+                time_to_first_fixation_ms: first.map(|(a, _)| a - start),
+                first_fixation_ms: first.map(|(a, b)| b - a),
+                dwell_ms,
+                visits: runs.iter().filter(|(a, b)| b - a >= options.min_fixation_ms).count(),
+                window_prop: if window_ms > 0.0 { dwell_ms / window_ms } else { 0.0 },
+                valid_prop: (valid_ms > 0.0).then(|| dwell_ms / valid_ms),
+            });
+        }
+    }
+    Ok(WindowMeasures { rows, skipped })
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Trial-level table (one row per recording × category); `categories` are catalog AOI columns (default: all) */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_window_measures(
+    app: AppHandle,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    window: Option<MeasureWindow>,
+    categories: Option<Vec<String>>,
+    options: Option<MeasureOptions>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<WindowMeasures>, String> {
+    let measures = MeasureSpec {
+        window: window.unwrap_or_default(),
+        categories: categories.unwrap_or_default(),
+        options: options.unwrap_or_default(),
+    };
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let result = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        window_measures(&conn, &disabled, &tests, &participants, &cohort, &measures)?
+    };
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        measures,
+        ..AnalysisSpec::new(AnalysisKind::Measures, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    manifest::attach(&app, manifest, "get_window_measures", spec, &disabled, None, result)
+}
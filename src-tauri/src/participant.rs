@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::aoi::{load_catalog, pick, AoiClass, AoiSets, TestAoi};
+use crate::items::{participant_box_counts, ItemOptions, ProportionSummary};
+use crate::manifest::{self, WithManifest};
+use crate::resample::ResampleParams;
+use crate::{table_exists, DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Per-child summary over every test they completed (disabled slices left
+out, as in get_tests_for_participant). Each test contributes one valid %
+(samples outside the invalid boxes) and one correct-AOI proportion
+(blue samples / valid samples, get_box_stats shares restricted to the
+catalog's correct_AOIs by default); these are averaged per participant
+and per truth_value × only_position condition.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConditionSummary {
+    pub truth_value: Option<String>,
+    pub only_position: Option<String>,
+    pub tests: usize,
+    pub correct: Option<ProportionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantSummary {
+    pub participant: String,
+    pub is_qac: Option<bool>,
+    pub tests_completed: usize,
+    pub tests: Vec<String>,
+    pub samples: i64,
+    /* mean over tests, 0–100 */
+    pub mean_valid_pct: Option<f64>,
+    pub correct: Option<ProportionSummary>,
+    pub conditions: Vec<ConditionSummary>,
+    /* no valid sample in any blue or red AOI across all tests; None without tests */
+    pub never_reaches_aoi: Option<bool>,
+}
+
+/* the command's result (an object, so a manifest can sit alongside) */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantSummaries {
+    pub participants: Vec<ParticipantSummary>,
+}
+
+type ConditionKey = (Option<String>, Option<String>);
+
+fn qac_flags(conn: &rusqlite::Connection) -> HashMap<String, bool> {
+    if !table_exists(conn, "participants") { return HashMap::new(); }
+    let Ok(mut stmt) = conn.prepare("SELECT participant, is_qac FROM participants") else { return HashMap::new(); };
+    stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?)))
+        .map(|rows| rows.filter_map(Result::ok).filter_map(|(p, q)| Some((p.trim().to_string(), q? != 0))).collect())
+        .unwrap_or_default()
+}
+
+/* One summary per spec participant (empty = everyone with gaze data) with `excluded` slices left out */
+pub fn participant_summary(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    spec: &AnalysisSpec,
+    excluded: &HashSet<DisabledSlice>,
+) -> Result<Vec<ParticipantSummary>, String> {
+    let participants = &spec.participants;
+    let counts = participant_box_counts(app, &[], participants, &spec.cohort, excluded, spec.items.resample.as_ref())?;
+    let (catalog, qac) = (load_catalog(conn)?, qac_flags(conn));
+    let sets = &spec.aoi;
+
+    let mut by_participant = BTreeMap::new();
+    // requested participants whose slices are all disabled still get a (zero-test) row
+    for p in participants { by_participant.entry(p.as_str()).or_insert_with(Vec::new); }
+    for ((test, p), c) in &counts { by_participant.entry(p.as_str()).or_insert_with(Vec::new).push((test.as_str(), c)); }
+
+    let mut out = Vec::new();
+    for (participant, tests) in by_participant {
+        let mut valid_pct: Vec<f64> = Vec::new();
+        let mut correct: Vec<f64> = Vec::new();
+        // (truth_value, only_position) → (tests, correct shares)
+        let mut by_condition: BTreeMap<ConditionKey, (usize, Vec<f64>)> = BTreeMap::new();
+        let (mut samples, mut aoi_samples) = (0, 0);
+        for (test, c) in &tests {
+            let row = catalog.get(*test);
+            let resolved = TestAoi::resolve(row, sets);
+            let (mut valid, mut blue, mut total) = (0, 0, 0);
+            for (b, n) in c.iter() {
+                total += n;
+                match resolved.classify(b) {
+                    AoiClass::Invalid => continue,
+                    AoiClass::Blue => { blue += n; aoi_samples += n; }
+                    AoiClass::Red => aoi_samples += n,
+                    AoiClass::Neither => {}
+                }
+                valid += n;
+            }
+            samples += total;
+            if total > 0 { valid_pct.push(valid as f64 / total as f64 * 100.0); }
+            let factor = |k: &str| row.and_then(|r| pick(r, k)).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+            let cell = by_condition.entry((factor("truth_value"), factor("only_position"))).or_default();
+            cell.0 += 1;
+            if valid > 0 {
+                let share = blue as f64 / valid as f64;
+                correct.push(share);
+                cell.1.push(share);
+            }
+        }
+        out.push(ParticipantSummary {
+            participant: participant.to_string(),
+            is_qac: qac.get(participant.trim()).copied(),
+            tests_completed: tests.len(),
+            tests: tests.iter().map(|(t, _)| t.to_string()).collect(),
+            samples,
+            mean_valid_pct: (!valid_pct.is_empty()).then(|| valid_pct.iter().sum::<f64>() / valid_pct.len() as f64),
+            correct: ProportionSummary::of(&correct),
+            conditions: by_condition
+                .into_iter()
+                .map(|((truth_value, only_position), (n, v))| ConditionSummary {
+                    truth_value,
+                    only_position,
+                    tests: n,
+                    correct: ProportionSummary::of(&v),
+                })
+                .collect(),
+            never_reaches_aoi: (!tests.is_empty()).then_some(aoi_samples == 0),
+        });
+    }
+    Ok(out)
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* One summary per participant (empty `participants` = everyone with gaze data) */
+#[tauri::command]
+pub async fn get_participant_summary(
+    app: AppHandle,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    aoi: Option<AoiSets>,
+    resample: Option<ResampleParams>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<ParticipantSummaries>, String> {
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let spec = AnalysisSpec {
+        participants,
+        cohort,
+        aoi: aoi.unwrap_or_default(),
+        items: ItemOptions { condition_by: None, resample },
+        ..AnalysisSpec::new(AnalysisKind::Participants, Vec::new())
+    };
+    let participants = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        participant_summary(&app, &conn, &spec, &disabled)?
+    };
+    manifest::attach(&app, manifest, "get_participant_summary", || spec, &disabled, None, ParticipantSummaries { participants })
+}
@@ -0,0 +1,257 @@
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OpenFlags;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::async_runtime::spawn_blocking;
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Emitter, Manager, State};
+use url::Url;
+
+use crate::cohort::CohortState;
+use crate::{cache, validate, load_disabled_from_disk, table_exists, DbPool, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Study databases: the bundled DB plus any number of user-chosen project
+DBs. One is active at a time; switching swaps the pool behind `DbPool`,
+reloads that DB's disabled slices and rebuilds its aggregate cache.
+────────────────────────────────────────────────────────────── */
+
+const MAX_RECENT: usize = 10;
+
+pub type SqlitePool = Pool<SqliteConnectionManager>;
+
+/* Pool handle that can be replaced while commands hold connections from the old one */
+pub struct SwappablePool(RwLock<Arc<SqlitePool>>);
+
+impl SwappablePool {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        SwappablePool(RwLock::new(pool))
+    }
+
+    pub fn get(&self) -> Result<PooledConnection<SqliteConnectionManager>, r2d2::Error> {
+        let pool = self.0.read().unwrap().clone();
+        pool.get()
+    }
+
+    pub fn swap(&self, pool: Arc<SqlitePool>) {
+        *self.0.write().unwrap() = pool;
+    }
+}
+
+/* Path of the active DB */
+pub struct DbPath(pub RwLock<PathBuf>);
+
+/* Open pools by path (the bundled DB is always open) */
+pub struct Projects {
+    pub bundled: PathBuf,
+    pub open: Mutex<BTreeMap<PathBuf, Arc<SqlitePool>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub path: String,
+    pub name: String,
+    pub last_opened_ms: u64,
+    #[serde(default)]
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseList {
+    pub active: String,
+    pub bundled: String,
+    pub open: Vec<String>,
+    pub recent: Vec<RecentProject>,
+}
+
+/* Read-only pool. The bundled DB never changes, so it is opened immutable;
+   project DBs may be re-indexed or re-imported, so SQLite keeps checking them. */
+pub fn open_pool(path: &Path, immutable: bool) -> Result<SqlitePool, String> {
+    open_pool_with(path, immutable, None)
+}
+
+/* Same, running `setup` (e.g. ATTACH + TEMP views) on every connection before it goes read-only */
+pub fn open_pool_with(path: &Path, immutable: bool, setup: Option<String>) -> Result<SqlitePool, String> {
+    let mut url = Url::from_file_path(path).map_err(|_| format!("bad DB path: {}", path.display()))?;
+    url.set_query(Some(if immutable { "mode=ro&immutable=1" } else { "mode=ro" }));
+
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI;
+    let manager = SqliteConnectionManager::file(url.as_str())
+        .with_flags(flags)
+        .with_init(move |c| {
+            if let Some(sql) = &setup { c.execute_batch(sql)?; }
+            c.execute_batch("PRAGMA query_only=ON;")?;
+            Ok(())
+        });
+
+    r2d2::Pool::builder()
+        .max_size(4)
+        .connection_timeout(Duration::from_secs(10))
+        .build(manager)
+        .map_err(|e| e.to_string())
+}
+
+/* Stable per-DB key for AppData sub-paths */
+pub fn db_key(path: &Path) -> String {
+    let hex = format!("{:x}", Sha256::digest(path.to_string_lossy().as_bytes()));
+    hex[..16].to_string()
+}
+
+pub fn is_bundled(app: &AppHandle, path: &Path) -> bool {
+    app.try_state::<Projects>().is_some_and(|p| p.bundled == path)
+}
+
+/* Per-DB file under AppData; the bundled DB keeps the legacy top-level location */
+pub fn data_file(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let active = app.state::<DbPath>().0.read().unwrap().clone();
+    let rel = if is_bundled(app, &active) { name.to_string() } else { format!("projects/{}/{name}", db_key(&active)) };
+    app.path().resolve(rel, BaseDirectory::AppData).map_err(|e| e.to_string())
+}
+
+fn recent_file(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .resolve("recent_projects.json", BaseDirectory::AppData)
+        .map_err(|e| e.to_string())
+}
+
+fn load_recent(app: &AppHandle) -> Vec<RecentProject> {
+    let Ok(path) = recent_file(app) else { return Vec::new(); };
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Vec<RecentProject>>(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn remember_recent(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let key = path.to_string_lossy().to_string();
+    let mut recent = load_recent(app);
+    recent.retain(|r| r.path != key);
+    recent.insert(0, RecentProject {
+        path: key,
+        name: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+        last_opened_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+        exists: true,
+    });
+    recent.truncate(MAX_RECENT);
+    let file = recent_file(app)?;
+    if let Some(parent) = file.parent() { let _ = fs::create_dir_all(parent); }
+    let json = serde_json::to_vec_pretty(&recent).map_err(|e| e.to_string())?;
+    fs::write(file, json).map_err(|e| e.to_string())
+}
+
+fn list(app: &AppHandle, projects: &Projects) -> DatabaseList {
+    let active = app.state::<DbPath>().0.read().unwrap().clone();
+    let open = projects.open.lock().unwrap().keys().map(|p| p.to_string_lossy().to_string()).collect();
+    let recent = load_recent(app)
+        .into_iter()
+        .map(|r| RecentProject { exists: Path::new(&r.path).exists(), ..r })
+        .collect();
+    DatabaseList {
+        active: active.to_string_lossy().to_string(),
+        bundled: projects.bundled.to_string_lossy().to_string(),
+        open,
+        recent,
+    }
+}
+
+/* Pool for `path`, opening (and sanity-checking) it if needed */
+fn ensure_open(projects: &Projects, path: &Path) -> Result<Arc<SqlitePool>, String> {
+    if let Some(pool) = projects.open.lock().unwrap().get(path) {
+        return Ok(pool.clone());
+    }
+    if !path.is_file() {
+        return Err(format!("database not found: {}", path.display()));
+    }
+    let pool = open_pool(path, false)?;
+    {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        if !table_exists(&conn, "gaze_data") {
+            return Err(format!("not an eye-tracking database (no gaze_data table): {}", path.display()));
+        }
+    }
+    let pool = Arc::new(pool);
+    projects.open.lock().unwrap().insert(path.to_path_buf(), pool.clone());
+    Ok(pool)
+}
+
+/* Make `path` the active DB (pool, disabled slices, aggregate cache) and tell the frontend.
+The pool is swapped last, so a command that gets a connection to the new DB never sees
+the previous DB's path, disabled slices or cohorts. */
+fn activate(app: &AppHandle, path: &Path, pool: Arc<SqlitePool>) {
+    // cohorts were attached to the previous DB
+    *app.state::<CohortState>().0.lock().unwrap() = None;
+    *app.state::<DbPath>().0.write().unwrap() = path.to_path_buf();
+    let disabled = load_disabled_from_disk(app);
+    *app.state::<DisabledStore>().0.write().unwrap() = disabled;
+    app.state::<DbPool>().0.swap(pool);
+    cache::refresh_in_background(app.clone());
+    let handle = app.clone();
+    spawn_blocking(move || {
+        if let Err(e) = validate::check_active(&handle) { println!("DB health check failed: {e}"); }
+    });
+    let _ = app.emit("database-changed", path.to_string_lossy().to_string());
+}
+
+fn resolve_path(path: &str) -> Result<PathBuf, String> {
+    let p = PathBuf::from(path.trim());
+    if p.as_os_str().is_empty() { return Err("missing param: path".to_string()); }
+    Ok(fs::canonicalize(&p).unwrap_or(p))
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn list_databases(app: AppHandle, projects: State<'_, Projects>) -> Result<DatabaseList, String> {
+    Ok(list(&app, &projects))
+}
+
+/* Open a project DB (added to recents); `activate` defaults to true */
+#[tauri::command]
+pub async fn open_database(
+    app: AppHandle,
+    path: String,
+    activate: Option<bool>,
+    projects: State<'_, Projects>,
+) -> Result<DatabaseList, String> {
+    let path = resolve_path(&path)?;
+    let pool = ensure_open(&projects, &path)?;
+    if !is_bundled(&app, &path) { remember_recent(&app, &path)?; }
+    if activate.unwrap_or(true) { self::activate(&app, &path, pool); }
+    Ok(list(&app, &projects))
+}
+
+/* Switch the active DB (opening it first when needed) */
+#[tauri::command]
+pub async fn switch_database(app: AppHandle, path: String, projects: State<'_, Projects>) -> Result<DatabaseList, String> {
+    let path = resolve_path(&path)?;
+    let pool = ensure_open(&projects, &path)?;
+    if !is_bundled(&app, &path) { remember_recent(&app, &path)?; }
+    activate(&app, &path, pool);
+    Ok(list(&app, &projects))
+}
+
+/* Close a project DB (default: the active one); closing the active DB falls back to the bundled one */
+#[tauri::command]
+pub async fn close_database(app: AppHandle, path: Option<String>, projects: State<'_, Projects>) -> Result<DatabaseList, String> {
+    let active = app.state::<DbPath>().0.read().unwrap().clone();
+    let path = match path {
+        Some(p) => resolve_path(&p)?,
+        None => active.clone(),
+    };
+    if path == projects.bundled {
+        return Err("the bundled database cannot be closed".to_string());
+    }
+    projects.open.lock().unwrap().remove(&path);
+    if path == active {
+        let bundled = projects.bundled.clone();
+        let pool = ensure_open(&projects, &bundled)?;
+        activate(&app, &bundled, pool);
+    }
+    Ok(list(&app, &projects))
+}
@@ -0,0 +1,734 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::ConditionDef;
+use crate::items::ProportionSummary;
+use crate::manifest::{self, WithManifest};
+use crate::{cohort, project, timestamp, DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Data quality beyond the recordings table's "Gaze samples" %: Tobii
+calibration / validation results (accuracy and precision per eye, in
+degrees or pixels) imported from a CSV/TSV export and stored per DB in
+calibration.json, and RMS sample-to-sample precision computed from the
+raw gaze inside I-VT fixations (velocity below a threshold for at least
+min_fixation_ms). Both feed the exclusion rules of saved analyses and
+the report's data-quality section.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Calibration,
+    Validation,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EyeValues {
+    pub left: Option<f64>,
+    pub right: Option<f64>,
+    pub average: Option<f64>,
+}
+
+impl EyeValues {
+    /* the reported average (else the mean of both eyes), or the worse eye */
+    pub fn pick(&self, worse_eye: bool) -> Option<f64> {
+        let eyes: Vec<f64> = [self.left, self.right].into_iter().flatten().collect();
+        if worse_eye && !eyes.is_empty() { return eyes.into_iter().reduce(f64::max); }
+        self.average.or_else(|| (!eyes.is_empty()).then(|| eyes.iter().sum::<f64>() / eyes.len() as f64))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityValues {
+    pub accuracy: EyeValues,
+    /* RMS precision as Tobii reports it */
+    pub precision: EyeValues,
+    pub precision_sd: EyeValues,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationRecord {
+    pub recording: String,
+    /* None when the export has no participant column */
+    pub participant: Option<String>,
+    pub stage: Stage,
+    pub deg: QualityValues,
+    pub px: QualityValues,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalibrationImport {
+    pub path: String,
+    pub rows: usize,
+    pub records: usize,
+    /* rows without a recording name or any value */
+    pub skipped_rows: usize,
+    pub columns_used: Vec<String>,
+    /* neither a key nor an accuracy / precision column in deg / px */
+    pub ignored_columns: Vec<String>,
+    /* imported recordings that do not occur in gaze_data */
+    pub unmatched_recordings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrecisionOptions {
+    #[serde(default = "default_velocity_threshold")]
+    pub velocity_threshold_deg_s: f64,
+    /* screen pixels per degree of visual angle at the viewing distance */
+    #[serde(default = "default_px_per_deg")]
+    pub px_per_deg: f64,
+    #[serde(default = "default_min_fixation_ms")]
+    pub min_fixation_ms: f64,
+    /* longer steps between samples end a fixation */
+    #[serde(default = "default_max_gap_ms")]
+    pub max_gap_ms: f64,
+}
+
+fn default_velocity_threshold() -> f64 { 30.0 }
+fn default_px_per_deg() -> f64 { 40.0 }
+fn default_min_fixation_ms() -> f64 { 60.0 }
+fn default_max_gap_ms() -> f64 { 75.0 }
+
+impl Default for PrecisionOptions {
+    fn default() -> Self {
+        PrecisionOptions {
+            velocity_threshold_deg_s: default_velocity_threshold(),
+            px_per_deg: default_px_per_deg(),
+            min_fixation_ms: default_min_fixation_ms(),
+            max_gap_ms: default_max_gap_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlicePrecision {
+    /* attached cohort the slice came from (None without cohorts) */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub recording: String,
+    pub samples: usize,
+    pub fixations: usize,
+    /* sample pairs inside fixations */
+    pub pairs: usize,
+    pub rms_s2s_px: Option<f64>,
+    pub rms_s2s_deg: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantQuality {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub participant: String,
+    pub slices: usize,
+    pub recordings: usize,
+    /* spread over the participant's slices */
+    pub rms_s2s_px: Option<ProportionSummary>,
+    pub rms_s2s_deg: Option<ProportionSummary>,
+    /* recordings with a validation (else calibration) record, and their mean values */
+    pub calibrated_recordings: usize,
+    pub accuracy_deg: Option<f64>,
+    pub precision_deg: Option<f64>,
+    pub accuracy_px: Option<f64>,
+    pub precision_px: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrecisionResult {
+    pub options: PrecisionOptions,
+    pub slices: Vec<SlicePrecision>,
+    pub participants: Vec<ParticipantQuality>,
+}
+
+/* Thresholds a saved analysis excludes slices by (all optional; nothing set = no rule) */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QualityRules {
+    /* imported validation (else calibration) results */
+    #[serde(default)]
+    pub max_accuracy_deg: Option<f64>,
+    #[serde(default)]
+    pub max_precision_deg: Option<f64>,
+    #[serde(default)]
+    pub max_accuracy_px: Option<f64>,
+    #[serde(default)]
+    pub max_precision_px: Option<f64>,
+    /* judge by the worse eye instead of the average */
+    #[serde(default)]
+    pub worse_eye: bool,
+    /* recordings without any record fail the calibration thresholds */
+    #[serde(default)]
+    pub exclude_uncalibrated: bool,
+    /* computed RMS sample-to-sample precision; slices without fixations are kept */
+    #[serde(default)]
+    pub max_rms_s2s_px: Option<f64>,
+    #[serde(default)]
+    pub max_rms_s2s_deg: Option<f64>,
+    #[serde(default)]
+    pub precision: PrecisionOptions,
+}
+
+impl QualityRules {
+    fn uses_calibration(&self) -> bool {
+        self.max_accuracy_deg.is_some() || self.max_precision_deg.is_some() || self.max_accuracy_px.is_some() || self.max_precision_px.is_some()
+    }
+
+    fn uses_precision(&self) -> bool {
+        self.max_rms_s2s_px.is_some() || self.max_rms_s2s_deg.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityExclusion {
+    pub slice: DisabledSlice,
+    pub reasons: Vec<String>,
+}
+
+/* ─────────────────────────── Calibration store ─────────────────────────── */
+
+fn calibration_file(app: &AppHandle) -> Result<PathBuf, String> {
+    project::data_file(app, "calibration.json")
+}
+
+pub fn load_calibration(app: &AppHandle) -> Result<Vec<CalibrationRecord>, String> {
+    match fs::read(calibration_file(app)?) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string()),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn save_calibration(app: &AppHandle, records: &[CalibrationRecord]) -> Result<(), String> {
+    let path = calibration_file(app)?;
+    if let Some(parent) = path.parent() { let _ = fs::create_dir_all(parent); }
+    let json = serde_json::to_vec_pretty(records).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/* Validation record for a slice's recording (else calibration), participant-specific first */
+fn record_for<'a>(records: &'a [CalibrationRecord], recording: &str, participant: &str) -> Option<&'a CalibrationRecord> {
+    let matches = |r: &&CalibrationRecord| r.recording == recording.trim() && r.participant.as_deref().is_none_or(|p| p == participant.trim());
+    records
+        .iter()
+        .filter(matches)
+        .max_by_key(|r| (r.stage, r.participant.is_some()))
+}
+
+/* ─────────────────────────── Import ─────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    Accuracy,
+    Precision,
+    PrecisionSd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Eye {
+    Left,
+    Right,
+    Average,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MeasureColumn {
+    index: usize,
+    stage: Option<Stage>,
+    metric: Metric,
+    eye: Eye,
+    deg: bool,
+}
+
+/* Split one delimited line, honouring double quotes */
+fn split_line(line: &str, delim: char) -> Vec<String> {
+    let mut out = Vec::new();
+    let (mut cur, mut quoted) = (String::new(), false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cur.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delim && !quoted => out.push(std::mem::take(&mut cur).trim().to_string()),
+            c => cur.push(c),
+        }
+    }
+    out.push(cur.trim().to_string());
+    out
+}
+
+fn measure_column(index: usize, header: &str) -> Option<MeasureColumn> {
+    let h = header.to_lowercase();
+    let metric = if h.contains("accuracy") {
+        Metric::Accuracy
+    } else if h.contains("precision") {
+        // "SD" / "std" as whole words only ("(sd)", " sd", "precision_std"), not inside other words
+        let words: Vec<&str> = h.split(|c: char| !c.is_alphanumeric()).collect();
+        if words.iter().any(|w| matches!(*w, "sd" | "std")) { Metric::PrecisionSd } else { Metric::Precision }
+    } else {
+        return None;
+    };
+    let deg = if h.contains("deg") || h.contains('°') {
+        true
+    } else if h.contains("px") || h.contains("pixel") {
+        false
+    } else {
+        return None;
+    };
+    let eye = if h.contains("left") { Eye::Left } else if h.contains("right") { Eye::Right } else { Eye::Average };
+    let stage = if h.contains("validation") {
+        Some(Stage::Validation)
+    } else if h.contains("calibration") {
+        Some(Stage::Calibration)
+    } else {
+        None
+    };
+    Some(MeasureColumn { index, stage, metric, eye, deg })
+}
+
+fn number(s: &str) -> Option<f64> {
+    let s = s.trim().trim_end_matches(['°', '%']).trim_end_matches("px").trim();
+    let s = if s.contains('.') { s.to_string() } else { s.replace(',', ".") };
+    s.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/* Records from a Tobii calibration / validation export (header row + one row per recording or stage) */
+fn parse_calibration(text: &str) -> (Vec<CalibrationRecord>, CalibrationImport) {
+    let mut lines = text.trim_start_matches('\u{feff}').lines().filter(|l| !l.trim().is_empty());
+    let header = lines.next().unwrap_or_default();
+    let delim = if header.contains('\t') { '\t' } else if header.contains(';') && !header.contains(',') { ';' } else { ',' };
+    let head = split_line(header, delim);
+    let find = |pred: &dyn Fn(&str) -> bool| head.iter().position(|h| pred(&h.to_lowercase()));
+    let recording_col = find(&|h| h == "recording name" || h == "recording")
+        .or_else(|| find(&|h| h.contains("recording") && !h.contains("date") && !h.contains("duration")));
+    let participant_col = find(&|h| h.contains("participant"));
+    let stage_col = find(&|h| matches!(h, "stage" | "type" | "kind" | "calibration type"));
+    let measures: Vec<MeasureColumn> = head.iter().enumerate().filter_map(|(i, h)| measure_column(i, h)).collect();
+
+    let used: BTreeSet<usize> = [recording_col, participant_col, stage_col].into_iter().flatten().chain(measures.iter().map(|m| m.index)).collect();
+    let mut summary = CalibrationImport {
+        path: String::new(),
+        rows: 0,
+        records: 0,
+        skipped_rows: 0,
+        columns_used: used.iter().map(|i| head[*i].clone()).collect(),
+        ignored_columns: head.iter().enumerate().filter(|(i, _)| !used.contains(i)).map(|(_, h)| h.clone()).collect(),
+        unmatched_recordings: Vec::new(),
+    };
+
+    // (recording, participant, stage) → record; later rows overwrite earlier values (last attempt wins)
+    let mut merged: BTreeMap<(String, Option<String>, Stage), CalibrationRecord> = BTreeMap::new();
+    for line in lines {
+        summary.rows += 1;
+        let cells = split_line(line, delim);
+        let cell = |i: Option<usize>| i.and_then(|i| cells.get(i)).map(|c| c.trim()).filter(|c| !c.is_empty());
+        let Some(recording) = cell(recording_col) else {
+            summary.skipped_rows += 1;
+            continue;
+        };
+        let participant = cell(participant_col).map(str::to_string);
+        let row_stage = cell(stage_col).map(|s| if s.to_lowercase().contains("cal") { Stage::Calibration } else { Stage::Validation });
+        let mut any = false;
+        for m in &measures {
+            let Some(v) = cell(Some(m.index)).and_then(number) else { continue; };
+            let stage = m.stage.or(row_stage).unwrap_or(Stage::Validation);
+            let rec = merged.entry((recording.to_string(), participant.clone(), stage)).or_insert_with(|| CalibrationRecord {
+                recording: recording.to_string(),
+                participant: participant.clone(),
+                stage,
+                deg: QualityValues::default(),
+                px: QualityValues::default(),
+            });
+            let values = if m.deg { &mut rec.deg } else { &mut rec.px };
+            let eyes = match m.metric {
+                Metric::Accuracy => &mut values.accuracy,
+                Metric::Precision => &mut values.precision,
+                Metric::PrecisionSd => &mut values.precision_sd,
+            };
+            let slot = match m.eye {
+                Eye::Left => &mut eyes.left,
+                Eye::Right => &mut eyes.right,
+                Eye::Average => &mut eyes.average,
+            };
+            *slot = Some(v);
+            any = true;
+        }
+        if !any { summary.skipped_rows += 1; }
+    }
+    let records: Vec<CalibrationRecord> = merged.into_values().collect();
+    summary.records = records.len();
+    (records, summary)
+}
+
+/* ─────────────────────────── Precision ─────────────────────────── */
+
+/* (epoch µs, gaze point) in time order */
+type Samples = Vec<(i64, Option<(f64, f64)>)>;
+
+struct SliceGaze {
+    cohort: Option<String>,
+    slice: DisabledSlice,
+    samples: Samples,
+}
+
+/* Gaze coordinates per (cohort, test, participant, recording), handed to `f` one slice at a time so
+   only one slice is held in memory; empty `tests` / `participants` = all */
+fn for_each_slice(
+    conn: &rusqlite::Connection,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    disabled: &HashSet<DisabledSlice>,
+    mut f: impl FnMut(SliceGaze),
+) -> Result<(), String> {
+    let cohort_col = cohort::column_sql(conn);
+    let mut query = format!(
+        r#"SELECT "Test Name", "Participant name", "Recording name", CAST("Exact time" AS TEXT), "Gaze point X", "Gaze point Y", {cohort_col}
+           FROM gaze_data WHERE "Test Name" IS NOT NULL"#,
+    );
+    if !tests.is_empty() {
+        query.push_str(&format!(" AND \"Test Name\" IN ({})", vec!["?"; tests.len()].join(",")));
+    }
+    if !participants.is_empty() {
+        query.push_str(&format!(" AND \"Participant name\" IN ({})", vec!["?"; participants.len()].join(",")));
+    }
+    query.push_str(cohort::filter_sql(conn, cohort)?);
+    // only the disabled slices the selection can reach; blank names match NULL as DisabledSlice does
+    let excluded: Vec<&DisabledSlice> = disabled
+        .iter()
+        .filter(|ds| tests.is_empty() || tests.contains(&ds.test_name))
+        .filter(|ds| participants.is_empty() || participants.contains(&ds.participant_name))
+        .collect();
+    if !excluded.is_empty() {
+        query.push_str(" AND NOT (");
+        for (i, _) in excluded.iter().enumerate() {
+            if i > 0 { query.push_str(" OR "); }
+            query.push_str(r#"("Test Name" = ? AND IFNULL("Recording name", '') = ? AND IFNULL("Participant name", '') = ?)"#);
+        }
+        query.push(')');
+    }
+    query.push_str(&format!(r#" ORDER BY {cohort_col}, "Test Name", IFNULL("Participant name", ''), IFNULL("Recording name", '')"#));
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let mut params: Vec<&dyn rusqlite::ToSql> = tests.iter().chain(participants).chain(cohort).map(|s| s as &dyn rusqlite::ToSql).collect();
+    for ds in &excluded {
+        params.push(&ds.test_name);
+        params.push(&ds.recording_name);
+        params.push(&ds.participant_name);
+    }
+    let mut rows = stmt.query(rusqlite::params_from_iter(params)).map_err(|e| e.to_string())?;
+
+    let mut flush = |cur: Option<SliceGaze>| {
+        if let Some(mut g) = cur {
+            g.samples.sort_by_key(|(t, _)| *t);
+            f(g);
+        }
+    };
+    let mut cur: Option<SliceGaze> = None;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let raw: Option<String> = row.get(3).map_err(|e| e.to_string())?;
+        let Some(t) = raw.as_deref().and_then(timestamp::parse) else { continue; };
+        let from: Option<String> = row.get(6).map_err(|e| e.to_string())?;
+        let slice = DisabledSlice {
+            test_name: row.get(0).map_err(|e| e.to_string())?,
+            participant_name: row.get::<_, Option<String>>(1).map_err(|e| e.to_string())?.unwrap_or_default(),
+            recording_name: row.get::<_, Option<String>>(2).map_err(|e| e.to_string())?.unwrap_or_default(),
+        };
+        let x: Option<f64> = row.get(4).map_err(|e| e.to_string())?;
+        let y: Option<f64> = row.get(5).map_err(|e| e.to_string())?;
+        // rows arrive grouped by slice
+        if !cur.as_ref().is_some_and(|g| g.cohort == from && g.slice == slice) {
+            flush(cur.take());
+            cur = Some(SliceGaze { cohort: from, slice, samples: Vec::new() });
+        }
+        if let Some(g) = cur.as_mut() { g.samples.push((t, x.zip(y))); }
+    }
+    flush(cur);
+    Ok(())
+}
+
+/* RMS of sample-to-sample distances inside I-VT fixations */
+fn slice_precision(g: &SliceGaze, o: &PrecisionOptions) -> SlicePrecision {
+    let threshold_px_s = o.velocity_threshold_deg_s * o.px_per_deg;
+    let max_gap = timestamp::from_ms(o.max_gap_ms);
+    let min_fix = timestamp::from_ms(o.min_fixation_ms);
+    let (mut fixations, mut pairs, mut sum_sq) = (0usize, 0usize, 0.0f64);
+    // current run of slow steps: start µs, end µs, squared step lengths
+    let mut run: Option<(i64, i64, Vec<f64>)> = None;
+    let mut close = |start: i64, end: i64, steps: Vec<f64>| {
+        if end - start < min_fix { return; }
+        fixations += 1;
+        pairs += steps.len();
+        sum_sq += steps.iter().sum::<f64>();
+    };
+    for w in g.samples.windows(2) {
+        let ((t0, p0), (t1, p1)) = (w[0], w[1]);
+        let dt = t1 - t0;
+        let step = match (p0, p1) {
+            (Some((x0, y0)), Some((x1, y1))) if dt > 0 && dt <= max_gap => Some((x1 - x0).powi(2) + (y1 - y0).powi(2)),
+            _ => None,
+        };
+        match step {
+            Some(d2) if d2.sqrt() / (dt as f64 / 1_000_000.0) < threshold_px_s => match run.as_mut() {
+                Some((_, end, steps)) => {
+                    *end = t1;
+                    steps.push(d2);
+                }
+                None => run = Some((t0, t1, vec![d2])),
+            },
+            _ => {
+                if let Some((start, end, steps)) = run.take() { close(start, end, steps); }
+            }
+        }
+    }
+    if let Some((start, end, steps)) = run.take() { close(start, end, steps); }
+    let rms_px = (pairs > 0).then(|| (sum_sq / pairs as f64).sqrt());
+    SlicePrecision {
+        cohort: g.cohort.clone(),
+        test_name: g.slice.test_name.clone(),
+        participant: g.slice.participant_name.clone(),
+        recording: g.slice.recording_name.clone(),
+        samples: g.samples.len(),
+        fixations,
+        pairs,
+        rms_s2s_px: rms_px,
+        rms_s2s_deg: rms_px.map(|v| v / o.px_per_deg.max(1e-9)),
+    }
+}
+
+pub fn compute_precision(
+    conn: &rusqlite::Connection,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    disabled: &HashSet<DisabledSlice>,
+    options: &PrecisionOptions,
+) -> Result<Vec<SlicePrecision>, String> {
+    let mut out = Vec::new();
+    for_each_slice(conn, tests, participants, cohort, disabled, |g| out.push(slice_precision(&g, options)))?;
+    Ok(out)
+}
+
+/* Per-participant (per cohort) precision plus mean imported accuracy / precision over their recordings */
+pub fn participant_quality(slices: &[SlicePrecision], records: &[CalibrationRecord]) -> Vec<ParticipantQuality> {
+    let mut by_participant: BTreeMap<(Option<&str>, &str), Vec<&SlicePrecision>> = BTreeMap::new();
+    for s in slices { by_participant.entry((s.cohort.as_deref(), s.participant.as_str())).or_default().push(s); }
+    by_participant
+        .into_iter()
+        .map(|((cohort, participant), slices)| {
+            let recordings: BTreeSet<&str> = slices.iter().map(|s| s.recording.as_str()).collect();
+            let recs: Vec<&CalibrationRecord> = recordings.iter().filter_map(|r| record_for(records, r, participant)).collect();
+            let mean = |f: &dyn Fn(&CalibrationRecord) -> Option<f64>| {
+                let v: Vec<f64> = recs.iter().filter_map(|r| f(r)).collect();
+                (!v.is_empty()).then(|| v.iter().sum::<f64>() / v.len() as f64)
+            };
+            let px: Vec<f64> = slices.iter().filter_map(|s| s.rms_s2s_px).collect();
+            let deg: Vec<f64> = slices.iter().filter_map(|s| s.rms_s2s_deg).collect();
+            ParticipantQuality {
+                cohort: cohort.map(str::to_string),
+                participant: participant.to_string(),
+                slices: slices.len(),
+                recordings: recordings.len(),
+                rms_s2s_px: ProportionSummary::of(&px),
+                rms_s2s_deg: ProportionSummary::of(&deg),
+                calibrated_recordings: recs.len(),
+                accuracy_deg: mean(&|r| r.deg.accuracy.pick(false)),
+                precision_deg: mean(&|r| r.deg.precision.pick(false)),
+                accuracy_px: mean(&|r| r.px.accuracy.pick(false)),
+                precision_px: mean(&|r| r.px.precision.pick(false)),
+            }
+        })
+        .collect()
+}
+
+/* Slice and participant precision for `tests` (empty = all), optionally from one cohort, with `excluded` slices left out */
+pub fn precision(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    excluded: &HashSet<DisabledSlice>,
+    options: &PrecisionOptions,
+) -> Result<PrecisionResult, String> {
+    let slices = compute_precision(conn, tests, participants, cohort, excluded, options)?;
+    let participants = participant_quality(&slices, &load_calibration(app)?);
+    Ok(PrecisionResult { options: options.clone(), slices, participants })
+}
+
+/* ─────────────────────────── Exclusion rules ─────────────────────────── */
+
+/* Slices of `tests` (empty = all) failing `rules`, with the reasons */
+pub fn rule_exclusions(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    rules: &QualityRules,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+) -> Result<Vec<QualityExclusion>, String> {
+    if !rules.uses_calibration() && !rules.uses_precision() { return Ok(Vec::new()); }
+    let records = if rules.uses_calibration() { load_calibration(app)? } else { Vec::new() };
+    let precision = compute_precision(conn, tests, participants, cohort, &HashSet::new(), &rules.precision)?;
+
+    let over = |label: &str, v: Option<f64>, max: Option<f64>, unit: &str| -> Option<String> {
+        let (v, max) = (v?, max?);
+        (v > max).then(|| format!("{label} {v:.2} {unit} > {max}"))
+    };
+    let mut out = Vec::new();
+    for s in &precision {
+        let mut reasons = Vec::new();
+        if rules.uses_calibration() {
+            match record_for(&records, &s.recording, &s.participant) {
+                Some(r) => {
+                    let stage = if r.stage == Stage::Validation { "validation" } else { "calibration" };
+                    reasons.extend(over(&format!("{stage} accuracy"), r.deg.accuracy.pick(rules.worse_eye), rules.max_accuracy_deg, "°"));
+                    reasons.extend(over(&format!("{stage} precision"), r.deg.precision.pick(rules.worse_eye), rules.max_precision_deg, "°"));
+                    reasons.extend(over(&format!("{stage} accuracy"), r.px.accuracy.pick(rules.worse_eye), rules.max_accuracy_px, "px"));
+                    reasons.extend(over(&format!("{stage} precision"), r.px.precision.pick(rules.worse_eye), rules.max_precision_px, "px"));
+                }
+                None if rules.exclude_uncalibrated => reasons.push("no calibration record".to_string()),
+                None => {}
+            }
+        }
+        reasons.extend(over("RMS-S2S", s.rms_s2s_px, rules.max_rms_s2s_px, "px"));
+        reasons.extend(over("RMS-S2S", s.rms_s2s_deg, rules.max_rms_s2s_deg, "°"));
+        if reasons.is_empty() { continue; }
+        out.push(QualityExclusion {
+            slice: DisabledSlice {
+                test_name: s.test_name.clone(),
+                recording_name: s.recording.clone(),
+                participant_name: s.participant.clone(),
+            },
+            reasons,
+        });
+    }
+    Ok(out)
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Import a calibration / validation export (CSV or TSV); `replace` (default) drops earlier imports,
+   otherwise records with the same recording / participant / stage are overwritten */
+#[tauri::command]
+pub async fn import_calibration(app: AppHandle, path: String, replace: Option<bool>) -> Result<CalibrationImport, String> {
+    let text = fs::read_to_string(path.trim()).map_err(|e| format!("{}: {e}", path.trim()))?;
+    let (records, mut summary) = parse_calibration(&text);
+    summary.path = path.trim().to_string();
+    if records.is_empty() {
+        return Err("no accuracy / precision values (in deg or px) with a recording name found".to_string());
+    }
+
+    let known: HashSet<String> = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(r#"SELECT DISTINCT TRIM("Recording name") FROM gaze_data"#).map_err(|e| e.to_string())?;
+        let names = stmt.query_map([], |r| r.get::<_, Option<String>>(0)).map_err(|e| e.to_string())?;
+        names.filter_map(Result::ok).flatten().collect()
+    };
+    let unmatched: BTreeSet<&str> = records.iter().map(|r| r.recording.as_str()).filter(|r| !known.contains(*r)).collect();
+    summary.unmatched_recordings = unmatched.into_iter().map(str::to_string).collect();
+
+    let mut all = if replace.unwrap_or(true) { Vec::new() } else { load_calibration(&app)? };
+    all.retain(|old| !records.iter().any(|r| (&r.recording, &r.participant, r.stage) == (&old.recording, &old.participant, old.stage)));
+    all.extend(records);
+    save_calibration(&app, &all)?;
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn get_calibration(app: AppHandle) -> Result<Vec<CalibrationRecord>, String> {
+    load_calibration(&app)
+}
+
+/* RMS sample-to-sample precision per slice and per participant (empty `tests` = every test) */
+#[tauri::command]
+pub async fn get_precision(
+    app: AppHandle,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    options: Option<PrecisionOptions>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<PrecisionResult>, String> {
+    let options = options.unwrap_or_default();
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let result = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        precision(&app, &conn, &tests, &participants, &cohort, &disabled, &options)?
+    };
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        precision: options,
+        ..AnalysisSpec::new(AnalysisKind::Precision, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    manifest::attach(&app, manifest, "get_precision", spec, &disabled, None, result)
+}
+
+/* Slices a set of rules would exclude (preview for the analysis editor) */
+#[tauri::command]
+pub async fn preview_quality_exclusions(
+    app: AppHandle,
+    rules: QualityRules,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+) -> Result<Vec<QualityExclusion>, String> {
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    rule_exclusions(&app, &conn, &rules, &tests, &participants, &cohort)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sd_columns_need_a_whole_word() {
+        let metric = |h: &str| measure_column(0, h).map(|m| m.metric);
+        assert_eq!(metric("Precision SD (deg)"), Some(Metric::PrecisionSd));
+        assert_eq!(metric("Validation precision (sd) px"), Some(Metric::PrecisionSd));
+        assert_eq!(metric("precision_std_deg"), Some(Metric::PrecisionSd));
+        // "sd" inside another word is not a standard deviation
+        assert_eq!(metric("Precision RMS-S2S (deg)"), Some(Metric::Precision));
+        assert_eq!(metric("Precision Tsdeg px"), Some(Metric::Precision));
+        assert_eq!(metric("Sample rate (deg)"), None);
+    }
+
+    #[test]
+    fn slices_stream_in_order_without_disabled_ones() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE gaze_data ("Test Name" TEXT, "Participant name" TEXT, "Recording name" TEXT, "Exact time" TEXT, "Gaze point X" REAL, "Gaze point Y" REAL);
+               INSERT INTO gaze_data VALUES
+                 ('T2', 'P1', 'R1', '2024-01-01 10:00:00.020', 1, 1),
+                 ('T1', 'P2', 'R1', '2024-01-01 10:00:00.000', 1, 1),
+                 ('T1', 'P1', 'R1', '2024-01-01 10:00:00.020', 2, NULL),
+                 ('T1', 'P1', 'R1', '2024-01-01 10:00:00.000', 1, 1),
+                 ('T1', NULL, 'R1', '2024-01-01 10:00:00.000', 1, 1),
+                 ('T1', '', 'R1', '2024-01-01 10:00:00.010', 1, 1),
+                 ('T1', 'P1', 'R1', 'not a time', 1, 1);"#,
+        )
+        .unwrap();
+        let slice = |t: &str, p: &str| DisabledSlice { test_name: t.to_string(), participant_name: p.to_string(), recording_name: "R1".to_string() };
+        let disabled: HashSet<DisabledSlice> = [slice("T1", "P2")].into();
+        let mut seen = Vec::new();
+        for_each_slice(&conn, &[], &[], &None, &disabled, |g| seen.push((g.slice, g.samples))).unwrap();
+        let keys: Vec<&DisabledSlice> = seen.iter().map(|(s, _)| s).collect();
+        assert_eq!(keys, [&slice("T1", ""), &slice("T1", "P1"), &slice("T2", "P1")]);
+        // NULL and blank participant are one slice; samples in time order, unparsable times skipped
+        assert_eq!(seen[0].1.len(), 2);
+        let times: Vec<i64> = seen[1].1.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times.len(), 2);
+        assert!(times[0] < times[1] && seen[1].1[1].1.is_none());
+
+        let mut only = Vec::new();
+        for_each_slice(&conn, &["T2".to_string()], &[], &None, &HashSet::new(), |g| only.push(g.slice)).unwrap();
+        assert_eq!(only, [slice("T2", "P1")]);
+    }
+}
@@ -0,0 +1,759 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::spawn_blocking;
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::all_tests;
+use crate::analysis::saved::{self, Analysis, AnalysisKind, AnalysisOutput, AnalysisSpec};
+use crate::binning::{participant_curves, recording_curves, RecordingCurve};
+use crate::manifest::{self, Manifest};
+use crate::project::DbPath;
+use crate::quality::{self, ParticipantQuality, QualityExclusion, QualityRules};
+use crate::{box_counts_sql, DbPool, DisabledSlice, DisabledStore};
+
+/* ──────────────────────────────────────────────────────────────
+Stand-alone reports for a saved analysis: one self-contained HTML file
+(inline CSS + SVG, no scripts) with box-share tables, binned curves,
+the analysis result, exclusions, a methods paragraph filled in from the
+spec and the reproducibility manifest. PDF output prints that HTML with
+a headless Chromium/Edge when one can be found.
+────────────────────────────────────────────────────────────── */
+
+const PALETTE: [&str; 6] = ["#2563eb", "#dc2626", "#16a34a", "#9333ea", "#ea580c", "#0891b2"];
+/* boxes shown as their own column in the summary table (the rest are summed into "other") */
+const MAX_BOX_COLUMNS: usize = 8;
+/* a headless browser still printing after this long is killed */
+const PDF_TIMEOUT: Duration = Duration::from_secs(60);
+
+const CSS: &str = "
+body { font-family: -apple-system, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; color: #111; max-width: 900px; margin: 2em auto; padding: 0 1em; line-height: 1.45; }
+h1 { font-size: 1.6em; margin-bottom: 0.1em; }
+h2 { font-size: 1.2em; border-bottom: 1px solid #ddd; padding-bottom: 0.2em; margin-top: 1.8em; }
+h3 { font-size: 1em; margin-top: 1.2em; }
+.meta { color: #555; font-size: 0.9em; }
+table { border-collapse: collapse; font-size: 0.85em; margin: 0.6em 0; }
+th, td { border: 1px solid #ddd; padding: 3px 8px; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #f5f5f5; }
+figure { margin: 1em 0; page-break-inside: avoid; }
+figcaption { font-size: 0.85em; color: #555; }
+pre { font-size: 0.75em; background: #f7f7f7; padding: 0.8em; overflow-x: auto; white-space: pre-wrap; word-break: break-all; }
+@media print { details { display: none; } }
+";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportOutput {
+    pub html_path: String,
+    pub pdf_path: Option<String>,
+    /* why the PDF was not written (the HTML still was) */
+    pub pdf_error: Option<String>,
+    pub manifest: Manifest,
+}
+
+/* ─────────────────────────── Formatting ─────────────────────────── */
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn num(x: f64, digits: usize) -> String {
+    if x.is_finite() { format!("{x:.digits$}") } else { "—".to_string() }
+}
+
+fn opt(x: Option<f64>, digits: usize) -> String {
+    x.map(|x| num(x, digits)).unwrap_or_else(|| "—".to_string())
+}
+
+fn p_value(p: f64) -> String {
+    if !p.is_finite() { "—".to_string() } else if p < 0.001 { "&lt; .001".to_string() } else { format!("{p:.3}") }
+}
+
+/* lowercase serde name of a unit enum (scale, correction) */
+fn label<T: Serialize>(v: &T) -> String {
+    serde_json::to_value(v).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+fn list(items: &[String]) -> String {
+    match items {
+        [] => "none".to_string(),
+        [one] => esc(one),
+        [init @ .., last] => format!("{} and {}", init.iter().map(|s| esc(s)).collect::<Vec<_>>().join(", "), esc(last)),
+    }
+}
+
+/* UTC "YYYY-MM-DD HH:MM" from epoch ms (civil-from-days) */
+fn utc(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02} {:02}:{:02} UTC", rem / 3600, rem % 3600 / 60)
+}
+
+fn table(out: &mut String, head: &[&str], rows: &[Vec<String>]) {
+    out.push_str("<table><thead><tr>");
+    for h in head { let _ = write!(out, "<th>{h}</th>"); }
+    out.push_str("</tr></thead><tbody>");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row { let _ = write!(out, "<td>{cell}</td>"); }
+        out.push_str("</tr>");
+    }
+    out.push_str("</tbody></table>\n");
+}
+
+/* ─────────────────────────── SVG charts ─────────────────────────── */
+
+struct Series {
+    name: String,
+    values: Vec<Option<f64>>,
+    dashed: bool,
+    color: &'static str,
+}
+
+/* Line chart over `x`; gaps in a series break the line. `band` shades an x range (e.g. an onset CI) */
+fn line_chart(x: &[f64], series: &[Series], y_label: &str, y_range: Option<(f64, f64)>, band: Option<(f64, f64)>) -> String {
+    let (w, h) = (680.0, 300.0);
+    let (left, right, top, bottom) = (56.0, 150.0, 14.0, 40.0);
+    let (pw, ph) = (w - left - right, h - top - bottom);
+
+    let (mut x0, mut x1) = x.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+    if !x0.is_finite() { (x0, x1) = (0.0, 1.0); }
+    if x1 - x0 < 1e-9 { x0 -= 0.5; x1 += 0.5; }
+    let (y0, y1) = y_range.unwrap_or_else(|| {
+        let vals = series.iter().flat_map(|s| s.values.iter().flatten().copied()).filter(|v| v.is_finite());
+        let (lo, hi) = vals.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), v| (a.min(v), b.max(v)));
+        if !lo.is_finite() { (0.0, 1.0) } else if hi - lo < 1e-9 { (lo - 0.5, hi + 0.5) } else { let pad = (hi - lo) * 0.05; (lo - pad, hi + pad) }
+    });
+    let sx = |v: f64| left + (v - x0) / (x1 - x0) * pw;
+    let sy = |v: f64| top + (1.0 - (v - y0) / (y1 - y0)) * ph;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">"#
+    );
+    if let Some((a, b)) = band {
+        let (a, b) = (sx(a.clamp(x0, x1)), sx(b.clamp(x0, x1)));
+        let _ = write!(svg, r##"<rect x="{a:.1}" y="{top}" width="{:.1}" height="{ph}" fill="#fde68a" opacity="0.5"/>"##, (b - a).max(1.0));
+    }
+    for i in 0..=4 {
+        let v = y0 + (y1 - y0) * i as f64 / 4.0;
+        let y = sy(v);
+        let _ = write!(
+            svg,
+            r##"<line x1="{left}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#eee"/><text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"##,
+            left + pw,
+            left - 6.0,
+            y + 4.0,
+            num(v, 2)
+        );
+    }
+    for i in 0..=5 {
+        let v = x0 + (x1 - x0) * i as f64 / 5.0;
+        let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#, sx(v), top + ph + 16.0, num(v, 2));
+    }
+    let _ = write!(
+        svg,
+        r##"<rect x="{left}" y="{top}" width="{pw}" height="{ph}" fill="none" stroke="#999"/><text x="{:.1}" y="{:.1}" text-anchor="middle">time from anchor (s)</text><text transform="translate(14 {:.1}) rotate(-90)" text-anchor="middle">{}</text>"##,
+        left + pw / 2.0,
+        h - 6.0,
+        top + ph / 2.0,
+        esc(y_label)
+    );
+
+    for (i, s) in series.iter().enumerate() {
+        let mut d = String::new();
+        let mut pen_down = false;
+        for (xv, v) in x.iter().zip(&s.values) {
+            match v.filter(|v| v.is_finite()) {
+                Some(v) => {
+                    let _ = write!(d, "{}{:.1} {:.1} ", if pen_down { "L" } else { "M" }, sx(*xv), sy(v.clamp(y0, y1)));
+                    pen_down = true;
+                }
+                None => pen_down = false,
+            }
+        }
+        let dash = if s.dashed { r#" stroke-dasharray="5 3""# } else { "" };
+        let _ = write!(svg, r#"<path d="{}" fill="none" stroke="{}" stroke-width="1.8"{dash}/>"#, d.trim_end(), s.color);
+        let ly = top + 10.0 + i as f64 * 16.0;
+        let lx = left + pw + 12.0;
+        let _ = write!(
+            svg,
+            r#"<line x1="{lx:.1}" y1="{ly:.1}" x2="{:.1}" y2="{ly:.1}" stroke="{}" stroke-width="2"{dash}/><text x="{:.1}" y="{:.1}">{}</text>"#,
+            lx + 18.0,
+            s.color,
+            lx + 22.0,
+            ly + 4.0,
+            esc(&s.name)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/* Mean over participants of blue / (blue + red) per bin for each condition, plus participant counts */
+fn condition_curves(spec: &AnalysisSpec, curves: &[RecordingCurve]) -> Vec<(String, usize, Vec<Option<f64>>)> {
+    spec.conditions
+        .iter()
+        .map(|c| {
+            let tests: HashSet<&str> = c.tests.iter().map(String::as_str).collect();
+            let per = participant_curves(curves, &tests, spec.bins.num_bins);
+            let means = (0..spec.bins.num_bins)
+                .map(|i| {
+                    let shares: Vec<f64> = per
+                        .values()
+                        .filter_map(|bins| bins.get(i))
+                        .filter(|b| b.blue + b.red > 0)
+                        .map(|b| b.blue as f64 / (b.blue + b.red) as f64)
+                        .collect();
+                    (!shares.is_empty()).then(|| shares.iter().sum::<f64>() / shares.len() as f64)
+                })
+                .collect();
+            (c.name.clone(), per.len(), means)
+        })
+        .collect()
+}
+
+/* ─────────────────────────── Sections ─────────────────────────── */
+
+fn box_table(conn: &rusqlite::Connection, spec: &AnalysisSpec, excluded: &HashSet<DisabledSlice>, out: &mut String) -> Result<(), String> {
+    let mut per_test: Vec<(String, HashMap<String, i64>)> = Vec::new();
+    for test in all_tests(&spec.conditions) {
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        let recordings: Vec<Option<String>> =
+            if spec.recordings.is_empty() { vec![None] } else { spec.recordings.iter().cloned().map(Some).collect() };
+        for rec in &recordings {
+            for (b, n) in box_counts_sql(conn, &test, &spec.participants, &None, rec, &None, excluded)? {
+                *counts.entry(b).or_default() += n;
+            }
+        }
+        per_test.push((test, counts));
+    }
+
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+    for (_, counts) in &per_test {
+        for (b, n) in counts { *totals.entry(b.clone()).or_default() += n; }
+    }
+    let mut boxes: Vec<(String, i64)> = totals.into_iter().collect();
+    boxes.sort_by_key(|(b, n)| (std::cmp::Reverse(*n), b.clone()));
+    let shown: Vec<String> = boxes.iter().take(MAX_BOX_COLUMNS).map(|(b, _)| b.clone()).collect();
+    let has_other = boxes.len() > shown.len();
+
+    let mut head: Vec<String> = vec!["Test".to_string(), "Samples".to_string()];
+    head.extend(shown.iter().map(|b| format!("{} %", esc(b))));
+    if has_other { head.push("other %".to_string()); }
+    let rows: Vec<Vec<String>> = per_test
+        .iter()
+        .map(|(test, counts)| {
+            let total: i64 = counts.values().sum();
+            let pct = |n: i64| if total > 0 { num(n as f64 / total as f64 * 100.0, 1) } else { "—".to_string() };
+            let mut row = vec![esc(test), total.to_string()];
+            row.extend(shown.iter().map(|b| pct(counts.get(b).copied().unwrap_or(0))));
+            if has_other {
+                row.push(pct(counts.iter().filter(|(b, _)| !shown.contains(b)).map(|(_, n)| n).sum()));
+            }
+            row
+        })
+        .collect();
+    out.push_str("<h2>Gaze share by box</h2>\n");
+    table(out, &head.iter().map(String::as_str).collect::<Vec<_>>(), &rows);
+    Ok(())
+}
+
+fn exclusion_section(spec: &AnalysisSpec, excluded: &HashSet<DisabledSlice>, quality: &QualitySummary, out: &mut String) {
+    let tests: HashSet<String> = all_tests(&spec.conditions).into_iter().collect();
+    let extra: HashSet<&DisabledSlice> = spec.exclusions.slices.iter().collect();
+    let failed: HashSet<&DisabledSlice> = quality.exclusions.iter().map(|q| &q.slice).collect();
+    let mut relevant: Vec<&DisabledSlice> = excluded.iter().filter(|s| tests.contains(&s.test_name)).collect();
+    relevant.sort_by(|a, b| (&a.test_name, &a.participant_name, &a.recording_name).cmp(&(&b.test_name, &b.participant_name, &b.recording_name)));
+
+    out.push_str("<h2>Exclusions</h2>\n");
+    let _ = writeln!(
+        out,
+        "<p>{} slice(s) excluded in total ({} from the database's disabled slices{}, {} specific to this analysis, {} by data-quality rules); {} of them touch the tests analysed.</p>",
+        excluded.len(),
+        excluded.iter().filter(|s| !extra.contains(s) && !failed.contains(s)).count(),
+        if spec.exclusions.use_disabled { "" } else { ", not applied" },
+        extra.len(),
+        failed.iter().filter(|s| !extra.contains(*s)).count(),
+        relevant.len()
+    );
+    if relevant.is_empty() { return; }
+    let rows: Vec<Vec<String>> = relevant
+        .iter()
+        .map(|s| {
+            let source = if extra.contains(s) {
+                "analysis"
+            } else if failed.contains(s) {
+                "quality"
+            } else {
+                "disabled"
+            };
+            vec![esc(&s.test_name), esc(&s.participant_name), esc(&s.recording_name), source.to_string()]
+        })
+        .collect();
+    table(out, &["Test", "Participant", "Recording", "Source"], &rows);
+}
+
+/* Data-quality rules of the analysis, what they excluded and per-participant accuracy / precision */
+struct QualitySummary {
+    rules: Option<QualityRules>,
+    exclusions: Vec<QualityExclusion>,
+    participants: Vec<ParticipantQuality>,
+}
+
+fn quality_section(quality: &QualitySummary, out: &mut String) {
+    out.push_str("<h2>Data quality</h2>\n");
+    match &quality.rules {
+        Some(r) => {
+            let mut rules = Vec::new();
+            let limit = |label: &str, v: Option<f64>, unit: &str| v.map(|v| format!("{label} ≤ {} {unit}", num(v, 2)));
+            rules.extend(limit("accuracy", r.max_accuracy_deg, "°"));
+            rules.extend(limit("precision", r.max_precision_deg, "°"));
+            rules.extend(limit("accuracy", r.max_accuracy_px, "px"));
+            rules.extend(limit("precision", r.max_precision_px, "px"));
+            rules.extend(limit("RMS-S2S", r.max_rms_s2s_deg, "°"));
+            rules.extend(limit("RMS-S2S", r.max_rms_s2s_px, "px"));
+            let _ = writeln!(
+                out,
+                "<p>Slices were excluded when their recording's validation (else calibration) results exceeded {}{}{}. RMS sample-to-sample precision was computed inside I-VT fixations ({} °/s threshold, {} px/°, fixations ≥ {} ms). {} slice(s) failed.</p>",
+                if rules.is_empty() { "no threshold".to_string() } else { rules.join(", ") },
+                if r.worse_eye { " (worse eye)" } else { " (eye average)" },
+                if r.exclude_uncalibrated { ", or when no calibration record exists" } else { "" },
+                num(r.precision.velocity_threshold_deg_s, 0),
+                num(r.precision.px_per_deg, 1),
+                num(r.precision.min_fixation_ms, 0),
+                quality.exclusions.len()
+            );
+        }
+        None => out.push_str("<p>No data-quality exclusion rules were applied.</p>\n"),
+    }
+    if !quality.exclusions.is_empty() {
+        let rows: Vec<Vec<String>> = quality
+            .exclusions
+            .iter()
+            .map(|q| vec![esc(&q.slice.test_name), esc(&q.slice.participant_name), esc(&q.slice.recording_name), esc(&q.reasons.join("; "))])
+            .collect();
+        table(out, &["Test", "Participant", "Recording", "Reasons"], &rows);
+    }
+    if quality.participants.is_empty() { return; }
+    let opt = |v: Option<f64>, digits: usize| v.map_or("–".to_string(), |v| num(v, digits));
+    let rows: Vec<Vec<String>> = quality
+        .participants
+        .iter()
+        .map(|p| {
+            vec![
+                esc(&p.participant),
+                p.recordings.to_string(),
+                p.calibrated_recordings.to_string(),
+                opt(p.accuracy_deg, 2),
+                opt(p.precision_deg, 2),
+                opt(p.rms_s2s_deg.as_ref().map(|s| s.mean), 3),
+                opt(p.rms_s2s_px.as_ref().map(|s| s.mean), 2),
+            ]
+        })
+        .collect();
+    table(out, &["Participant", "Recordings", "Calibrated", "Accuracy (°)", "Precision (°)", "RMS-S2S (°)", "RMS-S2S (px)"], &rows);
+}
+
+fn methods(spec: &AnalysisSpec, n_participants: usize, n_recordings: usize, n_excluded: usize, output: &AnalysisOutput) -> String {
+    let b = &spec.bins;
+    let anchor = match &b.anchor_word {
+        Some(w) => format!("the onset of the word “{}” (from the test catalogue's word windows)", esc(w)),
+        None if b.start_ms != 0.0 => format!("{} ms after the first sample of each recording", num(b.start_ms, 0)),
+        None => "the first sample of each recording".to_string(),
+    };
+    let shift = if b.shift_ms != 0.0 { format!(", shifted by {} ms", num(b.shift_ms, 0)) } else { String::new() };
+    let conditions: Vec<String> = spec
+        .conditions
+        .iter()
+        .map(|c| format!("{} ({} test{})", c.name, c.tests.len(), if c.tests.len() == 1 { "" } else { "s" }))
+        .collect();
+
+    let mut p = format!(
+        "Gaze data from {n_participants} participant(s) ({n_recordings} recording(s)) were analysed across {} condition(s): {}. ",
+        spec.conditions.len(),
+        list(&conditions)
+    );
+    let _ = write!(
+        p,
+        "Samples coded as {} were counted as target looks and samples coded as {} as competitor looks; {} were treated as invalid. ",
+        list(&spec.aoi.blue_keys),
+        list(&spec.aoi.red_keys),
+        list(&spec.aoi.invalid)
+    );
+    let binned = format!(
+        "Samples were aggregated into {} bins of {} ms time-locked to {anchor}{shift}, and the proportion of target looks was computed as target / (target + competitor) per bin",
+        b.num_bins,
+        num(b.bin_ms, 0)
+    );
+    let excluded = format!(" {n_excluded} participant × recording slice(s) were excluded before analysis.");
+
+    match output {
+        AnalysisOutput::Bins(_) => {
+            let _ = write!(p, "{binned}, pooling recordings within participant.{excluded}");
+        }
+        AnalysisOutput::Growth(r) => {
+            let _ = write!(
+                p,
+                "{binned}. Growth curves with orthogonal polynomial time terms up to degree {} were fit to each participant's curve on the {} scale by weighted least squares, and the per-participant coefficients were tested against zero (and between conditions, relative to {}) with t tests across participants; p-values were adjusted with correction “{}”.{excluded}",
+                r.degree,
+                label(&r.scale),
+                esc(r.reference.as_deref().unwrap_or("the first condition")),
+                label(&r.correction)
+            );
+        }
+        AnalysisOutput::Divergence(r) => {
+            let _ = write!(
+                p,
+                "{binned}. {} t tests comparing {} and {} were run in every bin on the {} scale; the divergence point was the first bin starting a run of significant bins (α = {}) of at least {} ms ({} bin(s)). Its {}% confidence interval was estimated from {} bootstrap resamples of participants (seed {}).{excluded}",
+                if r.paired { "Paired" } else { "Welch" },
+                esc(&r.condition_a),
+                esc(&r.condition_b),
+                label(&r.scale),
+                spec.divergence.alpha,
+                num(spec.divergence.sustain_ms, 0),
+                r.sustain_bins,
+                num(spec.divergence.ci_level * 100.0, 0),
+                r.n_boot,
+                r.seed
+            );
+        }
+        AnalysisOutput::Lmm(r) => {
+            let w = &spec.window;
+            let _ = write!(
+                p,
+                "Target looks were summed per trial in the window {}–{} ms after {}. A linear mixed model with crossed random intercepts ({}) was fit by {} to the {} of target looks, with fixed effects for {}; factor effects were tested with likelihood-ratio tests between ML fits, coefficient p-values are asymptotic Wald z tests (no Satterthwaite or Kenward–Roger df) and p-values were adjusted with correction “{}”.{excluded}",
+                num(w.start_ms, 0),
+                num(w.end_ms, 0),
+                match &w.anchor_word { Some(word) => format!("the onset of “{}”", esc(word)), None => "recording start".to_string() },
+                list(&r.random_effects.iter().map(|v| v.group.clone()).filter(|g| g != "Residual").collect::<Vec<_>>()),
+                if r.reml { "REML" } else { "maximum likelihood" },
+                label(&r.scale),
+                list(&spec.lmm.fixed),
+                label(&r.correction)
+            );
+        }
+        // slice summaries get no report (see generate_report)
+        _ => {}
+    }
+    p
+}
+
+fn result_section(output: &AnalysisOutput, out: &mut String) {
+    out.push_str("<h2>Results</h2>\n");
+    match output {
+        AnalysisOutput::Bins(r) => {
+            let _ = writeln!(out, "<p>{} recording(s) binned into {} bins.</p>", r.recordings.len(), r.x_sec.len());
+        }
+        AnalysisOutput::Growth(r) => {
+            let coef_rows = |coefs: &[crate::analysis::growth::GrowthCoefficient]| -> Vec<Vec<String>> {
+                coefs
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            esc(&c.condition),
+                            esc(&c.term),
+                            num(c.estimate, 4),
+                            num(c.se, 4),
+                            num(c.t, 2),
+                            num(c.df, 0),
+                            p_value(c.p),
+                            p_value(c.p_adjusted),
+                            c.test.label().to_string(),
+                            c.n.to_string(),
+                        ]
+                    })
+                    .collect()
+            };
+            let head = ["Condition", "Term", "Estimate", "SE", "t", "df", "p", "p (adj.)", "Test", "n"];
+            out.push_str("<h3>Coefficients</h3>\n");
+            table(out, &head, &coef_rows(&r.coefficients));
+            if !r.interactions.is_empty() {
+                let _ = writeln!(out, "<h3>Condition effects (reference: {})</h3>", esc(r.reference.as_deref().unwrap_or("—")));
+                table(out, &head, &coef_rows(&r.interactions));
+            }
+            let x = &r.x_sec;
+            let series: Vec<Series> = r
+                .curves
+                .iter()
+                .enumerate()
+                .flat_map(|(i, c)| {
+                    let color = PALETTE[i % PALETTE.len()];
+                    [
+                        Series { name: format!("{} observed", c.condition), values: c.observed.clone(), dashed: false, color },
+                        Series { name: format!("{} fitted", c.condition), values: c.fitted.iter().copied().map(Some).collect(), dashed: true, color },
+                    ]
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "<figure>{}<figcaption>Observed (solid) and fitted (dashed) curves on the {} scale.</figcaption></figure>",
+                line_chart(x, &series, &label(&r.scale), None, None),
+                label(&r.scale)
+            );
+        }
+        AnalysisOutput::Divergence(r) => {
+            let rows = vec![
+                vec!["Observed onset (ms)".to_string(), opt(r.observed_onset_ms, 0)],
+                vec!["Bootstrap mean onset (ms)".to_string(), opt(r.mean_onset_ms, 0)],
+                vec!["Bootstrap median onset (ms)".to_string(), opt(r.median_onset_ms, 0)],
+                vec!["CI (ms)".to_string(), format!("{} – {}", opt(r.ci_low_ms, 0), opt(r.ci_high_ms, 0))],
+                vec!["Resamples that diverged".to_string(), format!("{} / {}", r.n_diverged, r.n_boot)],
+                vec!["Participants".to_string(), r.n_participants.to_string()],
+            ];
+            table(out, &["", ""], &rows);
+            let x: Vec<f64> = r.observed_bins.iter().map(|b| b.x_sec).collect();
+            let series = [Series {
+                name: format!("{} − {}", r.condition_a, r.condition_b),
+                values: r.observed_bins.iter().map(|b| Some(b.mean_diff)).collect(),
+                dashed: false,
+                color: PALETTE[0],
+            }];
+            let band = r.ci_low_ms.zip(r.ci_high_ms).map(|(a, b)| (a / 1000.0, b / 1000.0));
+            let _ = writeln!(
+                out,
+                "<figure>{}<figcaption>Mean difference per bin on the {} scale; the shaded band is the onset CI.</figcaption></figure>",
+                line_chart(&x, &series, "difference", None, band),
+                label(&r.scale)
+            );
+        }
+        AnalysisOutput::Lmm(r) => {
+            let _ = writeln!(
+                out,
+                "<p>{} trials ({} dropped); log-likelihood {}, AIC {}, BIC {}{}.</p>",
+                r.n_obs,
+                r.n_dropped,
+                num(r.log_lik, 2),
+                num(r.aic, 2),
+                num(r.bic, 2),
+                if r.converged { "" } else { "; <strong>the fit did not converge</strong>" }
+            );
+            out.push_str("<h3>Fixed effects</h3>\n");
+            let rows: Vec<Vec<String>> = r
+                .fixed_effects
+                .iter()
+                .map(|f| vec![esc(&f.term), num(f.estimate, 4), num(f.se, 4), num(f.t, 2), p_value(f.p), p_value(f.p_adjusted)])
+                .collect();
+            table(out, &["Term", "Estimate", "SE", "t", "p", "p (adj.)"], &rows);
+            out.push_str("<h3>Random effects</h3>\n");
+            let rows: Vec<Vec<String>> = r
+                .random_effects
+                .iter()
+                .map(|v| vec![esc(&v.group), num(v.variance, 4), num(v.sd, 4), v.n_levels.to_string()])
+                .collect();
+            table(out, &["Group", "Variance", "SD", "Levels"], &rows);
+            if !r.lrt.is_empty() {
+                out.push_str("<h3>Likelihood-ratio tests</h3>\n");
+                let rows: Vec<Vec<String>> = r
+                    .lrt
+                    .iter()
+                    .map(|t| vec![esc(&t.factor), t.df.to_string(), num(t.chisq, 2), p_value(t.p), p_value(t.p_adjusted)])
+                    .collect();
+                table(out, &["Factor", "df", "χ²", "p", "p (adj.)"], &rows);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render(
+    conn: &rusqlite::Connection,
+    analysis: &Analysis,
+    excluded: &HashSet<DisabledSlice>,
+    output: &AnalysisOutput,
+    manifest: &Manifest,
+    db_path: &Path,
+    quality: &QualitySummary,
+) -> Result<String, String> {
+    let spec = &analysis.spec;
+    let tests = all_tests(&spec.conditions);
+    let mut curves = recording_curves(conn, excluded, &tests, &spec.participants, &spec.cohort, &spec.aoi, &spec.bins)?;
+    if !spec.recordings.is_empty() { curves.retain(|c| spec.recordings.contains(&c.recording)); }
+    let n_participants = curves.iter().map(|c| c.participant.as_str()).collect::<HashSet<_>>().len();
+    let relevant_excluded = excluded.iter().filter(|s| tests.contains(&s.test_name)).count();
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\"><title>{}</title><style>{CSS}</style></head><body>\n",
+        esc(&analysis.name)
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", esc(&analysis.name));
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">{} analysis · generated {} · database {} (sha256 {}…) · app v{}</p>",
+        label(&spec.kind),
+        utc(manifest.created_at_ms),
+        esc(&db_path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default()),
+        &manifest.db.sha256[..manifest.db.sha256.len().min(12)],
+        esc(&manifest.app_version)
+    );
+    if let Some(d) = analysis.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        let _ = writeln!(html, "<p>{}</p>", esc(d));
+    }
+
+    html.push_str("<h2>Methods</h2>\n");
+    let _ = writeln!(html, "<p>{}</p>", methods(spec, n_participants, curves.len(), relevant_excluded, output));
+
+    let rows: Vec<Vec<String>> = spec.conditions.iter().map(|c| vec![esc(&c.name), list(&c.tests)]).collect();
+    html.push_str("<h2>Conditions</h2>\n");
+    table(&mut html, &["Condition", "Tests"], &rows);
+
+    box_table(conn, spec, excluded, &mut html)?;
+
+    if spec.kind != AnalysisKind::Lmm {
+        let per = condition_curves(spec, &curves);
+        let series: Vec<Series> = per
+            .iter()
+            .enumerate()
+            .map(|(i, (name, n, values))| Series {
+                name: format!("{name} (n={n})"),
+                values: values.clone(),
+                dashed: false,
+                color: PALETTE[i % PALETTE.len()],
+            })
+            .collect();
+        html.push_str("<h2>Binned curves</h2>\n");
+        let _ = writeln!(
+            html,
+            "<figure>{}<figcaption>Proportion of target looks out of target + competitor looks, averaged over participants.</figcaption></figure>",
+            line_chart(&spec.bins.x_sec(), &series, "P(target)", Some((0.0, 1.0)), None)
+        );
+    }
+
+    result_section(output, &mut html);
+    exclusion_section(spec, excluded, quality, &mut html);
+    quality_section(quality, &mut html);
+
+    html.push_str("<h2>Reproducibility</h2>\n");
+    let _ = writeln!(
+        html,
+        "<p>Result sha256 <code>{}</code>. The manifest below re-runs this analysis through “Re-run from manifest”.</p>",
+        manifest.result_sha256
+    );
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    let _ = writeln!(html, "<details><summary>Manifest</summary><pre>{}</pre></details>", esc(&json));
+    html.push_str("</body></html>\n");
+    Ok(html)
+}
+
+/* ─────────────────────────── PDF ─────────────────────────── */
+
+/* Chromium-family browsers that can --print-to-pdf, most likely first */
+fn browser_candidates() -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> = std::env::var_os("REPORT_BROWSER").map(PathBuf::from).into_iter().collect();
+    if cfg!(target_os = "windows") {
+        for base in ["ProgramFiles(x86)", "ProgramFiles", "LOCALAPPDATA"].iter().filter_map(std::env::var_os) {
+            let base = PathBuf::from(base);
+            out.push(base.join("Microsoft/Edge/Application/msedge.exe"));
+            out.push(base.join("Google/Chrome/Application/chrome.exe"));
+        }
+    } else if cfg!(target_os = "macos") {
+        out.push(PathBuf::from("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"));
+        out.push(PathBuf::from("/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge"));
+        out.push(PathBuf::from("/Applications/Chromium.app/Contents/MacOS/Chromium"));
+    }
+    out.extend(["chromium", "chromium-browser", "google-chrome", "microsoft-edge"].map(PathBuf::from));
+    out
+}
+
+/* Blocking: run through spawn_blocking. A browser still running after PDF_TIMEOUT is killed */
+fn print_pdf(html: &Path, pdf: &Path) -> Result<(), String> {
+    let url = url::Url::from_file_path(html).map_err(|_| format!("bad report path: {}", html.display()))?;
+    let _ = fs::remove_file(pdf);
+    let mut timed_out = None;
+    for browser in browser_candidates() {
+        if browser.is_absolute() && !browser.exists() { continue; }
+        let Ok(mut child) = Command::new(&browser)
+            .args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+            .arg(format!("--print-to-pdf={}", pdf.display()))
+            .arg(url.as_str())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let deadline = Instant::now() + PDF_TIMEOUT;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(100)),
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    timed_out = Some(browser.clone());
+                    break None;
+                }
+                Err(_) => break None,
+            }
+        };
+        if status.is_some_and(|s| s.success()) && pdf.exists() { return Ok(()); }
+    }
+    Err(match timed_out {
+        Some(browser) => format!("{} did not print the PDF within {} s; open the HTML and print it instead", browser.display(), PDF_TIMEOUT.as_secs()),
+        None => "no headless Chrome/Edge found to print the PDF (set REPORT_BROWSER); open the HTML and print it instead".to_string(),
+    })
+}
+
+fn slug(name: &str) -> String {
+    let s: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' }).collect();
+    if s.trim_matches('_').is_empty() { "analysis".to_string() } else { s }
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Write the report of a saved analysis to `path` (default: reports/ next to analyses.json) */
+#[tauri::command]
+pub async fn generate_report(app: AppHandle, id: String, path: Option<String>, pdf: Option<bool>) -> Result<ReportOutput, String> {
+    let analysis = saved::find(&app, &id)?;
+    if !analysis.spec.kind.is_curve() {
+        return Err(format!("no report for {} analyses (bins, growth, divergence and LMM only)", label(&analysis.spec.kind)));
+    }
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let rules = analysis.spec.exclusions.quality.clone();
+    let failed = saved::quality_exclusions(&app, &conn, &analysis.spec)?;
+    // precision table over everything the analysis keeps before its quality rules
+    let kept = saved::exclusions(&analysis.spec, &disabled, &[]);
+    let options = rules.as_ref().map(|r| r.precision.clone()).unwrap_or_default();
+    let slices = quality::compute_precision(&conn, &all_tests(&analysis.spec.conditions), &analysis.spec.participants, &analysis.spec.cohort, &kept, &options)?;
+    let quality = QualitySummary { rules, participants: quality::participant_quality(&slices, &quality::load_calibration(&app)?), exclusions: failed };
+    let excluded = saved::exclusions(&analysis.spec, &disabled, &quality.exclusions);
+    let output = saved::run(&app, &conn, &analysis.spec, &excluded)?;
+    let manifest = manifest::build(&app, "generate_report", analysis.spec.clone(), &excluded, output.seed(), &output.result_value()?)?;
+    let db_path = app.state::<DbPath>().0.read().unwrap().clone();
+    let html = render(&conn, &analysis, &excluded, &output, &manifest, &db_path, &quality)?;
+
+    let html_path = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => crate::project::data_file(&app, &format!("reports/{}-{}.html", slug(&analysis.name), manifest.created_at_ms))?,
+    };
+    if let Some(parent) = html_path.parent() { let _ = fs::create_dir_all(parent); }
+    fs::write(&html_path, html).map_err(|e| format!("{}: {e}", html_path.display()))?;
+
+    let (pdf_path, pdf_error) = match pdf {
+        Some(true) => {
+            let target = html_path.with_extension("pdf");
+            let (html, pdf) = (html_path.clone(), target.clone());
+            match spawn_blocking(move || print_pdf(&html, &pdf)).await.map_err(|e| e.to_string()).and_then(|r| r) {
+                Ok(()) => (Some(target.to_string_lossy().to_string()), None),
+                Err(e) => (None, Some(e)),
+            }
+        }
+        _ => (None, None),
+    };
+    Ok(ReportOutput { html_path: html_path.to_string_lossy().to_string(), pdf_path, pdf_error, manifest })
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use crate::binning::GazeSample;
+use crate::timestamp;
+
+/* ──────────────────────────────────────────────────────────────
+Resampling onto a fixed time grid. Sample-count shares are biased when
+recordings differ in (or jitter around) their sampling rate, so each
+recording can first be mapped onto t0, t0 + 1/rate, … (in µs, rounded):
+Box from the nearest sample (or the last one, "hold"), coordinates
+linearly interpolated. Grid points inside a gap between samples longer
+than max_gap_ms become "missing" (track loss rows are often just absent).
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoxRule {
+    #[default]
+    Nearest,
+    Hold,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResampleParams {
+    #[serde(default = "default_rate_hz")]
+    pub rate_hz: f64,
+    #[serde(default)]
+    pub box_rule: BoxRule,
+    #[serde(default = "default_max_gap_ms")]
+    pub max_gap_ms: f64,
+}
+
+fn default_rate_hz() -> f64 { 60.0 }
+fn default_max_gap_ms() -> f64 { 100.0 }
+
+impl Default for ResampleParams {
+    fn default() -> Self {
+        ResampleParams { rate_hz: default_rate_hz(), box_rule: BoxRule::default(), max_gap_ms: default_max_gap_ms() }
+    }
+}
+
+/* One grid point: which sample supplies Box, and which pair (with weight of the second) the coordinates */
+#[derive(Debug, Clone, Copy)]
+pub struct GridPoint {
+    pub t_us: i64,
+    /* last sample at or before t_us */
+    pub prev: usize,
+    pub source: Option<usize>,
+    pub interp: Option<(usize, usize, f64)>,
+}
+
+/* Grid over `times` (ascending epoch µs) */
+pub fn grid(times: &[i64], params: &ResampleParams) -> Vec<GridPoint> {
+    match times.first() {
+        Some(&first) => grid_from(times, first, params),
+        None => vec![],
+    }
+}
+
+/* Grid points of anchor, anchor + 1/rate, … that fall within `times` (a stretch of a longer
+   recording whose grid starts at `anchor` ≤ times[0]) */
+pub fn grid_from(times: &[i64], anchor: i64, params: &ResampleParams) -> Vec<GridPoint> {
+    let (Some(&first), Some(&last)) = (times.first(), times.last()) else { return vec![]; };
+    let anchor = anchor.min(first);
+    let step = 1_000_000.0 / params.rate_hz.clamp(1.0, 10_000.0);
+    let max_gap = timestamp::from_ms(params.max_gap_ms);
+    let k0 = ((first - anchor) as f64 / step).ceil() as usize;
+    let k1 = ((last - anchor) as f64 / step).floor() as usize;
+    let mut out = Vec::with_capacity((k1 + 1).saturating_sub(k0));
+    let mut j = 0; // last sample at or before the grid time
+    for k in k0..=k1 {
+        let t = anchor + (k as f64 * step).round() as i64;
+        if t < first { continue; }
+        while j + 1 < times.len() && times[j + 1] <= t { j += 1; }
+        let next = (j + 1 < times.len()).then_some(j + 1);
+        let (d_prev, d_next) = (t - times[j], next.map(|i| times[i] - t));
+        // inside a gap longer than max_gap_ms only points right next to a sample keep its Box
+        let in_gap = next.is_some_and(|i| times[i] - times[j] > max_gap);
+        let tol = if in_gap { (step / 2.0) as i64 } else { max_gap };
+        let source = match params.box_rule {
+            BoxRule::Hold => (d_prev <= tol).then_some(j),
+            BoxRule::Nearest => match (next, d_next) {
+                (Some(i), Some(d)) if d < d_prev => (d <= tol).then_some(i),
+                _ => (d_prev <= tol).then_some(j),
+            },
+        };
+        let interp = match next {
+            _ if d_prev == 0 => Some((j, j, 0.0)),
+            Some(i) if times[i] - times[j] <= max_gap => Some((j, i, d_prev as f64 / (times[i] - times[j]) as f64)),
+            _ => None,
+        };
+        out.push(GridPoint { t_us: t, prev: j, source, interp });
+    }
+    out
+}
+
+/* Linear interpolation of an optional value at a grid point */
+pub fn interpolate(values: &[Option<f64>], p: &GridPoint) -> Option<f64> {
+    let (a, b, w) = p.interp?;
+    match (values[a], values[b]) {
+        (Some(x), Some(y)) => Some(x + (y - x) * w),
+        // one side missing: only the sample the point sits on counts
+        (Some(x), None) if w == 0.0 => Some(x),
+        _ => None,
+    }
+}
+
+/* Samples on the grid (borrowed unchanged when no resampling is asked for) */
+pub fn samples<'a>(samples: &'a [GazeSample], params: Option<&ResampleParams>) -> Cow<'a, [GazeSample]> {
+    let Some(params) = params else { return Cow::Borrowed(samples); };
+    let times: Vec<i64> = samples.iter().map(|s| s.t_us).collect();
+    Cow::Owned(
+        grid(&times, params)
+            .iter()
+            .map(|p| GazeSample {
+                t_us: p.t_us,
+                box_name: p.source.map(|i| samples[i].box_name.clone()).unwrap_or_else(|| "missing".to_string()),
+            })
+            .collect(),
+    )
+}
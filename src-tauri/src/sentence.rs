@@ -0,0 +1,433 @@
+use rusqlite::{OpenFlags, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use tauri::{AppHandle, State};
+
+use crate::aoi::{load_catalog, pick};
+use crate::project::{is_bundled, DbPath};
+use crate::vocab::{ANIMALS, MEASURE_WORDS, OBJECTS, ONLY_DAK, ONLY_JINGHAI, ONLY_ZAA, VERB};
+use crate::{table_exists, DbPool};
+
+/* ──────────────────────────────────────────────────────────────
+Sentence parser over the closed stimulus vocabulary, and a check of the
+hand-curated test_catalog columns (group / only_position / morpheme)
+against what each sentence says:
+  淨係/得 + subject ... → only_position "subject", group Szinghai
+  subject 淨係 verb ... / ... 咋 → only_position "verb", group Vzinghai
+Blank curated values can be filled in; differing ones are only reported.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    Only,
+    Subject,
+    Verb,
+    Measure,
+    Object,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParsedSentence {
+    pub tokens: Vec<Token>,
+    pub subject: Option<String>,
+    pub verb: Option<String>,
+    pub measure: Option<String>,
+    pub object: Option<String>,
+    pub only_word: Option<String>,
+    /* "subject" | "verb"; None without an only-word */
+    pub only_position: Option<String>,
+    /* "Szinghai" | "Vzinghai" */
+    pub group: Option<String>,
+    /* structural problems (unknown text, missing parts, wrong measure word, ...) */
+    pub problems: Vec<String>,
+}
+
+/* Longest vocabulary word at the start of `s` */
+fn next_word(s: &str) -> Option<(TokenKind, &'static str)> {
+    let only = [ONLY_JINGHAI, ONLY_DAK, ONLY_ZAA].map(|w| (TokenKind::Only, w));
+    let words = only
+        .into_iter()
+        .chain(std::iter::once((TokenKind::Verb, VERB)))
+        .chain(ANIMALS.iter().map(|w| (TokenKind::Subject, *w)))
+        .chain(OBJECTS.iter().map(|(w, _)| (TokenKind::Object, *w)))
+        .chain(MEASURE_WORDS.iter().map(|w| (TokenKind::Measure, *w)));
+    words.filter(|(_, w)| s.starts_with(w)).max_by_key(|(_, w)| w.len())
+}
+
+fn tokenize(sentence: &str) -> Vec<Token> {
+    let text: String = sentence
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_ascii_punctuation() && !"，。！？、；：「」『』…".contains(*c))
+        .collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(c) = rest.chars().next() {
+        match next_word(rest) {
+            Some((kind, w)) => {
+                tokens.push(Token { kind, text: w.to_string() });
+                rest = &rest[w.len()..];
+            }
+            None => {
+                // glue unknown characters into one token
+                match tokens.last_mut() {
+                    Some(t) if t.kind == TokenKind::Unknown => t.text.push(c),
+                    _ => tokens.push(Token { kind: TokenKind::Unknown, text: c.to_string() }),
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    tokens
+}
+
+pub fn parse(sentence: &str) -> ParsedSentence {
+    let tokens = tokenize(sentence);
+    let mut out = ParsedSentence::default();
+    let pos = |kind: TokenKind| tokens.iter().position(|t| t.kind == kind);
+    let first = |kind: TokenKind| tokens.iter().find(|t| t.kind == kind).map(|t| t.text.clone());
+
+    let kinds = [
+        (TokenKind::Subject, "subjects"),
+        (TokenKind::Verb, "verbs"),
+        (TokenKind::Measure, "measure words"),
+        (TokenKind::Object, "objects"),
+        (TokenKind::Only, "only-words"),
+    ];
+    for (kind, name) in kinds {
+        let n = tokens.iter().filter(|t| t.kind == kind).count();
+        if n > 1 { out.problems.push(format!("{n} {name}")); }
+    }
+    for t in tokens.iter().filter(|t| t.kind == TokenKind::Unknown) {
+        out.problems.push(format!("unknown text '{}'", t.text));
+    }
+    out.subject = first(TokenKind::Subject);
+    out.verb = first(TokenKind::Verb);
+    out.measure = first(TokenKind::Measure);
+    out.object = first(TokenKind::Object);
+    out.only_word = first(TokenKind::Only);
+    for (name, v) in [("subject", &out.subject), ("verb", &out.verb), ("object", &out.object)] {
+        if v.is_none() { out.problems.push(format!("no {name}")); }
+    }
+
+    let (s, v, m, o) = (pos(TokenKind::Subject), pos(TokenKind::Verb), pos(TokenKind::Measure), pos(TokenKind::Object));
+    if let (Some(s), Some(v)) = (s, v) {
+        if s > v { out.problems.push("subject after the verb".to_string()); }
+    }
+    if let (Some(v), Some(o)) = (v, o) {
+        if v > o { out.problems.push("object before the verb".to_string()); }
+    }
+    if let (Some(m), Some(o)) = (m, o) {
+        if m + 1 != o { out.problems.push("measure word not directly before the object".to_string()); }
+    }
+    if let (Some(measure), Some(object)) = (&out.measure, &out.object) {
+        if let Some((_, accepted)) = OBJECTS.iter().find(|(w, _)| w == object) {
+            if !accepted.contains(&measure.as_str()) {
+                out.problems.push(format!("{object} takes {}, not {measure}", accepted.join("/")));
+            }
+        }
+    }
+
+    if let (Some(word), Some(q)) = (out.only_word.as_deref(), pos(TokenKind::Only)) {
+        let position = match word {
+            w if w == ONLY_ZAA => {
+                if q + 1 != tokens.len() { out.problems.push(format!("{ONLY_ZAA} not sentence-final")); }
+                Some("verb")
+            }
+            w if w == ONLY_DAK => {
+                if s.is_some_and(|s| q > s) { out.problems.push(format!("{ONLY_DAK} not before the subject")); }
+                Some("subject")
+            }
+            _ => match (s, v) {
+                (Some(s), _) if q < s => Some("subject"),
+                (Some(s), Some(v)) if q > s && q < v => Some("verb"),
+                _ => {
+                    out.problems.push(format!("{ONLY_JINGHAI} neither before the subject nor before the verb"));
+                    None
+                }
+            },
+        };
+        out.only_position = position.map(str::to_string);
+        out.group = position.map(|p| if p == "subject" { "Szinghai" } else { "Vzinghai" }.to_string());
+    }
+    out.tokens = tokens;
+    out
+}
+
+/* ─────────────────────────── Catalog check ─────────────────────────── */
+
+const CHECKED_COLUMNS: [&str; 3] = ["group", "only_position", "morpheme"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldStatus {
+    /* curated value agrees with the sentence */
+    Ok,
+    /* curated value blank, derived value available */
+    Fill,
+    Mismatch,
+    /* nothing derivable (e.g. no only-word, unparsable sentence) */
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldCheck {
+    pub column: String,
+    pub curated: Option<String>,
+    pub derived: Option<String>,
+    pub status: FieldStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogRowCheck {
+    pub test_name: String,
+    pub sentence: Option<String>,
+    pub parsed: Option<ParsedSentence>,
+    pub fields: Vec<FieldCheck>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogDerivation {
+    pub rows: Vec<CatalogRowCheck>,
+    pub mismatches: usize,
+    /* rows whose sentence has structural problems */
+    pub problem_rows: usize,
+    pub fillable: usize,
+    /* cells written (apply = true) */
+    pub filled: usize,
+    pub writable: bool,
+    pub note: Option<String>,
+}
+
+/* Canonical form of a curated value, so "Subject", "S", "pre-subject" all compare equal */
+fn canonical(column: &str, value: &str) -> Option<String> {
+    let v = value.trim().to_lowercase();
+    if v.is_empty() { return None; }
+    let out = match column {
+        "group" if v.starts_with('s') => "Szinghai",
+        "group" if v.starts_with('v') => "Vzinghai",
+        "only_position" if v.contains("sub") || v == "s" || v.starts_with("sz") => "subject",
+        "only_position" if v.contains("verb") || v == "v" || v.starts_with("vz") => "verb",
+        "morpheme" if ["none", "no", "-", "無", "冇", "null"].contains(&v.as_str()) => "",
+        _ => return Some(value.trim().to_string()),
+    };
+    Some(out.to_string())
+}
+
+fn derived_value(column: &str, p: &ParsedSentence) -> Option<String> {
+    match column {
+        "group" => p.group.clone(),
+        "only_position" => p.only_position.clone(),
+        // a parsed sentence without an only-word has morpheme ""
+        "morpheme" => p.subject.as_ref().map(|_| p.only_word.clone().unwrap_or_default()),
+        _ => None,
+    }
+}
+
+fn check_rows(conn: &rusqlite::Connection) -> Result<Vec<CatalogRowCheck>, String> {
+    let catalog = load_catalog(conn)?;
+    let mut names: Vec<&String> = catalog.keys().collect();
+    names.sort();
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let row = &catalog[name];
+            let sentence = pick(row, "sentence").map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            let parsed = sentence.as_deref().map(parse);
+            let fields = CHECKED_COLUMNS
+                .iter()
+                .map(|col| {
+                    let curated = pick(row, col).map(str::to_string).filter(|s| !s.trim().is_empty());
+                    let derived = parsed.as_ref().and_then(|p| derived_value(col, p));
+                    let status = match (curated.as_deref().and_then(|c| canonical(col, c)), &derived) {
+                        (_, None) => FieldStatus::Unknown,
+                        (None, Some(d)) if d.is_empty() => FieldStatus::Ok,
+                        (None, Some(_)) => FieldStatus::Fill,
+                        (Some(c), Some(d)) if &c == d => FieldStatus::Ok,
+                        (Some(_), Some(_)) => FieldStatus::Mismatch,
+                    };
+                    FieldCheck { column: col.to_string(), curated, derived, status }
+                })
+                .collect();
+            CatalogRowCheck { test_name: name.clone(), sentence, parsed, fields }
+        })
+        .collect())
+}
+
+/* Spelling to write for a derived value: the one the catalog already uses most for it */
+fn spellings(rows: &[CatalogRowCheck]) -> HashMap<(String, String), String> {
+    let mut counts: HashMap<(String, String), HashMap<String, usize>> = HashMap::new();
+    for f in rows.iter().flat_map(|r| &r.fields).filter(|f| f.status == FieldStatus::Ok) {
+        if let (Some(c), Some(d)) = (&f.curated, &f.derived) {
+            *counts.entry((f.column.clone(), d.clone())).or_default().entry(c.trim().to_string()).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter_map(|(k, v)| v.into_iter().max_by_key(|(s, n)| (*n, std::cmp::Reverse(s.clone()))).map(|(s, _)| (k, s)))
+        .collect()
+}
+
+fn table_columns(conn: &rusqlite::Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare("PRAGMA table_info(test_catalog)").map_err(|e| e.to_string())?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .collect::<SqlResult<Vec<_>>>()
+        .map_err(|e| e.to_string());
+    cols
+}
+
+/* Write the Fill values (blank cells only); missing columns are added as TEXT */
+fn fill(conn: &rusqlite::Connection, rows: &[CatalogRowCheck]) -> Result<usize, String> {
+    let cols = table_columns(conn)?;
+    let test_col = cols.iter().find(|c| c.eq_ignore_ascii_case("test_name") || c.eq_ignore_ascii_case("test name")).cloned()
+        .ok_or("test_catalog has no test_name column")?;
+    let spelled = spellings(rows);
+    let mut filled = 0;
+    for col in CHECKED_COLUMNS {
+        let pending: Vec<(&str, &str)> = rows
+            .iter()
+            .flat_map(|r| r.fields.iter().filter(|f| f.column == col && f.status == FieldStatus::Fill).map(move |f| (r.test_name.as_str(), f)))
+            .filter_map(|(t, f)| f.derived.as_deref().map(|d| (t, d)))
+            .collect();
+        if pending.is_empty() { continue; }
+        let actual = match cols.iter().find(|c| c.eq_ignore_ascii_case(col) || c.replace(' ', "_").eq_ignore_ascii_case(col)) {
+            Some(c) => c.clone(),
+            None => {
+                conn.execute_batch(&format!("ALTER TABLE test_catalog ADD COLUMN \"{col}\" TEXT;")).map_err(|e| e.to_string())?;
+                col.to_string()
+            }
+        };
+        let sql = format!(
+            "UPDATE test_catalog SET \"{actual}\" = ?1
+             WHERE TRIM(\"{test_col}\") = ?2 AND (\"{actual}\" IS NULL OR TRIM(CAST(\"{actual}\" AS TEXT)) = '')"
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        for (test, derived) in pending {
+            let value = spelled.get(&(col.to_string(), derived.to_string())).map(String::as_str).unwrap_or(derived);
+            filled += stmt.execute(rusqlite::params![value, test]).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(filled)
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn parse_sentence(sentence: String) -> Result<ParsedSentence, String> {
+    Ok(parse(&sentence))
+}
+
+/* Check group / only_position / morpheme against the sentences; with `apply`, fill blank cells (writable project DBs only) */
+#[tauri::command]
+pub async fn derive_test_catalog(
+    app: AppHandle,
+    apply: Option<bool>,
+    pool: State<'_, DbPool>,
+    db_path: State<'_, DbPath>,
+) -> Result<CatalogDerivation, String> {
+    let path = db_path.0.read().unwrap().clone();
+    let bundled = is_bundled(&app, &path);
+    let read_only_file = fs::metadata(&path).map(|m| m.permissions().readonly()).unwrap_or(true);
+    let writable = !bundled && !read_only_file;
+    let mut note = bundled.then(|| "bundled database is read-only; the catalog can only be filled in a project DB".to_string());
+    if !bundled && read_only_file {
+        note = Some("database file is read-only".to_string());
+    }
+
+    let conn = pool.0.get().map_err(|e| e.to_string())?;
+    if !table_exists(&conn, "test_catalog") {
+        return Err("no test_catalog table in this database".to_string());
+    }
+    let rows = check_rows(&conn)?;
+    let count = |s: FieldStatus| rows.iter().flat_map(|r| &r.fields).filter(|f| f.status == s).count();
+    let (mismatches, fillable) = (count(FieldStatus::Mismatch), count(FieldStatus::Fill));
+    let problem_rows = rows.iter().filter(|r| r.parsed.as_ref().is_some_and(|p| !p.problems.is_empty())).count();
+
+    let mut filled = 0;
+    if apply.unwrap_or(false) && writable && fillable > 0 {
+        let rw = rusqlite::Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_WRITE).map_err(|e| e.to_string())?;
+        filled = fill(&rw, &rows)?;
+        note = Some(format!("filled {filled} blank cell(s)"));
+    }
+    Ok(CatalogDerivation { rows, mismatches, problem_rows, fillable, filled, writable, note })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(s: &str) -> Vec<(TokenKind, String)> {
+        parse(s).tokens.into_iter().map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn readme_sentences() {
+        for (sentence, position, group) in [
+            ("淨係牛仔拎咗枝鉛筆", "subject", "Szinghai"),
+            ("得牛仔拎咗枝鉛筆", "subject", "Szinghai"),
+            ("牛仔淨係拎咗枝鉛筆", "verb", "Vzinghai"),
+            ("牛仔拎咗枝鉛筆咋", "verb", "Vzinghai"),
+        ] {
+            let p = parse(sentence);
+            assert!(p.problems.is_empty(), "{sentence}: {:?}", p.problems);
+            assert_eq!(p.only_position.as_deref(), Some(position), "{sentence}");
+            assert_eq!(p.group.as_deref(), Some(group), "{sentence}");
+            assert_eq!((p.subject.as_deref(), p.measure.as_deref(), p.object.as_deref()), (Some("牛仔"), Some("枝"), Some("鉛筆")));
+        }
+        let plain = parse("牛仔拎咗枝鉛筆。");
+        assert!(plain.problems.is_empty());
+        assert_eq!((plain.only_word, plain.group), (None, None));
+    }
+
+    #[test]
+    fn objects_win_over_their_characters() {
+        assert_eq!(
+            texts("馬仔拎咗枝雪條"),
+            [(TokenKind::Subject, "馬仔"), (TokenKind::Verb, "拎咗"), (TokenKind::Measure, "枝"), (TokenKind::Object, "雪條")]
+                .map(|(k, t)| (k, t.to_string()))
+        );
+        assert_eq!(parse("兔仔拎咗條鎖匙").object.as_deref(), Some("鎖匙"));
+        assert_eq!(parse("兔仔拎咗隻匙羹").object.as_deref(), Some("匙羹"));
+    }
+
+    #[test]
+    fn wrong_measure_word() {
+        let p = parse("貓仔拎咗架鉛筆");
+        assert_eq!(p.problems, vec!["鉛筆 takes 枝, not 架".to_string()]);
+        // either accepted word is fine
+        assert!(parse("貓仔拎咗條雪條").problems.is_empty());
+    }
+
+    #[test]
+    fn unknown_text_and_misplaced_zaa() {
+        let p = parse("牛仔拎咗枝鉛筆呀");
+        assert_eq!(p.problems, vec!["unknown text '呀'".to_string()]);
+        let p = parse("牛仔咋拎咗枝鉛筆");
+        assert!(p.problems.contains(&format!("{ONLY_ZAA} not sentence-final")));
+    }
+
+    #[test]
+    fn canonical_spellings() {
+        assert_eq!(canonical("group", " szinghai "), Some("Szinghai".to_string()));
+        assert_eq!(canonical("group", "V"), Some("Vzinghai".to_string()));
+        for v in ["Subject", "S", "pre-subject", "Szinghai"] {
+            assert_eq!(canonical("only_position", v).as_deref(), Some("subject"), "{v}");
+        }
+        for v in ["verb", "V", "pre-verb", "vzinghai"] {
+            assert_eq!(canonical("only_position", v).as_deref(), Some("verb"), "{v}");
+        }
+        assert_eq!(canonical("morpheme", "冇").as_deref(), Some(""));
+        assert_eq!(canonical("morpheme", " 淨係 ").as_deref(), Some("淨係"));
+        assert_eq!(canonical("group", "  "), None);
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timestamp;
+
+/* ──────────────────────────────────────────────────────────────
+Gaze coordinate smoothing. A pipeline of filters runs over each
+recording's "Gaze point X/Y" in order; Box is left as exported. Series
+are split into segments at missing coordinates and at gaps longer than
+max_gap_ms, and every filter restarts per segment (track loss is never
+bridged). Median and Savitzky–Golay work on sample counts, so they
+assume a steady rate (resample first when it is not); the 1-euro
+filter uses the timestamps.
+────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SmoothFilter {
+    /* moving median over `window` samples (odd) */
+    Median {
+        #[serde(default = "default_median_window")]
+        window: usize,
+    },
+    /* least-squares polynomial of degree `order` over `window` samples (odd) */
+    SavitzkyGolay {
+        #[serde(default = "default_sg_window")]
+        window: usize,
+        #[serde(default = "default_sg_order")]
+        order: usize,
+    },
+    /* Casiez et al. 2012: cutoffs in Hz, beta per (coordinate unit / s) */
+    OneEuro {
+        #[serde(default = "default_min_cutoff")]
+        min_cutoff: f64,
+        #[serde(default = "default_beta")]
+        beta: f64,
+        #[serde(default = "default_d_cutoff")]
+        d_cutoff: f64,
+    },
+}
+
+fn default_median_window() -> usize { 5 }
+fn default_sg_window() -> usize { 7 }
+fn default_sg_order() -> usize { 2 }
+fn default_min_cutoff() -> f64 { 1.0 }
+fn default_beta() -> f64 { 0.007 }
+fn default_d_cutoff() -> f64 { 1.0 }
+
+/* upper bounds on user input: window in samples (odd), Savitzky–Golay degree */
+const MAX_WINDOW: usize = 1001;
+const MAX_SG_ORDER: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmoothParams {
+    #[serde(default)]
+    pub filters: Vec<SmoothFilter>,
+    #[serde(default = "default_max_gap_ms")]
+    pub max_gap_ms: f64,
+}
+
+fn default_max_gap_ms() -> f64 { 100.0 }
+
+impl Default for SmoothParams {
+    fn default() -> Self {
+        SmoothParams { filters: Vec::new(), max_gap_ms: default_max_gap_ms() }
+    }
+}
+
+impl SmoothFilter {
+    /* Parameters as actually applied (even windows widened, windows and order capped, order kept below the window) */
+    fn normalized(&self) -> SmoothFilter {
+        let odd = |w: usize| w.min(MAX_WINDOW - 1) | 1;
+        match *self {
+            SmoothFilter::Median { window } => SmoothFilter::Median { window: odd(window) },
+            SmoothFilter::SavitzkyGolay { window, order } => {
+                let window = odd(window);
+                SmoothFilter::SavitzkyGolay { window, order: order.min(MAX_SG_ORDER).min(window - 1) }
+            }
+            SmoothFilter::OneEuro { min_cutoff, beta, d_cutoff } => SmoothFilter::OneEuro {
+                min_cutoff: min_cutoff.max(1e-6),
+                beta: beta.max(0.0),
+                d_cutoff: d_cutoff.max(1e-6),
+            },
+        }
+    }
+
+    fn apply(&self, times: &[i64], values: &mut [f64]) {
+        match *self {
+            SmoothFilter::Median { window } => median(values, window / 2),
+            SmoothFilter::SavitzkyGolay { window, order } => savitzky_golay(values, window / 2, order),
+            SmoothFilter::OneEuro { min_cutoff, beta, d_cutoff } => one_euro(times, values, min_cutoff, beta, d_cutoff),
+        }
+    }
+}
+
+impl SmoothParams {
+    pub fn normalized(&self) -> SmoothParams {
+        SmoothParams { filters: self.filters.iter().map(SmoothFilter::normalized).collect(), max_gap_ms: self.max_gap_ms }
+    }
+}
+
+/* Windows shrink symmetrically at the segment edges (and to the segment itself) */
+fn median(values: &mut [f64], h: usize) {
+    let src = values.to_vec();
+    let n = src.len();
+    let h = h.min(n.saturating_sub(1) / 2);
+    let mut buf = Vec::with_capacity(2 * h + 1);
+    for (i, v) in values.iter_mut().enumerate() {
+        let r = h.min(i).min(n - 1 - i);
+        buf.clear();
+        buf.extend_from_slice(&src[i - r..=i + r]);
+        buf.sort_by(f64::total_cmp);
+        *v = buf[r];
+    }
+}
+
+/* Smoothing weights for offsets -h..=h: first row of (AᵀA)⁻¹Aᵀ with A[i][k] = iᵏ */
+fn sg_weights(h: usize, order: usize) -> Vec<f64> {
+    let m = order + 1;
+    let offsets: Vec<f64> = (-(h as i64)..=h as i64).map(|i| i as f64).collect();
+    // normal equations AᵀA · c = e0, solved by Gauss–Jordan with partial pivoting
+    let mut a: Vec<Vec<f64>> = (0..m)
+        .map(|r| {
+            let mut row: Vec<f64> = (0..m).map(|c| offsets.iter().map(|x| x.powi((r + c) as i32)).sum()).collect();
+            row.push(if r == 0 { 1.0 } else { 0.0 });
+            row
+        })
+        .collect();
+    for col in 0..m {
+        let pivot = (col..m).max_by(|x, y| a[*x][col].abs().total_cmp(&a[*y][col].abs())).unwrap_or(col);
+        a.swap(col, pivot);
+        let p = a[col][col];
+        if p.abs() < 1e-12 { continue; }
+        for v in a[col].iter_mut() { *v /= p; }
+        let pivot_row = a[col].clone();
+        for (r, row) in a.iter_mut().enumerate() {
+            let f = row[col];
+            if r == col || f == 0.0 { continue; }
+            for (v, p) in row.iter_mut().zip(&pivot_row) { *v -= f * p; }
+        }
+    }
+    let coef: Vec<f64> = a.iter().map(|row| row[m]).collect();
+    offsets.iter().map(|x| coef.iter().enumerate().map(|(k, c)| c * x.powi(k as i32)).sum()).collect()
+}
+
+fn savitzky_golay(values: &mut [f64], h: usize, order: usize) {
+    let src = values.to_vec();
+    let n = src.len();
+    let h = h.min(n.saturating_sub(1) / 2);
+    let mut weights: Vec<Option<Vec<f64>>> = vec![None; h + 1];
+    for (i, v) in values.iter_mut().enumerate() {
+        let r = h.min(i).min(n - 1 - i);
+        if r == 0 { continue; }
+        let w = weights[r].get_or_insert_with(|| sg_weights(r, order.min(2 * r)));
+        *v = w.iter().zip(&src[i - r..=i + r]).map(|(w, x)| w * x).sum();
+    }
+}
+
+fn one_euro(times: &[i64], values: &mut [f64], min_cutoff: f64, beta: f64, d_cutoff: f64) {
+    let alpha = |cutoff: f64, dt: f64| 1.0 / (1.0 + 1.0 / (2.0 * std::f64::consts::PI * cutoff * dt));
+    let Some(&first) = values.first() else { return; };
+    let (mut x_hat, mut dx_hat) = (first, 0.0);
+    for i in 1..values.len() {
+        let dt = (times[i] - times[i - 1]) as f64 / 1_000_000.0;
+        if dt <= 0.0 {
+            values[i] = x_hat;
+            continue;
+        }
+        let dx = (values[i] - x_hat) / dt;
+        dx_hat += alpha(d_cutoff, dt) * (dx - dx_hat);
+        let cutoff = min_cutoff + beta * dx_hat.abs();
+        x_hat += alpha(cutoff, dt) * (values[i] - x_hat);
+        values[i] = x_hat;
+    }
+}
+
+/* Smooth one recording in place (`times` ascending epoch µs); missing coordinates stay missing */
+pub fn series(times: &[i64], values: &mut [Option<f64>], params: &SmoothParams) {
+    let filters: Vec<SmoothFilter> = params.filters.iter().map(SmoothFilter::normalized).collect();
+    if filters.is_empty() { return; }
+    let max_gap = timestamp::from_ms(params.max_gap_ms);
+    let mut start = 0;
+    while start < values.len() {
+        if values[start].is_none() {
+            start += 1;
+            continue;
+        }
+        let mut end = start + 1;
+        while end < values.len() && values[end].is_some() && times[end] - times[end - 1] <= max_gap { end += 1; }
+        let mut seg: Vec<f64> = values[start..end].iter().flatten().copied().collect();
+        for f in &filters { f.apply(&times[start..end], &mut seg); }
+        for (v, s) in values[start..end].iter_mut().zip(seg) { *v = Some(s); }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sg_weights_window_7_order_2() {
+        let want = [-2.0, 3.0, 6.0, 7.0, 6.0, 3.0, -2.0].map(|w| w / 21.0);
+        for (g, w) in sg_weights(3, 2).iter().zip(want) { assert!((g - w).abs() < 1e-12); }
+    }
+
+    #[test]
+    fn savitzky_golay_keeps_quadratics() {
+        let mut v: Vec<f64> = (0..12).map(|i| (i as f64).powi(2) - 3.0 * i as f64).collect();
+        let want = v.clone();
+        savitzky_golay(&mut v, 3, 2);
+        for (g, w) in v.iter().zip(want) { assert!((g - w).abs() < 1e-9); }
+    }
+
+    #[test]
+    fn median_shrinks_at_edges() {
+        let mut v = vec![1.0, 100.0, 3.0, 4.0, 5.0];
+        median(&mut v, 1);
+        assert_eq!(v, vec![1.0, 3.0, 4.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn series_restarts_at_gaps_and_keeps_missing() {
+        let times: Vec<i64> = vec![0, 10_000, 20_000, 500_000, 510_000];
+        let mut values = vec![Some(1.0), Some(100.0), Some(3.0), None, Some(7.0)];
+        let params = SmoothParams { filters: vec![SmoothFilter::Median { window: 3 }], max_gap_ms: 100.0 };
+        series(&times, &mut values, &params);
+        assert_eq!(values, vec![Some(1.0), Some(3.0), Some(3.0), None, Some(7.0)]);
+    }
+
+    #[test]
+    fn oversized_windows_are_capped() {
+        let f = SmoothFilter::SavitzkyGolay { window: usize::MAX, order: usize::MAX }.normalized();
+        assert_eq!(f, SmoothFilter::SavitzkyGolay { window: MAX_WINDOW, order: MAX_SG_ORDER });
+        let times: Vec<i64> = (0..5).map(|i| i * 10_000).collect();
+        let mut values: Vec<Option<f64>> = (0..5).map(|i| Some(i as f64)).collect();
+        let params = SmoothParams { filters: vec![f, SmoothFilter::Median { window: usize::MAX }], max_gap_ms: 100.0 };
+        series(&times, &mut values, &params);
+        assert_eq!(values.len(), 5);
+    }
+}
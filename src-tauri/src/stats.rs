@@ -0,0 +1,611 @@
+/* ──────────────────────────────────────────────────────────────
+Numeric helpers shared by the analysis commands.
+Small dense linear algebra (row-major), OLS, distributions.
+────────────────────────────────────────────────────────────── */
+
+use serde::{Deserialize, Serialize};
+
+pub fn mean(v: &[f64]) -> f64 {
+    if v.is_empty() { return 0.0; }
+    v.iter().sum::<f64>() / v.len() as f64
+}
+
+/* Sample variance (n - 1) */
+pub fn variance(v: &[f64]) -> f64 {
+    if v.len() < 2 { return 0.0; }
+    let m = mean(v);
+    v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (v.len() - 1) as f64
+}
+
+pub fn sd(v: &[f64]) -> f64 {
+    variance(v).sqrt()
+}
+
+/* Percentile with linear interpolation; `sorted` must be ascending */
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() { return f64::NAN; }
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/* ─────────────────────────── Linear algebra ─────────────────────────── */
+
+/* Lower Cholesky factor of an n×n SPD matrix, None if not positive definite */
+pub fn cholesky(a: &[f64], n: usize) -> Option<Vec<f64>> {
+    let mut l = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let mut s = a[i * n + j];
+            for k in 0..j { s -= l[i * n + k] * l[j * n + k]; }
+            if i == j {
+                if s <= 1e-12 { return None; }
+                l[i * n + i] = s.sqrt();
+            } else {
+                l[i * n + j] = s / l[j * n + j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/* Solve L Lᵀ x = b given the Cholesky factor */
+pub fn cholesky_solve(l: &[f64], n: usize, b: &[f64]) -> Vec<f64> {
+    let mut y = vec![0.0; n];
+    for i in 0..n {
+        let mut s = b[i];
+        for k in 0..i { s -= l[i * n + k] * y[k]; }
+        y[i] = s / l[i * n + i];
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let mut s = y[i];
+        for k in i + 1..n { s -= l[k * n + i] * x[k]; }
+        x[i] = s / l[i * n + i];
+    }
+    x
+}
+
+pub fn spd_inverse(a: &[f64], n: usize) -> Option<Vec<f64>> {
+    let l = cholesky(a, n)?;
+    let mut inv = vec![0.0; n * n];
+    let mut e = vec![0.0; n];
+    for j in 0..n {
+        e.iter_mut().for_each(|v| *v = 0.0);
+        e[j] = 1.0;
+        let col = cholesky_solve(&l, n, &e);
+        for i in 0..n { inv[i * n + j] = col[i]; }
+    }
+    Some(inv)
+}
+
+/* XᵀWX and XᵀWy for row-major design rows (W = diag(w)) */
+pub fn cross_products(x: &[Vec<f64>], y: &[f64], w: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let p = x.first().map(|r| r.len()).unwrap_or(0);
+    let mut xtx = vec![0.0; p * p];
+    let mut xty = vec![0.0; p];
+    for ((row, yi), wi) in x.iter().zip(y).zip(w) {
+        for i in 0..p {
+            xty[i] += wi * row[i] * yi;
+            for j in 0..p { xtx[i * p + j] += wi * row[i] * row[j]; }
+        }
+    }
+    (xtx, xty)
+}
+
+#[derive(Debug, Clone)]
+pub struct OlsFit {
+    pub beta: Vec<f64>,
+    pub se: Vec<f64>,
+}
+
+/* Weighted least squares with inverse-variance weights `w` (all 1.0 for OLS);
+   None when the design is rank deficient or has no residual df */
+pub fn wls(x: &[Vec<f64>], y: &[f64], w: &[f64]) -> Option<OlsFit> {
+    let n = y.len();
+    let p = x.first()?.len();
+    if n <= p { return None; }
+    let (xtx, xty) = cross_products(x, y, w);
+    let l = cholesky(&xtx, p)?;
+    let beta = cholesky_solve(&l, p, &xty);
+    let rss: f64 = x
+        .iter()
+        .zip(y)
+        .zip(w)
+        .map(|((row, yi), wi)| {
+            let fit: f64 = row.iter().zip(&beta).map(|(a, b)| a * b).sum();
+            wi * (yi - fit).powi(2)
+        })
+        .sum();
+    let sigma2 = rss / (n - p) as f64;
+    let inv = spd_inverse(&xtx, p)?;
+    let se = (0..p).map(|i| (inv[i * p + i] * sigma2).max(0.0).sqrt()).collect();
+    Some(OlsFit { beta, se })
+}
+
+/* Orthogonal polynomial basis of degree 1..=degree over `x` (as R's poly()):
+   columns are centred, mutually orthogonal and of unit length. */
+pub fn orthogonal_poly(x: &[f64], degree: usize) -> Vec<Vec<f64>> {
+    let n = x.len();
+    let m = mean(x);
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(degree);
+    let constant = vec![1.0 / (n as f64).sqrt(); n];
+    for d in 1..=degree {
+        let mut v: Vec<f64> = x.iter().map(|xi| (xi - m).powi(d as i32)).collect();
+        for prev in std::iter::once(&constant).chain(basis.iter()) {
+            let dot: f64 = v.iter().zip(prev).map(|(a, b)| a * b).sum();
+            v.iter_mut().zip(prev).for_each(|(a, b)| *a -= dot * b);
+        }
+        let norm = v.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if norm > 0.0 { v.iter_mut().for_each(|a| *a /= norm); }
+        basis.push(v);
+    }
+    basis
+}
+
+/* ─────────────────────────── Proportion transforms ─────────────────────────── */
+
+/* Empirical logit of y looks out of n (Barr 2008) and its inverse-variance weight */
+pub fn empirical_logit(y: f64, n: f64) -> (f64, f64) {
+    let (a, b) = (y + 0.5, n - y + 0.5);
+    ((a / b).ln(), 1.0 / (1.0 / a + 1.0 / b))
+}
+
+/* Variance-stabilising arcsine square-root of y / n (radians) */
+pub fn arcsine_sqrt(y: f64, n: f64) -> f64 {
+    (y / n).clamp(0.0, 1.0).sqrt().asin()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProportionScale {
+    #[default]
+    Proportion,
+    Elog,
+    Arcsine,
+}
+
+impl ProportionScale {
+    /* (value, weight) for y out of n; None when n == 0 */
+    pub fn apply(self, y: i64, n: i64) -> Option<(f64, f64)> {
+        if n <= 0 { return None; }
+        let (y, n) = (y as f64, n as f64);
+        Some(match self {
+            ProportionScale::Proportion => (y / n, 1.0),
+            ProportionScale::Elog => empirical_logit(y, n),
+            ProportionScale::Arcsine => (arcsine_sqrt(y, n), 1.0),
+        })
+    }
+}
+
+/* Raw counts with every transformed scale, for outputs that offer `transforms` */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProportionTransforms {
+    pub count: i64,
+    pub n: i64,
+    pub proportion: f64,
+    pub elog: f64,
+    pub elog_weight: f64,
+    pub arcsine: f64,
+}
+
+impl ProportionTransforms {
+    pub fn new(count: i64, n: i64) -> Option<Self> {
+        if n <= 0 { return None; }
+        let (elog, elog_weight) = empirical_logit(count as f64, n as f64);
+        Some(ProportionTransforms {
+            count,
+            n,
+            proportion: count as f64 / n as f64,
+            elog,
+            elog_weight,
+            arcsine: arcsine_sqrt(count as f64, n as f64),
+        })
+    }
+}
+
+/* ─────────────────────────── Distributions ─────────────────────────── */
+
+pub fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation (g = 7, n = 9)
+    const COEF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let mut a = COEF[0];
+    for (i, c) in COEF.iter().enumerate().skip(1) { a += c / (x + i as f64); }
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/* Continued fraction for the regularized incomplete beta (Numerical Recipes betacf) */
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    const EPS: f64 = 1e-14;
+    const FPMIN: f64 = 1e-300;
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < FPMIN { d = FPMIN; }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FPMIN { d = FPMIN; }
+        c = 1.0 + aa / c;
+        if c.abs() < FPMIN { c = FPMIN; }
+        d = 1.0 / d;
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FPMIN { d = FPMIN; }
+        c = 1.0 + aa / c;
+        if c.abs() < FPMIN { c = FPMIN; }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() < EPS { break; }
+    }
+    h
+}
+
+/* Regularized incomplete beta I_x(a, b) */
+pub fn inc_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 { return 0.0; }
+    if x >= 1.0 { return 1.0; }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let front = ln_front.exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_cf(a, b, x) / a
+    } else {
+        1.0 - front * beta_cf(b, a, 1.0 - x) / b
+    }
+}
+
+/* Two-sided p-value for a Student t statistic */
+pub fn t_two_sided_p(t: f64, df: f64) -> f64 {
+    if t.is_nan() || df <= 0.0 { return f64::NAN; }
+    if t.is_infinite() { return 0.0; }
+    inc_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
+/* ─────────────────────────── t tests ─────────────────────────── */
+
+#[derive(Debug, Clone, Copy)]
+pub struct TTest {
+    pub estimate: f64,
+    pub se: f64,
+    pub t: f64,
+    pub df: f64,
+    pub p: f64,
+}
+
+/* One-sample (or paired, on differences) t test against 0 */
+pub fn one_sample_t(v: &[f64]) -> Option<TTest> {
+    if v.len() < 2 { return None; }
+    let estimate = mean(v);
+    let se = sd(v) / (v.len() as f64).sqrt();
+    let df = (v.len() - 1) as f64;
+    let t = if se > 0.0 { estimate / se } else if estimate == 0.0 { 0.0 } else { f64::INFINITY.copysign(estimate) };
+    Some(TTest { estimate, se, t, df, p: t_two_sided_p(t, df) })
+}
+
+/* Welch two-sample t test of mean(a) - mean(b) */
+pub fn welch_t(a: &[f64], b: &[f64]) -> Option<TTest> {
+    if a.len() < 2 || b.len() < 2 { return None; }
+    let (va, vb) = (variance(a) / a.len() as f64, variance(b) / b.len() as f64);
+    let estimate = mean(a) - mean(b);
+    let se = (va + vb).sqrt();
+    let df = if va + vb > 0.0 {
+        (va + vb).powi(2) / (va.powi(2) / (a.len() - 1) as f64 + vb.powi(2) / (b.len() - 1) as f64)
+    } else {
+        (a.len() + b.len() - 2) as f64
+    };
+    let t = if se > 0.0 { estimate / se } else if estimate == 0.0 { 0.0 } else { f64::INFINITY.copysign(estimate) };
+    Some(TTest { estimate, se, t, df, p: t_two_sided_p(t, df) })
+}
+
+/* Regularized upper incomplete gamma Q(a, x) (series / continued fraction, Numerical Recipes) */
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 { return 1.0; }
+    let gln = ln_gamma(a);
+    if x < a + 1.0 {
+        let (mut ap, mut sum) = (a, 1.0 / a);
+        let mut del = sum;
+        for _ in 0..500 {
+            ap += 1.0;
+            del *= x / ap;
+            sum += del;
+            if del.abs() < sum.abs() * 1e-15 { break; }
+        }
+        1.0 - sum * (-x + a * x.ln() - gln).exp()
+    } else {
+        const FPMIN: f64 = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / FPMIN;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < FPMIN { d = FPMIN; }
+            c = b + an / c;
+            if c.abs() < FPMIN { c = FPMIN; }
+            d = 1.0 / d;
+            let del = d * c;
+            h *= del;
+            if (del - 1.0).abs() < 1e-15 { break; }
+        }
+        (-x + a * x.ln() - gln).exp() * h
+    }
+}
+
+/* Upper tail of the chi-square distribution */
+pub fn chi2_sf(x: f64, df: f64) -> f64 {
+    if x.is_nan() || df <= 0.0 { return f64::NAN; }
+    gamma_q(df / 2.0, x / 2.0).clamp(0.0, 1.0)
+}
+
+/* Two-sided p-value for a standard normal statistic */
+pub fn z_two_sided_p(z: f64) -> f64 {
+    if z.is_nan() { return f64::NAN; }
+    gamma_q(0.5, z * z / 2.0).clamp(0.0, 1.0)
+}
+
+/* ─────────────────────────── Optimisation ─────────────────────────── */
+
+/* Nelder–Mead simplex minimisation; returns (argmin, min, converged) */
+pub fn nelder_mead<F: FnMut(&[f64]) -> f64>(mut f: F, start: &[f64], step: f64, tol: f64, max_iter: usize) -> (Vec<f64>, f64, bool) {
+    let n = start.len();
+    let mut simplex: Vec<Vec<f64>> = vec![start.to_vec()];
+    for i in 0..n {
+        let mut p = start.to_vec();
+        p[i] += step;
+        simplex.push(p);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|p| f(p)).collect();
+    let mut converged = false;
+    for _ in 0..max_iter {
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+        if (values[n] - values[0]).abs() <= tol * (values[0].abs() + tol) {
+            converged = true;
+            break;
+        }
+        let centroid: Vec<f64> = (0..n).map(|j| simplex[..n].iter().map(|p| p[j]).sum::<f64>() / n as f64).collect();
+        let towards = |coef: f64| -> Vec<f64> {
+            centroid.iter().zip(&simplex[n]).map(|(c, w)| c + coef * (w - c)).collect()
+        };
+        let reflected = towards(-1.0);
+        let fr = f(&reflected);
+        if fr < values[0] {
+            let expanded = towards(-2.0);
+            let fe = f(&expanded);
+            if fe < fr { simplex[n] = expanded; values[n] = fe; } else { simplex[n] = reflected; values[n] = fr; }
+        } else if fr < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = fr;
+        } else {
+            let contracted = if fr < values[n] { towards(-0.5) } else { towards(0.5) };
+            let fc = f(&contracted);
+            if fc < values[n].min(fr) {
+                simplex[n] = contracted;
+                values[n] = fc;
+            } else {
+                for i in 1..=n {
+                    simplex[i] = simplex[0].iter().zip(&simplex[i]).map(|(b, p)| b + 0.5 * (p - b)).collect();
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+    let best = (0..=n).min_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap_or(0);
+    (simplex[best].clone(), values[best], converged)
+}
+
+/* ─────────────────────────── Multiple comparisons ─────────────────────────── */
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Correction {
+    #[default]
+    None,
+    Bonferroni,
+    Holm,
+    /* Benjamini–Hochberg false discovery rate */
+    Fdr,
+}
+
+/* Adjusted p-values (same order as `p`); NaN entries stay NaN and don't count towards m */
+pub fn adjust_p(p: &[f64], method: Correction) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..p.len()).filter(|&i| !p[i].is_nan()).collect();
+    order.sort_by(|&a, &b| p[a].total_cmp(&p[b]));
+    let m = order.len() as f64;
+    let mut out = p.to_vec();
+    match method {
+        Correction::None => {}
+        Correction::Bonferroni => {
+            for &i in &order { out[i] = (p[i] * m).min(1.0); }
+        }
+        Correction::Holm => {
+            let mut running: f64 = 0.0;
+            for (k, &i) in order.iter().enumerate() {
+                running = running.max((m - k as f64) * p[i]);
+                out[i] = running.min(1.0);
+            }
+        }
+        Correction::Fdr => {
+            let mut running: f64 = 1.0;
+            for (k, &i) in order.iter().enumerate().rev() {
+                running = running.min(m / (k + 1) as f64 * p[i]);
+                out[i] = running.min(1.0);
+            }
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TfceParams {
+    /* extent exponent */
+    #[serde(default = "default_tfce_e")]
+    pub e: f64,
+    /* height exponent */
+    #[serde(default = "default_tfce_h")]
+    pub h: f64,
+    /* number of threshold steps between 0 and max |stat| */
+    #[serde(default = "default_tfce_steps")]
+    pub steps: usize,
+}
+
+fn default_tfce_e() -> f64 { 0.5 }
+fn default_tfce_h() -> f64 { 2.0 }
+fn default_tfce_steps() -> usize { 100 }
+
+/* upper bound on threshold steps (user input; each step sweeps the whole statistic) */
+const MAX_TFCE_STEPS: usize = 10_000;
+
+impl Default for TfceParams {
+    fn default() -> Self {
+        TfceParams { e: default_tfce_e(), h: default_tfce_h(), steps: default_tfce_steps() }
+    }
+}
+
+impl TfceParams {
+    pub fn check(&self) -> Result<(), String> {
+        if self.steps > MAX_TFCE_STEPS {
+            return Err(format!("TFCE steps must be at most {MAX_TFCE_STEPS} (got {})", self.steps));
+        }
+        Ok(())
+    }
+}
+
+/* Threshold-free cluster enhancement of a 1-D statistic (Smith & Nichols 2009).
+   Positive and negative clusters are enhanced separately; the sign is kept.
+   `dh` fixes the threshold step so permuted maps integrate on the same grid. */
+pub fn tfce(stat: &[f64], dh: f64, params: &TfceParams) -> Vec<f64> {
+    let mut out = vec![0.0; stat.len()];
+    if dh <= 0.0 || !dh.is_finite() { return out; }
+    for sign in [1.0, -1.0] {
+        let v: Vec<f64> = stat.iter().map(|s| if s.is_finite() { (s * sign).max(0.0) } else { 0.0 }).collect();
+        let top = v.iter().cloned().fold(0.0, f64::max);
+        let mut h = dh;
+        while h <= top {
+            let mut i = 0;
+            while i < v.len() {
+                if v[i] < h { i += 1; continue; }
+                let start = i;
+                while i < v.len() && v[i] >= h { i += 1; }
+                let score = ((i - start) as f64).powf(params.e) * h.powf(params.h) * dh;
+                for o in &mut out[start..i] { *o += sign * score; }
+            }
+            h += dh;
+        }
+    }
+    out
+}
+
+/* Threshold step for `tfce`: max |stat| split into `steps` */
+pub fn tfce_step(stat: &[f64], steps: usize) -> f64 {
+    let top = stat.iter().filter(|s| s.is_finite()).map(|s| s.abs()).fold(0.0, f64::max);
+    top / steps.max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() <= tol
+    }
+
+    #[test]
+    fn inc_beta_known_values() {
+        // I_0.4(2, 3) = P(Binomial(4, 0.4) >= 2)
+        assert!(close(inc_beta(2.0, 3.0, 0.4), 0.5248, 1e-10));
+        assert!(close(inc_beta(1.0, 1.0, 0.3), 0.3, 1e-12));
+        assert_eq!(inc_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(inc_beta(2.0, 3.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn t_p_values() {
+        assert!(close(t_two_sided_p(2.228, 10.0), 0.05, 1e-3));
+        assert!(close(t_two_sided_p(0.0, 5.0), 1.0, 1e-12));
+        assert!(t_two_sided_p(f64::NAN, 5.0).is_nan());
+    }
+
+    #[test]
+    fn gamma_q_known_values() {
+        // Q(1, x) = e^-x, on both the series and the continued-fraction branch
+        assert!(close(gamma_q(1.0, 0.5), (-0.5f64).exp(), 1e-12));
+        assert!(close(gamma_q(1.0, 2.0), (-2.0f64).exp(), 1e-12));
+        assert!(close(chi2_sf(3.841459, 1.0), 0.05, 1e-6));
+        assert!(close(z_two_sided_p(1.959964), 0.05, 1e-6));
+    }
+
+    #[test]
+    fn adjust_p_fixed_vector() {
+        let p = [0.01, 0.04, 0.03, 0.005];
+        let check = |got: Vec<f64>, want: [f64; 4]| {
+            for (g, w) in got.iter().zip(want) { assert!(close(*g, w, 1e-12), "{got:?} != {want:?}"); }
+        };
+        check(adjust_p(&p, Correction::None), p);
+        check(adjust_p(&p, Correction::Bonferroni), [0.04, 0.16, 0.12, 0.02]);
+        check(adjust_p(&p, Correction::Holm), [0.03, 0.06, 0.06, 0.02]);
+        check(adjust_p(&p, Correction::Fdr), [0.02, 0.04, 0.04, 0.02]);
+    }
+
+    #[test]
+    fn adjust_p_skips_nan() {
+        let out = adjust_p(&[0.01, f64::NAN, 0.02], Correction::Bonferroni);
+        assert!(close(out[0], 0.02, 1e-12) && out[1].is_nan() && close(out[2], 0.04, 1e-12));
+    }
+
+    #[test]
+    fn tfce_single_peak() {
+        let stat = [0.0, 1.0, 2.0, 1.0, 0.0];
+        let params = TfceParams { e: 0.5, h: 2.0, steps: 2 };
+        let out = tfce(&stat, tfce_step(&stat, params.steps), &params);
+        // h = 1: one cluster of 3 (√3 · 1² · 1); h = 2: one cluster of 1 (1 · 2² · 1)
+        let want = [0.0, 3f64.sqrt(), 3f64.sqrt() + 4.0, 3f64.sqrt(), 0.0];
+        for (g, w) in out.iter().zip(want) { assert!(close(*g, w, 1e-12)); }
+        let neg: Vec<f64> = stat.iter().map(|s| -s).collect();
+        let out_neg = tfce(&neg, tfce_step(&neg, params.steps), &params);
+        for (g, w) in out_neg.iter().zip(want) { assert!(close(*g, -w, 1e-12)); }
+    }
+
+    #[test]
+    fn orthogonal_poly_three_points() {
+        let basis = orthogonal_poly(&[1.0, 2.0, 3.0], 2);
+        let (r2, r6) = (2f64.sqrt(), 6f64.sqrt());
+        for (g, w) in basis[0].iter().zip([-1.0 / r2, 0.0, 1.0 / r2]) { assert!(close(*g, w, 1e-12)); }
+        for (g, w) in basis[1].iter().zip([1.0 / r6, -2.0 / r6, 1.0 / r6]) { assert!(close(*g, w, 1e-12)); }
+    }
+
+    #[test]
+    fn nelder_mead_quadratic() {
+        let (x, fx, converged) = nelder_mead(|p| (p[0] - 3.0).powi(2) + (p[1] + 1.0).powi(2) + 2.0, &[0.0, 0.0], 1.0, 1e-14, 5000);
+        assert!(converged);
+        assert!(close(x[0], 3.0, 1e-4) && close(x[1], -1.0, 1e-4));
+        assert!(close(fx, 2.0, 1e-8));
+    }
+}
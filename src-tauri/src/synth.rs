@@ -0,0 +1,673 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::aoi::AOI_CODE_TO_BOX;
+use crate::indexes::RECOMMENDED;
+use crate::vocab::{ANIMALS, OBJECTS, ONLY_DAK, ONLY_JINGHAI, ONLY_ZAA, VERB};
+
+/* ──────────────────────────────────────────────────────────────
+Synthetic study DB for tests and demos (the real one holds children's
+data). Same tables and columns as eye_tracking.db; sentences come from
+the README vocabulary. Each trial is a chain of fixations and saccades
+over a three-animal scene, with blinks and track loss, sampled at a fixed
+rate. The planted effect is the share of target looks among target +
+competitor looks (correct_AOIs vs the other AOI columns, i.e. the default
+blue/red sets) over time, per condition; the ground truth is stored in
+synthetic_truth / synthetic_participants.
+────────────────────────────────────────────────────────────── */
+
+const SCREEN_W: f64 = 1920.0;
+const SCREEN_H: f64 = 1080.0;
+/* spoken sentence starts this far into the video */
+const SENTENCE_START_SEC: f64 = 1.0;
+const TRIAL_GAP_MS: f64 = 1500.0;
+const FIXATION_JITTER_PX: f64 = 12.0;
+/* upper bound on video_ms / picture_ms (user input; every trial is simulated sample by sample) */
+const MAX_PHASE_MS: f64 = 60_000.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynthCondition {
+    pub name: String,
+    /* "subject" (Szinghai) or "verb" (Vzinghai) */
+    pub only_position: String,
+    /* 淨係, 得, 咋 or "" for no only-word */
+    pub morpheme: String,
+    #[serde(default = "default_truth")]
+    pub truth_value: String,
+    /* P(target | target or competitor) before the effect */
+    pub baseline: f64,
+    /* ... and after it (population value, logistic rise) */
+    pub target_share: f64,
+    /* midpoint of the rise, ms from trial onset */
+    pub onset_ms: f64,
+    /* logistic scale of the rise, ms */
+    pub rise_ms: f64,
+}
+
+fn default_truth() -> String { "T".to_string() }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SynthConfig {
+    pub participants: usize,
+    pub items_per_condition: usize,
+    pub conditions: Vec<SynthCondition>,
+    pub seed: Option<u64>,
+    pub sample_rate_hz: f64,
+    pub video_ms: f64,
+    pub picture_ms: f64,
+    /* between-participant / between-item SD of the target share, logit scale */
+    pub participant_sd: f64,
+    pub item_sd: f64,
+    pub blink_rate_hz: f64,
+    /* chance that a trial contains one longer track-loss episode */
+    pub track_loss_prob: f64,
+    /* the last `non_qac` participants get is_qac = 0 */
+    pub non_qac: usize,
+    pub participant_prefix: String,
+}
+
+impl Default for SynthConfig {
+    fn default() -> Self {
+        let condition = |name: &str, only_position: &str, target_share: f64, onset_ms: f64| SynthCondition {
+            name: name.to_string(),
+            only_position: only_position.to_string(),
+            morpheme: ONLY_JINGHAI.to_string(),
+            truth_value: default_truth(),
+            baseline: 0.5,
+            target_share,
+            onset_ms,
+            rise_ms: 250.0,
+        };
+        SynthConfig {
+            participants: 12,
+            items_per_condition: 8,
+            conditions: vec![condition("Szinghai", "subject", 0.6, 2600.0), condition("Vzinghai", "verb", 0.8, 2200.0)],
+            seed: None,
+            sample_rate_hz: 60.0,
+            video_ms: 4500.0,
+            picture_ms: 3000.0,
+            participant_sd: 0.4,
+            item_sd: 0.2,
+            blink_rate_hz: 0.3,
+            track_loss_prob: 0.05,
+            non_qac: 2,
+            participant_prefix: "SYN".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SynthSummary {
+    pub path: String,
+    pub seed: u64,
+    pub participants: usize,
+    pub tests: usize,
+    pub recordings: usize,
+    pub gaze_rows: usize,
+    pub valid_pct: f64,
+}
+
+/* ─────────────────────────── Scene ─────────────────────────── */
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Rect {
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        x >= self.x0 && x <= self.x1 && y >= self.y0 && y <= self.y1
+    }
+
+    /* uniform point in the central 60% of the box */
+    fn sample(&self, rng: &mut StdRng) -> (f64, f64) {
+        let (cx, cy) = ((self.x0 + self.x1) / 2.0, (self.y0 + self.y1) / 2.0);
+        let (hw, hh) = ((self.x1 - self.x0) * 0.3, (self.y1 - self.y0) * 0.3);
+        (cx + rng.gen_range(-hw..=hw), cy + rng.gen_range(-hh..=hh))
+    }
+}
+
+/* AOI codes per animal slot: (animal, first object, second object) */
+const SLOTS: [(&str, &str, &str); 3] = [("S1", "O1A", "O2A"), ("S2", "O1B", "O2B"), ("S3", "O3A", "O3B")];
+
+/* Three animals across the screen, each with two objects below it */
+fn layout() -> Vec<(&'static str, Rect)> {
+    let mut out = Vec::new();
+    for (i, (s, o1, o2)) in SLOTS.iter().enumerate() {
+        let cx = SCREEN_W * (i as f64 * 2.0 + 1.0) / 6.0;
+        out.push((*s, Rect { x0: cx - 180.0, y0: 230.0, x1: cx + 180.0, y1: 530.0 }));
+        out.push((*o1, Rect { x0: cx - 190.0, y0: 640.0, x1: cx - 10.0, y1: 820.0 }));
+        out.push((*o2, Rect { x0: cx + 10.0, y0: 640.0, x1: cx + 190.0, y1: 820.0 }));
+    }
+    out
+}
+
+fn box_name(code: &str) -> &'static str {
+    AOI_CODE_TO_BOX.iter().find(|(c, _)| *c == code).map(|(_, b)| *b).unwrap_or("other")
+}
+
+fn classify(scene: &[(&'static str, Rect)], p: Option<(f64, f64)>) -> &'static str {
+    let Some((x, y)) = p else { return "missing"; };
+    if !(0.0..=SCREEN_W).contains(&x) || !(0.0..=SCREEN_H).contains(&y) { return "out_of_screen"; }
+    scene.iter().find(|(_, r)| r.contains((x, y))).map(|(c, _)| box_name(c)).unwrap_or("other")
+}
+
+/* One test: its sentence, word windows and which AOI codes play which role */
+struct Item {
+    test_name: String,
+    condition: usize,
+    sentence: String,
+    words: Vec<(String, f64, f64)>,
+    image_name: String,
+    self_codes: Vec<&'static str>,
+    target: Vec<&'static str>,
+    competitors: Vec<&'static str>,
+    potential: Vec<&'static str>,
+    /* AOIs listed in no catalog column */
+    neutral: Vec<&'static str>,
+    offset: f64,
+}
+
+fn sentence_words(cond: &SynthCondition, animal: &str, measure: &str, object: &str) -> Vec<String> {
+    let only = cond.morpheme.trim();
+    let mut w: Vec<String> = Vec::new();
+    if only == ONLY_DAK || (only == ONLY_JINGHAI && cond.only_position == "subject") { w.push(only.to_string()); }
+    w.push(animal.to_string());
+    if only == ONLY_JINGHAI && cond.only_position != "subject" { w.push(only.to_string()); }
+    w.extend([VERB.to_string(), measure.to_string(), object.to_string()]);
+    if only == ONLY_ZAA { w.push(only.to_string()); }
+    w
+}
+
+fn make_item(rng: &mut StdRng, cfg: &SynthConfig, condition: usize, index: usize, n: usize) -> Item {
+    let cond = &cfg.conditions[condition];
+    let slot = n % 3;
+    let (animal, (object, measures)) = (ANIMALS[n % ANIMALS.len()], OBJECTS[(n * 5 + condition) % OBJECTS.len()]);
+    let measure = measures[0];
+    let words = sentence_words(cond, animal, measure, object);
+
+    let mut t = SENTENCE_START_SEC;
+    let timed: Vec<(String, f64, f64)> = words
+        .iter()
+        .map(|w| {
+            let len = 0.18 * w.chars().count() as f64 + rng.gen_range(0.05..0.15);
+            let out = (w.clone(), t, t + len);
+            t += len + 0.04;
+            out
+        })
+        .collect();
+
+    let (m, c, p) = (SLOTS[slot], SLOTS[(slot + 1) % 3], SLOTS[(slot + 2) % 3]);
+    Item {
+        test_name: format!("{}_{}{}", cond.name, cond.truth_value, index + 1),
+        condition,
+        sentence: words.concat(),
+        words: timed,
+        image_name: format!("{}_{}{}.png", cond.name, cond.truth_value, index + 1),
+        self_codes: vec![m.0, m.1],
+        target: vec![m.2],
+        competitors: vec![c.0, c.1],
+        potential: vec![p.0],
+        neutral: vec![c.2, p.1, p.2],
+        offset: normal(rng) * cfg.item_sd,
+    }
+}
+
+/* ─────────────────────────── Gaze model ─────────────────────────── */
+
+fn normal(rng: &mut StdRng) -> f64 {
+    let (u1, u2): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen());
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+fn logit(p: f64) -> f64 {
+    let p = p.clamp(1e-6, 1.0 - 1e-6);
+    (p / (1.0 - p)).ln()
+}
+
+fn logistic(x: f64) -> f64 { 1.0 / (1.0 + (-x).exp()) }
+
+/* Population target share at `t_ms` into the trial */
+fn share_at(cond: &SynthCondition, t_ms: f64) -> f64 {
+    let w = logistic((t_ms - cond.onset_ms) / cond.rise_ms.max(1.0));
+    cond.baseline + (cond.target_share - cond.baseline) * w
+}
+
+struct Segment {
+    start: f64,
+    end: f64,
+    from: (f64, f64),
+    to: (f64, f64),
+}
+
+/* Where the next fixation lands; `share` is P(target | target or competitor) */
+fn next_fixation(rng: &mut StdRng, scene: &[(&'static str, Rect)], item: &Item, share: f64) -> (f64, f64) {
+    let rect = |code: &str| scene.iter().find(|(c, _)| *c == code).map(|(_, r)| *r);
+    let u: f64 = rng.gen();
+    if u < 0.02 {
+        // glance off screen
+        return (rng.gen_range(-200.0..0.0), rng.gen_range(0.0..SCREEN_H));
+    }
+    if u < 0.10 {
+        // blank screen area above the animals
+        return (rng.gen_range(100.0..SCREEN_W - 100.0), rng.gen_range(40.0..180.0));
+    }
+    let pool: Vec<&'static str> = if u < 0.18 {
+        item.neutral.clone()
+    } else if rng.gen::<f64>() < share {
+        item.target.clone()
+    } else {
+        item.self_codes.iter().chain(&item.competitors).chain(&item.potential).copied().collect()
+    };
+    let code = pool.choose(rng).copied().unwrap_or("S1");
+    rect(code).map(|r| r.sample(rng)).unwrap_or((SCREEN_W / 2.0, SCREEN_H / 2.0))
+}
+
+/* Sample times (ms from onset) with gaze positions; None = lost (blink / track loss) */
+fn simulate_trial(
+    rng: &mut StdRng,
+    cfg: &SynthConfig,
+    scene: &[(&'static str, Rect)],
+    item: &Item,
+    person_offset: f64,
+) -> Vec<(f64, Option<(f64, f64)>)> {
+    let cond = &cfg.conditions[item.condition];
+    let trial_ms = cfg.video_ms + cfg.picture_ms;
+
+    let mut segments: Vec<Segment> = Vec::new();
+    let (mut t, mut pos) = (0.0, (SCREEN_W / 2.0, SCREEN_H / 2.0));
+    while t < trial_ms {
+        let share = logistic(logit(share_at(cond, t)) + person_offset + item.offset);
+        let next = next_fixation(rng, scene, item, share);
+        let dist = ((next.0 - pos.0).powi(2) + (next.1 - pos.1).powi(2)).sqrt();
+        let saccade = 20.0 + dist / 40.0;
+        segments.push(Segment { start: t, end: t + saccade, from: pos, to: next });
+        t += saccade;
+        let fixation = (240f64.ln() + 0.4 * normal(rng)).exp().clamp(80.0, 1200.0);
+        segments.push(Segment { start: t, end: t + fixation, from: next, to: next });
+        t += fixation;
+        pos = next;
+    }
+
+    let mut lost: Vec<(f64, f64)> = Vec::new();
+    let rate = cfg.blink_rate_hz.max(0.0) / 1000.0;
+    if rate > 0.0 {
+        let mut b = -rng.gen_range(f64::EPSILON..1.0f64).ln() / rate;
+        while b < trial_ms {
+            let len = rng.gen_range(80.0..250.0);
+            lost.push((b, b + len));
+            b += len - rng.gen_range(f64::EPSILON..1.0f64).ln() / rate;
+        }
+    }
+    if rng.gen::<f64>() < cfg.track_loss_prob {
+        let start = rng.gen_range(0.0..trial_ms);
+        lost.push((start, start + rng.gen_range(400.0..1800.0)));
+    }
+
+    let dt = 1000.0 / cfg.sample_rate_hz.max(1.0);
+    let mut out = Vec::with_capacity((trial_ms / dt) as usize + 1);
+    let mut seg = 0;
+    let mut s = 0.0;
+    while s < trial_ms {
+        while seg + 1 < segments.len() && segments[seg].end <= s { seg += 1; }
+        let g = &segments[seg];
+        let f = if g.end > g.start { ((s - g.start) / (g.end - g.start)).clamp(0.0, 1.0) } else { 1.0 };
+        let p = (
+            g.from.0 + (g.to.0 - g.from.0) * f + normal(rng) * FIXATION_JITTER_PX,
+            g.from.1 + (g.to.1 - g.from.1) * f + normal(rng) * FIXATION_JITTER_PX,
+        );
+        let gone = lost.iter().any(|(a, b)| s >= *a && s < *b);
+        // timestamps jitter slightly, like a real tracker clock
+        out.push((s + normal(rng) * 0.3, (!gone).then_some(p)));
+        s += dt;
+    }
+    out
+}
+
+/* "YYYY-MM-DD HH:MM:SS.fff" for `ms` after midnight of `day` */
+fn exact_time(day: &str, ms: f64) -> String {
+    let ms = ms.max(0.0).round() as u64;
+    format!("{day} {:02}:{:02}:{:02}.{:03}", ms / 3_600_000 % 24, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+/* ─────────────────────────── Writing ─────────────────────────── */
+
+const SCHEMA: &str = r#"
+CREATE TABLE gaze_data (
+    "Exact time" TEXT,
+    "Gaze point X" REAL,
+    "Gaze point Y" REAL,
+    Box TEXT,
+    "Presented Media name" TEXT,
+    "Timeline name" TEXT,
+    "Participant name" TEXT,
+    "Recording name" TEXT,
+    "Test Name" TEXT
+);
+CREATE TABLE test_catalog (
+    test_name TEXT,
+    sentence TEXT,
+    "group" TEXT,
+    self_AOIs TEXT,
+    correct_AOIs TEXT,
+    potentially_correct_AOIs TEXT,
+    incorrect_AOIs TEXT,
+    correct_NULL TEXT,
+    potentially_correct_NULL TEXT,
+    incorrect_NULL TEXT,
+    image_path TEXT,
+    "Image name" TEXT,
+    truth_value TEXT,
+    only_position TEXT,
+    morpheme TEXT,
+    series TEXT,
+    case_no INTEGER,
+    timeline TEXT,
+    word_windows_json TEXT
+);
+CREATE TABLE participants (participant TEXT PRIMARY KEY, is_qac INTEGER NOT NULL);
+CREATE TABLE recordings (
+    Recording TEXT,
+    Participant TEXT,
+    Timeline TEXT,
+    "Recording date" TEXT,
+    "Recording duration" INTEGER,
+    "Gaze samples" INTEGER
+);
+CREATE TABLE test_group (
+    test_name TEXT,
+    "Timeline name" TEXT,
+    "Recording name" TEXT,
+    "Participant name" TEXT,
+    "Presented Media name" TEXT,
+    duration_seconds REAL
+);
+CREATE TABLE synthetic_truth (
+    test_name TEXT PRIMARY KEY,
+    condition TEXT,
+    baseline REAL,
+    target_share REAL,
+    onset_ms REAL,
+    rise_ms REAL,
+    item_offset REAL
+);
+CREATE TABLE synthetic_participants (participant TEXT PRIMARY KEY, logit_offset REAL);
+CREATE TABLE synthetic_meta (key TEXT PRIMARY KEY, value TEXT);
+"#;
+
+fn check(cfg: &SynthConfig) -> Result<(), String> {
+    if cfg.participants == 0 || cfg.items_per_condition == 0 || cfg.conditions.is_empty() {
+        return Err("need at least one participant, condition and item".to_string());
+    }
+    if !(1.0..=2000.0).contains(&cfg.sample_rate_hz) {
+        return Err("sample_rate_hz must be within 1..=2000".to_string());
+    }
+    for (name, ms) in [("video_ms", cfg.video_ms), ("picture_ms", cfg.picture_ms)] {
+        if !(ms > 0.0 && ms <= MAX_PHASE_MS) {
+            return Err(format!("{name} must be within 0..={MAX_PHASE_MS} (got {ms})"));
+        }
+    }
+    for c in &cfg.conditions {
+        if c.name.trim().is_empty() { return Err("condition names must not be empty".to_string()); }
+        if ![c.baseline, c.target_share].iter().all(|p| (0.0..=1.0).contains(p)) {
+            return Err(format!("condition '{}': baseline / target_share must be proportions", c.name));
+        }
+        if !(c.onset_ms.is_finite() && c.rise_ms.is_finite() && c.rise_ms > 0.0) {
+            return Err(format!("condition '{}': onset_ms must be finite and rise_ms positive", c.name));
+        }
+    }
+    Ok(())
+}
+
+/* Write a synthetic DB to `path` (must not exist) */
+pub fn generate(path: &Path, cfg: &SynthConfig) -> Result<SynthSummary, String> {
+    check(cfg)?;
+    if path.exists() { return Err(format!("{} already exists", path.display())); }
+    if let Some(parent) = path.parent() { let _ = fs::create_dir_all(parent); }
+    let seed = cfg.seed.unwrap_or_else(|| rand::random::<u64>() >> 11);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let scene = layout();
+
+    let mut items: Vec<Item> = Vec::new();
+    for c in 0..cfg.conditions.len() {
+        for i in 0..cfg.items_per_condition {
+            let n = items.len();
+            items.push(make_item(&mut rng, cfg, c, i, n));
+        }
+    }
+
+    let mut conn = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+
+    let codes = |v: &[&str]| v.join(", ");
+    for item in &items {
+        let cond = &cfg.conditions[item.condition];
+        let words: Vec<serde_json::Value> = item
+            .words
+            .iter()
+            .map(|(w, s, e)| serde_json::json!({ "chinese_word": w, "start_sec": (s * 1000.0).round() / 1000.0, "end_sec": (e * 1000.0).round() / 1000.0 }))
+            .collect();
+        tx.execute(
+            r#"INSERT INTO test_catalog (test_name, sentence, "group", self_AOIs, correct_AOIs, potentially_correct_AOIs,
+                   incorrect_AOIs, correct_NULL, potentially_correct_NULL, incorrect_NULL, image_path, "Image name",
+                   truth_value, only_position, morpheme, series, case_no, timeline, word_windows_json)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, '', '', '', NULL, ?8, ?9, ?10, ?11, 'synthetic', ?12, 'Timeline1', ?13)"#,
+            params![
+                item.test_name,
+                item.sentence,
+                cond.name,
+                codes(&item.self_codes),
+                codes(&item.target),
+                codes(&item.potential),
+                codes(&item.competitors),
+                item.image_name,
+                cond.truth_value,
+                cond.only_position,
+                cond.morpheme,
+                items.iter().filter(|i| i.condition == item.condition).position(|i| i.test_name == item.test_name).unwrap_or(0) as i64 + 1,
+                serde_json::Value::Array(words).to_string(),
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO synthetic_truth VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![item.test_name, cond.name, cond.baseline, cond.target_share, cond.onset_ms, cond.rise_ms, item.offset],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let (mut gaze_rows, mut valid_rows) = (0usize, 0usize);
+    {
+        let mut gaze = tx
+            .prepare(
+                r#"INSERT INTO gaze_data ("Exact time", "Gaze point X", "Gaze point Y", Box, "Presented Media name",
+                       "Timeline name", "Participant name", "Recording name", "Test Name")
+                   VALUES (?1, ?2, ?3, ?4, ?5, 'Timeline1', ?6, ?7, ?8)"#,
+            )
+            .map_err(|e| e.to_string())?;
+        let mut group = tx
+            .prepare(r#"INSERT INTO test_group VALUES (?1, 'Timeline1', ?2, ?3, ?4, ?5)"#)
+            .map_err(|e| e.to_string())?;
+
+        for p in 0..cfg.participants {
+            let participant = format!("{}{:03}", cfg.participant_prefix, p + 1);
+            let recording = format!("{participant}_Recording1");
+            let day = format!("2024-03-{:02}", 4 + p % 25);
+            let offset = normal(&mut rng) * cfg.participant_sd;
+            let is_qac = i64::from(p + cfg.non_qac < cfg.participants);
+            tx.execute("INSERT INTO participants VALUES (?1, ?2)", params![participant, is_qac]).map_err(|e| e.to_string())?;
+            tx.execute("INSERT INTO synthetic_participants VALUES (?1, ?2)", params![participant, offset]).map_err(|e| e.to_string())?;
+
+            let mut order: Vec<usize> = (0..items.len()).collect();
+            order.shuffle(&mut rng);
+            let session_start = 9.0 * 3_600_000.0 + rng.gen_range(0.0..3_600_000.0);
+            let mut clock = session_start;
+            let (mut n, mut n_valid) = (0usize, 0usize);
+            for &i in &order {
+                let item = &items[i];
+                for (t, pos) in simulate_trial(&mut rng, cfg, &scene, item, offset) {
+                    let media = if t < cfg.video_ms { format!("{}.mp4", item.test_name) } else { item.image_name.clone() };
+                    let (x, y) = match pos { Some((x, y)) => (Some(x.round()), Some(y.round())), None => (None, None) };
+                    let b = classify(&scene, pos);
+                    gaze.execute(params![exact_time(&day, clock + t), x, y, b, media, participant, recording, item.test_name])
+                        .map_err(|e| e.to_string())?;
+                    n += 1;
+                    if pos.is_some() { n_valid += 1; }
+                }
+                group.execute(params![item.test_name, recording, participant, format!("{}.mp4", item.test_name), cfg.video_ms / 1000.0])
+                    .map_err(|e| e.to_string())?;
+                group.execute(params![item.test_name, recording, participant, item.image_name, cfg.picture_ms / 1000.0])
+                    .map_err(|e| e.to_string())?;
+                clock += cfg.video_ms + cfg.picture_ms + TRIAL_GAP_MS;
+            }
+            let valid_pct = if n > 0 { (n_valid as f64 / n as f64 * 100.0).round() as i64 } else { 0 };
+            tx.execute(
+                "INSERT INTO recordings VALUES (?1, ?2, 'Timeline1', ?3, ?4, ?5)",
+                params![recording, participant, day, (clock - session_start).round() as i64, valid_pct],
+            )
+            .map_err(|e| e.to_string())?;
+            gaze_rows += n;
+            valid_rows += n_valid;
+        }
+    }
+
+    for (name, table, cols) in RECOMMENDED {
+        let cols_sql = cols.iter().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", ");
+        tx.execute_batch(&format!("CREATE INDEX \"{name}\" ON {table} ({cols_sql});")).map_err(|e| e.to_string())?;
+    }
+    let config_json = serde_json::to_string(&SynthConfig { seed: Some(seed), ..cfg.clone() }).map_err(|e| e.to_string())?;
+    tx.execute("INSERT INTO synthetic_meta VALUES ('config', ?1)", params![config_json]).map_err(|e| e.to_string())?;
+    tx.execute("INSERT INTO synthetic_meta VALUES ('app_version', ?1)", params![env!("CARGO_PKG_VERSION")]).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    conn.execute_batch("ANALYZE;").map_err(|e| e.to_string())?;
+
+    Ok(SynthSummary {
+        path: path.to_string_lossy().to_string(),
+        seed,
+        participants: cfg.participants,
+        tests: items.len(),
+        recordings: cfg.participants,
+        gaze_rows,
+        valid_pct: if gaze_rows > 0 { valid_rows as f64 / gaze_rows as f64 * 100.0 } else { 0.0 },
+    })
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Generate a synthetic DB at `path` (open it with open_database afterwards) */
+#[tauri::command]
+pub async fn generate_synthetic_db(path: String, config: Option<SynthConfig>, overwrite: Option<bool>) -> Result<SynthSummary, String> {
+    let path = Path::new(path.trim()).to_path_buf();
+    if overwrite.unwrap_or(false) && path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    let cfg = config.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || generate(&path, &cfg)).await.map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timestamp;
+    use crate::validate::{validate, Severity};
+    use std::collections::{BTreeMap, HashMap};
+
+    fn codes(list: &str) -> Vec<&'static str> {
+        list.split(',').map(str::trim).filter(|c| !c.is_empty()).map(box_name).collect()
+    }
+
+    #[test]
+    fn generated_db_validates_and_plants_the_effect() {
+        let path = std::env::temp_dir().join(format!("synth-test-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let cfg = SynthConfig { participants: 12, items_per_condition: 4, seed: Some(2), participant_sd: 0.0, item_sd: 0.0, ..SynthConfig::default() };
+        let summary = generate(&path, &cfg).expect("generate");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+
+        // schema: every expected table and column, with the expected affinity; the only
+        // warnings are NULLs the data is meant to have (track loss, unused *_NULL columns)
+        let report = validate(&conn, &path, true).unwrap();
+        assert!(report.ok);
+        for t in &report.tables {
+            assert!(t.present, "{}", t.table);
+            for c in &t.columns {
+                // present under its exact name (affinity problems surface as issues below)
+                assert!(c.declared_type.is_some() && c.found_as.is_none(), "{}.{}", t.table, c.name);
+                assert_eq!(c.mismatched_values.unwrap_or(0), 0, "{}.{}", t.table, c.name);
+            }
+        }
+        assert!(report.orphans.iter().all(|o| o.count == 0), "{:?}", report.orphans);
+        let serious: Vec<_> = report.issues.iter().filter(|i| i.severity != Severity::Info && i.code != "null_values").collect();
+        assert!(serious.is_empty(), "{serious:?}");
+
+        // row counts
+        let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap() as usize;
+        assert_eq!(summary.seed, cfg.seed.unwrap());
+        assert_eq!(count("SELECT COUNT(*) FROM gaze_data"), summary.gaze_rows);
+        assert_eq!(count("SELECT COUNT(*) FROM test_catalog"), summary.tests);
+        assert_eq!(summary.tests, cfg.conditions.len() * cfg.items_per_condition);
+        assert_eq!(count("SELECT COUNT(*) FROM participants"), summary.participants);
+        assert_eq!(count("SELECT COUNT(*) FROM recordings"), summary.recordings);
+        assert_eq!(count(r#"SELECT COUNT(DISTINCT "Recording name") FROM gaze_data"#), summary.recordings);
+        let valid = count(r#"SELECT COUNT(*) FROM gaze_data WHERE "Gaze point X" IS NOT NULL"#);
+        assert!((valid as f64 / summary.gaze_rows as f64 * 100.0 - summary.valid_pct).abs() < 1e-9);
+
+        // late-window share of target looks among target + competitor looks, per condition
+        let mut roles: HashMap<String, (usize, Vec<&'static str>, Vec<&'static str>)> = HashMap::new();
+        let mut stmt = conn.prepare(r#"SELECT test_name, "group", correct_AOIs, self_AOIs || ',' || incorrect_AOIs || ',' || potentially_correct_AOIs FROM test_catalog"#).unwrap();
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?))).unwrap();
+        for row in rows {
+            let (test, group, target, others) = row.unwrap();
+            let condition = cfg.conditions.iter().position(|c| c.name == group).unwrap();
+            roles.insert(test, (condition, codes(&target), codes(&others)));
+        }
+        let mut trials: BTreeMap<(String, String), Vec<(i64, String)>> = BTreeMap::new();
+        let mut stmt = conn.prepare(r#"SELECT "Participant name", "Test Name", "Exact time", Box FROM gaze_data ORDER BY rowid"#).unwrap();
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?))).unwrap();
+        for row in rows {
+            let (participant, test, time, b) = row.unwrap();
+            trials.entry((participant, test)).or_default().push((timestamp::parse(&time).unwrap(), b));
+        }
+        // samples of dwells (≥ 5 samples on one Box) in the late window; saccade samples crossing AOIs are left out
+        let mut looks = vec![(0usize, 0usize); cfg.conditions.len()];
+        for ((_, test), samples) in &trials {
+            let (condition, target, others) = &roles[test];
+            let cond = &cfg.conditions[*condition];
+            let late = cond.onset_ms + 4.0 * cond.rise_ms;
+            for run in samples.chunk_by(|a, b| a.1 == b.1).filter(|r| r.len() >= 5) {
+                let n = run.iter().filter(|(t, _)| timestamp::to_ms(t - samples[0].0) >= late).count();
+                let l = &mut looks[*condition];
+                if target.contains(&run[0].1.as_str()) { l.0 += n; } else if others.contains(&run[0].1.as_str()) { l.1 += n; }
+            }
+        }
+        for (cond, (target, other)) in cfg.conditions.iter().zip(looks) {
+            let share = target as f64 / (target + other) as f64;
+            assert!((share - cond.target_share).abs() < 0.06, "{}: {share} vs {}", cond.name, cond.target_share);
+        }
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn check_rejects_bad_durations() {
+        let mut cfg = SynthConfig { video_ms: 0.0, ..SynthConfig::default() };
+        assert!(check(&cfg).is_err());
+        cfg.video_ms = f64::INFINITY;
+        assert!(check(&cfg).is_err());
+        cfg = SynthConfig { picture_ms: f64::NAN, ..SynthConfig::default() };
+        assert!(check(&cfg).is_err());
+        cfg = SynthConfig::default();
+        cfg.conditions[0].rise_ms = 0.0;
+        assert!(check(&cfg).is_err());
+        assert!(check(&SynthConfig::default()).is_ok());
+    }
+}
@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/* ──────────────────────────────────────────────────────────────
+"Exact time" parsing. Every time computation works on i64 epoch
+microseconds parsed here once; the text column only sorts correctly
+when every row uses the same layout, so nothing orders by it in SQL.
+Accepted: "YYYY-MM-DD HH:MM:SS(.ffffff)" / ISO 8601 with an optional Z
+or ±HH(:MM) offset (converted to UTC), bare "HH:MM:SS(.ffffff)" (time
+within day 0), or a plain number of milliseconds. Times without an
+offset are taken as UTC, i.e. the recording PC's wall clock.
+────────────────────────────────────────────────────────────── */
+
+const US_PER_MS: i64 = 1_000;
+const US_PER_DAY: i64 = 86_400_000_000;
+
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn field(s: &str, max: i64) -> Option<i64> {
+    let s = s.trim();
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) { return None; }
+    s.parse().ok().filter(|v| *v <= max)
+}
+
+/* "SS" or "SS.ffffff" (',' accepted) -> µs; digits past the sixth are dropped */
+fn seconds_us(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (whole, frac) = s.split_once(['.', ',']).unwrap_or((s, ""));
+    let sec = field(whole, 60)?;
+    if !frac.bytes().all(|b| b.is_ascii_digit()) { return None; }
+    let digits: String = frac.chars().chain(std::iter::repeat('0')).take(6).collect();
+    Some(sec * 1_000_000 + digits.parse::<i64>().ok()?)
+}
+
+/* "Exact time" -> epoch µs (None when malformed) */
+pub fn parse(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if s.is_empty() { return None; }
+    if let Ok(v) = s.parse::<i64>() { return v.checked_mul(US_PER_MS); }
+    if let Ok(v) = s.parse::<f64>() {
+        return v.is_finite().then(|| (v * US_PER_MS as f64).round() as i64);
+    }
+
+    let (date, rest) = match s.find(['T', ' ']) {
+        Some(i) if s[..i].contains('-') => (Some(&s[..i]), s[i + 1..].trim()),
+        _ => (None, s),
+    };
+    let day_us = match date {
+        Some(d) => {
+            let mut it = d.split('-');
+            let y = field(it.next()?, 9999)?;
+            let m = field(it.next()?, 12).filter(|m| *m >= 1)?;
+            let dd = field(it.next()?, 31).filter(|d| *d >= 1)?;
+            if it.next().is_some() { return None; }
+            days_from_civil(y, m, dd) * US_PER_DAY
+        }
+        None => 0,
+    };
+
+    // split off timezone designator: Z, ±HH, ±HH:MM or ±HHMM
+    let (clock, offset_us) = if let Some(c) = rest.strip_suffix('Z') {
+        (c, 0)
+    } else if let Some(i) = rest.rfind(['+', '-']) {
+        let tz = &rest[i + 1..];
+        let (h, m) = match tz.split_once(':') {
+            Some((h, m)) => (h, m),
+            None if tz.len() == 4 => tz.split_at(2),
+            None => (tz, "0"),
+        };
+        let sign = if rest.as_bytes()[i] == b'-' { -1 } else { 1 };
+        (&rest[..i], sign * (field(h, 23)? * 3_600_000_000 + field(m, 59)? * 60_000_000))
+    } else {
+        (rest, 0)
+    };
+
+    let mut parts = clock.split(':');
+    let h = field(parts.next()?, 23)?;
+    let m = field(parts.next()?, 59)?;
+    let sec = match parts.next() {
+        Some(sec) => seconds_us(sec)?,
+        None => 0,
+    };
+    if parts.next().is_some() { return None; }
+    Some(day_us + h * 3_600_000_000 + m * 60_000_000 + sec - offset_us)
+}
+
+pub fn to_ms(us: i64) -> f64 {
+    us as f64 / US_PER_MS as f64
+}
+
+pub fn from_ms(ms: f64) -> i64 {
+    (ms * US_PER_MS as f64).round() as i64
+}
+
+/* Inverse of parse: "YYYY-MM-DD HH:MM:SS.ffffff" (bare clock time within day 0) */
+pub fn format(us: i64) -> String {
+    let (days, rem) = (us.div_euclid(US_PER_DAY), us.rem_euclid(US_PER_DAY));
+    let clock = format!(
+        "{:02}:{:02}:{:02}.{:06}",
+        rem / 3_600_000_000,
+        rem / 60_000_000 % 60,
+        rem / 1_000_000 % 60,
+        rem % 1_000_000
+    );
+    if days == 0 { return clock; }
+    // civil_from_days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02} {clock}")
+}
+
+/* ─────────────────────────── Checks ─────────────────────────── */
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordingTimeCheck {
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub rows: i64,
+    /* non-blank values parse() rejects */
+    pub malformed: i64,
+    /* rows (in stored order) earlier than the latest time seen before them */
+    pub out_of_order: i64,
+    /* rows repeating the previous row's time */
+    pub duplicates: i64,
+    pub first_us: Option<i64>,
+    pub last_us: Option<i64>,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeCheck {
+    pub scanned_rows: i64,
+    pub malformed: i64,
+    pub out_of_order: i64,
+    pub duplicates: i64,
+    /* only recordings with at least one problem */
+    pub recordings: Vec<RecordingTimeCheck>,
+}
+
+const MAX_TIME_EXAMPLES: usize = 5;
+
+/* Scan the first `limit` gaze_data rows (-1 = all) in stored (rowid) order */
+pub fn check(conn: &rusqlite::Connection, limit: i64) -> Result<TimeCheck, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT "Test Name", "Participant name", "Timeline name", "Recording name", CAST("Exact time" AS TEXT)
+               FROM gaze_data ORDER BY rowid LIMIT ?1"#,
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([limit]).map_err(|e| e.to_string())?;
+    let mut by_recording: HashMap<[String; 4], (RecordingTimeCheck, Option<i64>)> = HashMap::new();
+    let mut out = TimeCheck::default();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let key: [String; 4] = [
+            row.get::<_, Option<String>>(0).map_err(|e| e.to_string())?.unwrap_or_default(),
+            row.get::<_, Option<String>>(1).map_err(|e| e.to_string())?.unwrap_or_default(),
+            row.get::<_, Option<String>>(2).map_err(|e| e.to_string())?.unwrap_or_default(),
+            row.get::<_, Option<String>>(3).map_err(|e| e.to_string())?.unwrap_or_default(),
+        ];
+        let raw: Option<String> = row.get(4).map_err(|e| e.to_string())?;
+        out.scanned_rows += 1;
+        let (rec, prev) = by_recording.entry(key).or_insert_with_key(|[t, p, tl, r]| {
+            let rec = RecordingTimeCheck { test_name: t.clone(), participant: p.clone(), timeline: tl.clone(), recording: r.clone(), ..Default::default() };
+            (rec, None)
+        });
+        rec.rows += 1;
+        let Some(raw) = raw.filter(|r| !r.trim().is_empty()) else { continue; };
+        let Some(t) = parse(&raw) else {
+            rec.malformed += 1;
+            if rec.examples.len() < MAX_TIME_EXAMPLES { rec.examples.push(format!("malformed: {raw}")); }
+            continue;
+        };
+        match *prev {
+            Some(p) if t < p => {
+                rec.out_of_order += 1;
+                if rec.examples.len() < MAX_TIME_EXAMPLES { rec.examples.push(format!("out of order: {raw} after {}", format(p))); }
+            }
+            Some(p) if t == p => rec.duplicates += 1,
+            _ => {}
+        }
+        *prev = Some(prev.map_or(t, |p| p.max(t)));
+        rec.first_us = Some(rec.first_us.map_or(t, |f| f.min(t)));
+        rec.last_us = Some(rec.last_us.map_or(t, |l| l.max(t)));
+    }
+    let mut recordings: Vec<RecordingTimeCheck> = by_recording
+        .into_values()
+        .map(|(r, _)| r)
+        .filter(|r| r.malformed + r.out_of_order + r.duplicates > 0)
+        .collect();
+    recordings.sort_by(|a, b| (&a.test_name, &a.participant, &a.timeline, &a.recording).cmp(&(&b.test_name, &b.participant, &b.timeline, &b.recording)));
+    out.malformed = recordings.iter().map(|r| r.malformed).sum();
+    out.out_of_order = recordings.iter().map(|r| r.out_of_order).sum();
+    out.duplicates = recordings.iter().map(|r| r.duplicates).sum();
+    out.recordings = recordings;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_and_z_agree() {
+        let utc = parse("2024-03-05T10:00:00.123Z").unwrap();
+        assert_eq!(parse("2024-03-05 10:00:00.123"), Some(utc));
+        assert_eq!(parse("2024-03-05 12:00:00.123+02:00"), Some(utc));
+        assert_eq!(parse("2024-03-05T05:00:00.123-0500"), Some(utc));
+        assert_eq!(parse("2024-03-05T11:00:00.123+01"), Some(utc));
+    }
+
+    #[test]
+    fn round_trips() {
+        for s in ["2024-03-05 10:00:00.123456", "1999-12-31 23:59:59.000001", "10:00:00.500000"] {
+            assert_eq!(format(parse(s).unwrap()), s);
+        }
+        assert_eq!(format(parse("2024-03-05T12:00:00.5+02:00").unwrap()), "2024-03-05 10:00:00.500000");
+        assert_eq!(parse("1970-01-02 00:00:00"), Some(86_400_000_000));
+    }
+
+    #[test]
+    fn numbers_are_milliseconds() {
+        assert_eq!(parse("1500"), Some(1_500_000));
+        assert_eq!(parse("1.5"), Some(1_500));
+        assert_eq!(to_ms(from_ms(12.5)), 12.5);
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        for s in ["", "24:00", "24:00:00", "2024-03-05 24:00:00", "10:60:00", "10:00:61", "2024-13-01 10:00:00", "abc", "10:00:00:00"] {
+            assert_eq!(parse(s), None, "{s}");
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::analysis::saved::{AnalysisKind, AnalysisSpec};
+use crate::analysis::ConditionDef;
+use crate::aoi::{self, BASE_AOI_KEYS};
+use crate::binning::load_recordings;
+use crate::items::ProportionSummary;
+use crate::manifest::{self, WithManifest};
+use crate::resample::{self, ResampleParams};
+use crate::{DbPool, DisabledSlice, DisabledStore, RowMap};
+
+/* ──────────────────────────────────────────────────────────────
+First-order AOI transitions. Each sample's Box is mapped to the first
+catalog AOI column (in `categories` order) listing it, else "other";
+invalid boxes are dropped and consecutive repeats collapsed, so the
+matrix counts moves between categories (no self-transitions).
+Stationary entropy is taken over the visit distribution π,
+transition entropy is Σ π_i · H(row i); both in bits, also
+normalised by log2(number of states).
+────────────────────────────────────────────────────────────── */
+
+const OTHER: &str = "other";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionOptions {
+    /* catalog AOI columns used as states (default: all seven) */
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /* keep boxes outside every category as an "other" state */
+    #[serde(default = "default_true")]
+    pub include_other: bool,
+    #[serde(default = "default_invalid")]
+    pub invalid: Vec<String>,
+    /* catalog factor recordings are grouped by */
+    #[serde(default = "default_condition_by")]
+    pub condition_by: String,
+    #[serde(default)]
+    pub resample: Option<ResampleParams>,
+}
+
+fn default_true() -> bool { true }
+fn default_invalid() -> Vec<String> { vec!["missing".to_string(), "out_of_screen".to_string()] }
+fn default_condition_by() -> String { "group".to_string() }
+
+impl Default for TransitionOptions {
+    fn default() -> Self {
+        TransitionOptions {
+            categories: Vec::new(),
+            include_other: default_true(),
+            invalid: default_invalid(),
+            condition_by: default_condition_by(),
+            resample: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entropy {
+    pub stationary: f64,
+    pub transition: f64,
+    pub stationary_norm: f64,
+    pub transition_norm: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransitionMatrix {
+    /* counts[i][j]: moves from states[i] to states[j] */
+    pub counts: Vec<Vec<i64>>,
+    /* row-normalised counts (rows without outgoing moves are all 0) */
+    pub probabilities: Vec<Vec<f64>>,
+    /* visits per state (collapsed runs) */
+    pub visits: Vec<i64>,
+    pub transitions: i64,
+    pub entropy: Option<Entropy>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordingTransitions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cohort: Option<String>,
+    pub test_name: String,
+    pub participant: String,
+    pub timeline: String,
+    pub recording: String,
+    pub condition: Option<String>,
+    pub matrix: TransitionMatrix,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConditionTransitions {
+    pub condition: Option<String>,
+    pub recordings: usize,
+    /* pooled counts over the condition's recordings */
+    pub matrix: TransitionMatrix,
+    /* spread of the per-recording entropies */
+    pub stationary_entropy: Option<ProportionSummary>,
+    pub transition_entropy: Option<ProportionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransitionResult {
+    pub states: Vec<String>,
+    pub condition_by: String,
+    pub recordings: Vec<RecordingTransitions>,
+    pub conditions: Vec<ConditionTransitions>,
+}
+
+fn h(p: impl Iterator<Item = f64>) -> f64 {
+    -p.filter(|p| *p > 0.0).map(|p| p * p.log2()).sum::<f64>()
+}
+
+impl TransitionMatrix {
+    fn from_counts(counts: Vec<Vec<i64>>, visits: Vec<i64>) -> Self {
+        let probabilities: Vec<Vec<f64>> = counts
+            .iter()
+            .map(|row| {
+                let n: i64 = row.iter().sum();
+                row.iter().map(|c| if n > 0 { *c as f64 / n as f64 } else { 0.0 }).collect()
+            })
+            .collect();
+        let transitions = counts.iter().flatten().sum();
+        let total: i64 = visits.iter().sum();
+        let entropy = (total > 0).then(|| {
+            let pi: Vec<f64> = visits.iter().map(|v| *v as f64 / total as f64).collect();
+            let stationary = h(pi.iter().copied());
+            let transition: f64 = pi.iter().zip(&probabilities).map(|(p, row)| p * h(row.iter().copied())).sum();
+            let max = (visits.len() as f64).log2();
+            let norm = |x: f64| if max > 0.0 { x / max } else { 0.0 };
+            Entropy { stationary, transition, stationary_norm: norm(stationary), transition_norm: norm(transition) }
+        });
+        TransitionMatrix { counts, probabilities, visits, transitions, entropy }
+    }
+}
+
+/* Box → state index for one test */
+fn state_map(row: Option<&RowMap>, categories: &[String]) -> HashMap<String, usize> {
+    let mut out = HashMap::new();
+    let Some(row) = row else { return out; };
+    for (i, key) in categories.iter().enumerate() {
+        for b in aoi::boxes_for(row, std::slice::from_ref(key)) {
+            out.entry(b).or_insert(i);
+        }
+    }
+    out
+}
+
+/* Per-recording and per-condition transitions for `tests` with `excluded` slices left out */
+pub fn aoi_transitions(
+    conn: &rusqlite::Connection,
+    excluded: &HashSet<DisabledSlice>,
+    tests: &[String],
+    participants: &[String],
+    cohort: &Option<String>,
+    options: &TransitionOptions,
+) -> Result<TransitionResult, String> {
+    let categories: Vec<String> = if options.categories.is_empty() {
+        BASE_AOI_KEYS.iter().map(|k| k.to_string()).collect()
+    } else {
+        options.categories.clone()
+    };
+    let mut states = categories.clone();
+    if options.include_other { states.push(OTHER.to_string()); }
+    let k = states.len();
+
+    let catalog = aoi::load_catalog(conn)?;
+    let recs = load_recordings(conn, excluded, tests, participants, cohort)?;
+
+    let mut maps: HashMap<&str, HashMap<String, usize>> = HashMap::new();
+    let mut recordings = Vec::new();
+    for rec in &recs {
+        let row = catalog.get(&rec.test_name);
+        let map = maps.entry(rec.test_name.as_str()).or_insert_with(|| state_map(row, &categories));
+        let mut seq: Vec<usize> = Vec::new();
+        for s in resample::samples(&rec.samples, options.resample.as_ref()).iter() {
+            if options.invalid.contains(&s.box_name) { continue; }
+            let state = match map.get(&s.box_name) {
+                Some(i) => *i,
+                None if options.include_other => k - 1,
+                None => continue,
+            };
+            if seq.last() != Some(&state) { seq.push(state); }
+        }
+        let mut counts = vec![vec![0i64; k]; k];
+        for w in seq.windows(2) { counts[w[0]][w[1]] += 1; }
+        let mut visits = vec![0i64; k];
+        for s in &seq { visits[*s] += 1; }
+        recordings.push(RecordingTransitions {
+            cohort: rec.cohort.clone(),
+            test_name: rec.test_name.clone(),
+            participant: rec.participant.clone(),
+            timeline: rec.timeline.clone(),
+            recording: rec.recording.clone(),
+            condition: row.and_then(|r| aoi::pick(r, &options.condition_by)).map(|v| v.trim().to_string()),
+            matrix: TransitionMatrix::from_counts(counts, visits),
+        });
+    }
+
+    let mut grouped: BTreeMap<Option<&str>, Vec<&RecordingTransitions>> = BTreeMap::new();
+    for r in &recordings { grouped.entry(r.condition.as_deref()).or_default().push(r); }
+    let conditions = grouped
+        .into_iter()
+        .map(|(condition, recs)| {
+            let mut counts = vec![vec![0i64; k]; k];
+            let mut visits = vec![0i64; k];
+            for r in &recs {
+                for (i, row) in r.matrix.counts.iter().enumerate() {
+                    for (j, c) in row.iter().enumerate() { counts[i][j] += c; }
+                }
+                for (v, c) in visits.iter_mut().zip(&r.matrix.visits) { *v += c; }
+            }
+            let per = |f: fn(&Entropy) -> f64| -> Vec<f64> { recs.iter().filter_map(|r| r.matrix.entropy.as_ref().map(f)).collect() };
+            ConditionTransitions {
+                condition: condition.map(str::to_string),
+                recordings: recs.len(),
+                matrix: TransitionMatrix::from_counts(counts, visits),
+                stationary_entropy: ProportionSummary::of(&per(|e| e.stationary)),
+                transition_entropy: ProportionSummary::of(&per(|e| e.transition)),
+            }
+        })
+        .collect();
+
+    Ok(TransitionResult { states, condition_by: options.condition_by.clone(), recordings, conditions })
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+#[tauri::command]
+pub async fn get_aoi_transitions(
+    app: AppHandle,
+    tests: Vec<String>,
+    participants: Vec<String>,
+    cohort: Option<String>,
+    options: Option<TransitionOptions>,
+    manifest: Option<bool>,
+) -> Result<WithManifest<TransitionResult>, String> {
+    let options = options.unwrap_or_default();
+    let disabled = app.state::<DisabledStore>().0.read().unwrap().clone();
+    let result = {
+        let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+        aoi_transitions(&conn, &disabled, &tests, &participants, &cohort, &options)?
+    };
+    let spec = || AnalysisSpec {
+        participants,
+        cohort,
+        transitions: options,
+        ..AnalysisSpec::new(AnalysisKind::Transitions, vec![ConditionDef { name: "tests".to_string(), tests }])
+    };
+    manifest::attach(&app, manifest, "get_aoi_transitions", spec, &disabled, None, result)
+}
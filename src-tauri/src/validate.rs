@@ -0,0 +1,440 @@
+use rusqlite::OpenFlags;
+use rusqlite::Result as SqlResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::aoi::BASE_AOI_KEYS;
+use crate::project::DbPath;
+use crate::timestamp::{self, TimeCheck};
+use crate::{table_exists, DbPool};
+
+/* ──────────────────────────────────────────────────────────────
+Schema validation / health check: required tables and columns, declared
+type affinities, null rates, orphaned rows and "Exact time" values that
+are malformed or out of order within a recording, so a broken or
+outdated DB is reported instead of silently yielding empty results.
+────────────────────────────────────────────────────────────── */
+
+/* rows scanned for null rates / storage types unless `full` is asked for */
+const SAMPLE_ROWS: i64 = 200_000;
+const MAX_EXAMPLES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Fatal,
+}
+
+/* (column, expected affinity, severity when missing) */
+type ColumnSpec = (&'static str, Option<&'static str>, Severity);
+
+const GAZE_COLUMNS: [ColumnSpec; 9] = [
+    ("Test Name", Some("TEXT"), Severity::Fatal),
+    ("Participant name", Some("TEXT"), Severity::Fatal),
+    ("Recording name", Some("TEXT"), Severity::Fatal),
+    ("Timeline name", Some("TEXT"), Severity::Warning),
+    ("Exact time", None, Severity::Fatal),
+    ("Box", Some("TEXT"), Severity::Fatal),
+    ("Gaze point X", Some("REAL"), Severity::Warning),
+    ("Gaze point Y", Some("REAL"), Severity::Warning),
+    ("Presented Media name", Some("TEXT"), Severity::Warning),
+];
+
+const PARTICIPANT_COLUMNS: [ColumnSpec; 1] = [("participant", Some("TEXT"), Severity::Warning)];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub code: String,
+    pub table: Option<String>,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnReport {
+    pub name: String,
+    /* actual column name when matched loosely (case, '_' vs ' ') */
+    pub found_as: Option<String>,
+    pub declared_type: Option<String>,
+    pub affinity: Option<String>,
+    pub expected_affinity: Option<String>,
+    /* NULL or blank values among the scanned rows */
+    pub nulls: Option<i64>,
+    pub null_rate: Option<f64>,
+    /* values whose storage class does not fit the expected affinity */
+    pub mismatched_values: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableReport {
+    pub table: String,
+    pub present: bool,
+    pub rows: Option<i64>,
+    pub scanned_rows: Option<i64>,
+    pub columns: Vec<ColumnReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanCheck {
+    pub check: String,
+    pub description: String,
+    pub count: i64,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub db_path: String,
+    pub checked_at_ms: u64,
+    /* false when only the first SAMPLE_ROWS rows were scanned */
+    pub full_scan: bool,
+    /* no fatal issues */
+    pub ok: bool,
+    pub fatal: usize,
+    pub warnings: usize,
+    pub tables: Vec<TableReport>,
+    pub orphans: Vec<OrphanCheck>,
+    /* "Exact time" check over the same rows (None when a key column is missing) */
+    pub timestamps: Option<TimeCheck>,
+    pub issues: Vec<Issue>,
+}
+
+/* Latest report for the active DB (set at startup and on every switch) */
+pub struct HealthStore(pub Mutex<Option<ValidationReport>>);
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/* SQLite's affinity rules for a declared column type */
+pub fn affinity(declared: &str) -> &'static str {
+    let t = declared.to_uppercase();
+    if t.contains("INT") { "INTEGER" }
+    else if t.contains("CHAR") || t.contains("CLOB") || t.contains("TEXT") { "TEXT" }
+    else if t.is_empty() || t.contains("BLOB") { "BLOB" }
+    else if t.contains("REAL") || t.contains("FLOA") || t.contains("DOUB") { "REAL" }
+    else { "NUMERIC" }
+}
+
+fn affinity_fits(actual: &str, expected: &str) -> bool {
+    match expected {
+        "REAL" | "INTEGER" | "NUMERIC" => matches!(actual, "REAL" | "INTEGER" | "NUMERIC"),
+        other => actual == other,
+    }
+}
+
+/* storage classes acceptable for an expected affinity (typeof() names) */
+fn storage_classes(expected: &str) -> &'static str {
+    match expected {
+        "REAL" | "INTEGER" | "NUMERIC" => "'integer','real','null'",
+        _ => "'text','integer','real','null'",
+    }
+}
+
+/* (name, declared type) from PRAGMA table_info */
+fn table_columns(conn: &rusqlite::Connection, table: &str) -> Result<Vec<(String, String)>, String> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", quote(table))).map_err(|e| e.to_string())?;
+    let cols = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?.unwrap_or_default())))
+        .map_err(|e| e.to_string())?
+        .collect::<SqlResult<Vec<_>>>()
+        .map_err(|e| e.to_string());
+    cols
+}
+
+/* Loose match in the spirit of aoi::pick (case-insensitive, '_' == ' ') */
+fn find_column<'a>(cols: &'a [(String, String)], name: &str) -> Option<&'a (String, String)> {
+    let norm = |s: &str| s.to_lowercase().replace('_', " ");
+    cols.iter().find(|(c, _)| c == name).or_else(|| cols.iter().find(|(c, _)| norm(c) == norm(name)))
+}
+
+struct Checker {
+    issues: Vec<Issue>,
+}
+
+impl Checker {
+    fn push(&mut self, severity: Severity, code: &str, table: &str, column: Option<&str>, message: String) {
+        self.issues.push(Issue {
+            severity,
+            code: code.to_string(),
+            table: Some(table.to_string()),
+            column: column.map(|c| c.to_string()),
+            message,
+        });
+    }
+
+    /* Presence, affinity and (sampled) null / storage-class counts for one table */
+    fn table(
+        &mut self,
+        conn: &rusqlite::Connection,
+        table: &str,
+        missing: Severity,
+        specs: &[ColumnSpec],
+        limit: i64,
+    ) -> Result<TableReport, String> {
+        if !table_exists(conn, table) {
+            self.push(missing, "missing_table", table, None, format!("table '{table}' is missing"));
+            return Ok(TableReport { table: table.to_string(), present: false, rows: None, scanned_rows: None, columns: Vec::new() });
+        }
+        let cols = table_columns(conn, table)?;
+        let rows: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", quote(table)), [], |r| r.get(0))
+            .map_err(|e| e.to_string())?;
+
+        let mut columns: Vec<ColumnReport> = Vec::new();
+        let mut found: Vec<(usize, String, Option<&str>)> = Vec::new();
+        for (name, expected, severity) in specs {
+            let Some((actual, declared)) = find_column(&cols, name) else {
+                self.push(*severity, "missing_column", table, Some(name), format!("column '{name}' is missing from '{table}'"));
+                columns.push(ColumnReport {
+                    name: name.to_string(),
+                    found_as: None,
+                    declared_type: None,
+                    affinity: None,
+                    expected_affinity: expected.map(|e| e.to_string()),
+                    nulls: None,
+                    null_rate: None,
+                    mismatched_values: None,
+                });
+                continue;
+            };
+            let aff = affinity(declared);
+            if let Some(exp) = expected.filter(|e| !affinity_fits(aff, e)) {
+                self.push(
+                    Severity::Warning,
+                    "type_affinity",
+                    table,
+                    Some(name),
+                    format!("'{table}.{actual}' is declared '{declared}' ({aff} affinity), expected {exp}"),
+                );
+            }
+            found.push((columns.len(), actual.clone(), *expected));
+            columns.push(ColumnReport {
+                name: name.to_string(),
+                found_as: (actual != name).then(|| actual.clone()),
+                declared_type: Some(declared.clone()),
+                affinity: Some(aff.to_string()),
+                expected_affinity: expected.map(|e| e.to_string()),
+                nulls: None,
+                null_rate: None,
+                mismatched_values: None,
+            });
+        }
+
+        // One pass over (a sample of) the table for all found columns
+        let mut scanned_rows = None;
+        if !found.is_empty() {
+            let mut sel = vec!["COUNT(*)".to_string()];
+            for (_, col, expected) in &found {
+                let c = quote(col);
+                sel.push(format!("SUM({c} IS NULL OR TRIM(CAST({c} AS TEXT)) = '')"));
+                sel.push(format!("SUM(typeof({c}) NOT IN ({}))", storage_classes(expected.unwrap_or("TEXT"))));
+            }
+            let cols_sql = found.iter().map(|(_, c, _)| quote(c)).collect::<Vec<_>>().join(", ");
+            let sql = format!("SELECT {} FROM (SELECT {cols_sql} FROM {} LIMIT ?1)", sel.join(", "), quote(table));
+            let counts: Vec<i64> = conn
+                .query_row(&sql, [limit], |r| (0..sel.len()).map(|i| Ok(r.get::<_, Option<i64>>(i)?.unwrap_or(0))).collect())
+                .map_err(|e| e.to_string())?;
+            let scanned = counts[0];
+            scanned_rows = Some(scanned);
+            for (k, (idx, actual, expected)) in found.iter().enumerate() {
+                let (nulls, mismatched) = (counts[1 + 2 * k], counts[2 + 2 * k]);
+                let rate = if scanned > 0 { nulls as f64 / scanned as f64 } else { 0.0 };
+                let report = &mut columns[*idx];
+                report.nulls = Some(nulls);
+                report.null_rate = Some(rate);
+                report.mismatched_values = expected.map(|_| mismatched);
+                let severity = specs.iter().find(|s| s.0 == report.name).map(|s| s.2).unwrap_or(Severity::Warning);
+                if scanned > 0 && nulls == scanned && severity == Severity::Fatal {
+                    self.push(Severity::Fatal, "all_null", table, Some(actual), format!("'{table}.{actual}' is empty in every scanned row"));
+                } else if nulls > 0 {
+                    self.push(
+                        Severity::Warning,
+                        "null_values",
+                        table,
+                        Some(actual),
+                        format!("'{table}.{actual}' is NULL/blank in {nulls} of {scanned} scanned rows ({:.1}%)", rate * 100.0),
+                    );
+                }
+                if let Some(exp) = expected.filter(|_| mismatched > 0) {
+                    self.push(
+                        Severity::Warning,
+                        "storage_type",
+                        table,
+                        Some(actual),
+                        format!("{mismatched} value(s) in '{table}.{actual}' are not stored as {exp}"),
+                    );
+                }
+            }
+        }
+
+        Ok(TableReport { table: table.to_string(), present: true, rows: Some(rows), scanned_rows, columns })
+    }
+}
+
+/* Distinct trimmed non-blank `col` values among the first `limit` rows of `table` (-1 = all) */
+fn distinct_keys(conn: &rusqlite::Connection, table: &str, col: &str, limit: i64) -> Result<BTreeSet<String>, String> {
+    let c = quote(col);
+    let sql = format!(
+        "SELECT DISTINCT TRIM(CAST({c} AS TEXT)) FROM (SELECT {c} FROM {} LIMIT ?1)
+         WHERE {c} IS NOT NULL AND TRIM(CAST({c} AS TEXT)) <> ''",
+        quote(table)
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let values = stmt.query_map([limit], |r| r.get::<_, String>(0)).map_err(|e| e.to_string())?;
+    values.collect::<SqlResult<BTreeSet<String>>>().map_err(|e| e.to_string())
+}
+
+/* Values of `from` that have no match in `other` (both from distinct_keys) */
+fn orphans(check: &str, description: &str, from: &BTreeSet<String>, other: &BTreeSet<String>) -> OrphanCheck {
+    let values: Vec<&String> = from.difference(other).collect();
+    OrphanCheck {
+        check: check.to_string(),
+        description: description.to_string(),
+        count: values.len() as i64,
+        examples: values.into_iter().take(MAX_EXAMPLES).cloned().collect(),
+    }
+}
+
+fn column_of<'a>(t: &'a TableReport, name: &str) -> Option<&'a str> {
+    t.columns.iter().find(|c| c.name == name && c.declared_type.is_some()).map(|c| c.found_as.as_deref().unwrap_or(&c.name))
+}
+
+pub fn validate(conn: &rusqlite::Connection, db_path: &Path, full: bool) -> Result<ValidationReport, String> {
+    let limit = if full { -1 } else { SAMPLE_ROWS };
+    let mut checker = Checker { issues: Vec::new() };
+
+    let gaze = checker.table(conn, "gaze_data", Severity::Fatal, &GAZE_COLUMNS, limit)?;
+    if gaze.rows == Some(0) {
+        checker.push(Severity::Fatal, "empty_table", "gaze_data", None, "gaze_data has no rows".to_string());
+    }
+    let keys = ["Test Name", "Participant name", "Timeline name", "Recording name", "Exact time"];
+    let timestamps = if keys.iter().all(|k| column_of(&gaze, k) == Some(*k)) {
+        let check = timestamp::check(conn, limit)?;
+        let recs = |pick: fn(&timestamp::RecordingTimeCheck) -> i64| check.recordings.iter().filter(|r| pick(r) > 0).count();
+        if check.malformed > 0 {
+            let n = recs(|r| r.malformed);
+            checker.push(Severity::Warning, "malformed_time", "gaze_data", Some("Exact time"), format!("{} 'Exact time' value(s) in {n} recording(s) cannot be parsed; those rows are skipped", check.malformed));
+        }
+        if check.out_of_order > 0 {
+            let n = recs(|r| r.out_of_order);
+            checker.push(Severity::Warning, "time_out_of_order", "gaze_data", Some("Exact time"), format!("{} row(s) in {n} recording(s) are stored out of time order", check.out_of_order));
+        }
+        if check.duplicates > 0 {
+            let n = recs(|r| r.duplicates);
+            checker.push(Severity::Info, "duplicate_time", "gaze_data", Some("Exact time"), format!("{} row(s) in {n} recording(s) repeat the previous timestamp", check.duplicates));
+        }
+        Some(check)
+    } else {
+        None
+    };
+    let mut catalog_specs: Vec<ColumnSpec> = vec![("test_name", Some("TEXT"), Severity::Fatal)];
+    catalog_specs.extend(BASE_AOI_KEYS.iter().map(|k| (*k, Some("TEXT"), Severity::Warning)));
+    let catalog = checker.table(conn, "test_catalog", Severity::Fatal, &catalog_specs, limit)?;
+    let participants = if !table_exists(conn, "participants") && table_exists(conn, "participant_qac") {
+        checker.push(Severity::Info, "legacy_table", "participant_qac", None, "using legacy 'participant_qac' in place of 'participants'".to_string());
+        checker.table(conn, "participant_qac", Severity::Warning, &PARTICIPANT_COLUMNS, limit)?
+    } else {
+        checker.table(conn, "participants", Severity::Warning, &PARTICIPANT_COLUMNS, limit)?
+    };
+
+    // key sets are read once per table (gaze_data within the scanned rows) and diffed here
+    let mut orphan_checks = Vec::new();
+    let scanned = if full { "" } else { " in the scanned gaze rows" };
+    let gaze_test = column_of(&gaze, "Test Name");
+    let gaze_part = column_of(&gaze, "Participant name");
+    if let (Some(g), Some(c)) = (gaze_test, column_of(&catalog, "test_name")) {
+        let (gaze_tests, catalog_tests) = (distinct_keys(conn, "gaze_data", g, limit)?, distinct_keys(conn, "test_catalog", c, -1)?);
+        let o = orphans("gaze_tests_not_in_catalog", "tests in gaze_data without a test_catalog row", &gaze_tests, &catalog_tests);
+        if o.count > 0 {
+            checker.push(Severity::Warning, "orphaned_rows", "gaze_data", Some(g), format!("{} test(s) in gaze_data are missing from test_catalog", o.count));
+        }
+        orphan_checks.push(o);
+        let o = orphans("catalog_tests_without_gaze", "test_catalog tests with no gaze_data", &catalog_tests, &gaze_tests);
+        if o.count > 0 {
+            checker.push(Severity::Info, "unused_rows", "test_catalog", Some(c), format!("{} catalog test(s) have no gaze data{scanned}", o.count));
+        }
+        orphan_checks.push(o);
+    }
+    if let (Some(g), Some(p)) = (gaze_part, column_of(&participants, "participant")) {
+        let table = participants.table.as_str();
+        let (gaze_parts, listed) = (distinct_keys(conn, "gaze_data", g, limit)?, distinct_keys(conn, table, p, -1)?);
+        let o = orphans("gaze_participants_not_in_participants", "participants in gaze_data without a participants row", &gaze_parts, &listed);
+        if o.count > 0 {
+            checker.push(Severity::Warning, "orphaned_rows", "gaze_data", Some(g), format!("{} participant(s) in gaze_data are missing from {table}", o.count));
+        }
+        orphan_checks.push(o);
+        let o = orphans("participants_without_gaze", "participants rows with no gaze_data", &listed, &gaze_parts);
+        if o.count > 0 {
+            checker.push(Severity::Info, "unused_rows", table, Some(p), format!("{} participant(s) have no gaze data{scanned}", o.count));
+        }
+        orphan_checks.push(o);
+    }
+
+    let mut issues = checker.issues;
+    issues.sort_by_key(|i| std::cmp::Reverse(i.severity));
+    let fatal = issues.iter().filter(|i| i.severity == Severity::Fatal).count();
+    let warnings = issues.iter().filter(|i| i.severity == Severity::Warning).count();
+    Ok(ValidationReport {
+        db_path: db_path.to_string_lossy().to_string(),
+        checked_at_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+        full_scan: full,
+        ok: fatal == 0,
+        fatal,
+        warnings,
+        tables: vec![gaze, catalog, participants],
+        orphans: orphan_checks,
+        timestamps,
+        issues,
+    })
+}
+
+/* Sampled check of the active DB; stores the report and tells the frontend */
+pub fn check_active(app: &AppHandle) -> Result<ValidationReport, String> {
+    let path = app.state::<DbPath>().0.read().unwrap().clone();
+    let conn = app.state::<DbPool>().0.get().map_err(|e| e.to_string())?;
+    let report = validate(&conn, &path, false)?;
+    *app.state::<HealthStore>().0.lock().unwrap() = Some(report.clone());
+    let _ = app.emit("database-health", report.clone());
+    Ok(report)
+}
+
+/* ─────────────────────────── Commands ─────────────────────────── */
+
+/* Validate the active DB, or any DB file when `path` is given; `full` scans every row */
+#[tauri::command]
+pub async fn validate_database(
+    path: Option<String>,
+    full: Option<bool>,
+    pool: State<'_, DbPool>,
+    db_path: State<'_, DbPath>,
+    health: State<'_, HealthStore>,
+) -> Result<ValidationReport, String> {
+    let full = full.unwrap_or(false);
+    match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => {
+            let conn = rusqlite::Connection::open_with_flags(&p, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| e.to_string())?;
+            validate(&conn, Path::new(&p), full)
+        }
+        None => {
+            let active = db_path.0.read().unwrap().clone();
+            let conn = pool.0.get().map_err(|e| e.to_string())?;
+            let report = validate(&conn, &active, full)?;
+            *health.0.lock().unwrap() = Some(report.clone());
+            Ok(report)
+        }
+    }
+}
+
+/* Last stored report (startup / last switch) */
+#[tauri::command]
+pub async fn get_database_health(health: State<'_, HealthStore>) -> Result<Option<ValidationReport>, String> {
+    Ok(health.0.lock().unwrap().clone())
+}
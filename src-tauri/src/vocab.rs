@@ -0,0 +1,40 @@
+/* ──────────────────────────────────────────────────────────────
+Closed stimulus vocabulary of the experiment (see README): subjects,
+objects with the measure word each takes, the verb and the only-words.
+────────────────────────────────────────────────────────────── */
+
+pub const ANIMALS: [&str; 9] = ["貓仔", "豬仔", "牛仔", "狗仔", "馬騮", "馬仔", "雞仔", "兔仔", "羊仔"];
+
+/* (object, accepted measure words; the first is the usual one) */
+pub const OBJECTS: [(&str, &[&str]); 21] = [
+    ("水樽", &["個", "枝"]),
+    ("紙巾", &["盒"]),
+    ("火車", &["架"]),
+    ("香蕉", &["條"]),
+    ("餅乾", &["塊"]),
+    ("單車", &["架"]),
+    ("匙羹", &["隻", "把"]),
+    ("鉛筆", &["枝"]),
+    ("口罩", &["個"]),
+    ("牙膏", &["枝"]),
+    ("蘋果", &["個"]),
+    ("飛機", &["架"]),
+    ("較剪", &["把", "個"]),
+    ("蛋糕", &["塊"]),
+    ("書包", &["個"]),
+    ("鎖匙", &["條", "把"]),
+    ("頸巾", &["條"]),
+    ("雪條", &["枝", "條"]),
+    ("西瓜", &["個"]),
+    ("枕頭", &["個"]),
+    ("鑰匙", &["條", "把"]),
+];
+
+pub const MEASURE_WORDS: [&str; 8] = ["個", "盒", "架", "隻", "塊", "枝", "把", "條"];
+
+pub const VERB: &str = "拎咗";
+
+/* pre-subject / pre-verb only-words and the sentence-final particle */
+pub const ONLY_JINGHAI: &str = "淨係";
+pub const ONLY_DAK: &str = "得";
+pub const ONLY_ZAA: &str = "咋";
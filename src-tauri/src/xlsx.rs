@@ -0,0 +1,348 @@
+use std::fmt::Write as _;
+
+/* ──────────────────────────────────────────────────────────────
+Minimal .xlsx writer: SpreadsheetML parts in an uncompressed (stored)
+zip. Strings are written inline as UTF-8, so CJK names survive as-is.
+Only what the exporters need: a bold header row (frozen), integer /
+2-decimal / 1-decimal number formats and column widths.
+────────────────────────────────────────────────────────────── */
+
+const MAX_SHEET_NAME: usize = 31;
+
+#[derive(Debug, Clone)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Int(i64),
+    /* two decimals (durations) */
+    Num(f64),
+    /* one decimal (percentages) */
+    Pct(f64),
+}
+
+impl From<&str> for Cell {
+    fn from(s: &str) -> Self { Cell::Text(s.to_string()) }
+}
+
+impl From<String> for Cell {
+    fn from(s: String) -> Self { Cell::Text(s) }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(s: Option<String>) -> Self { s.map(Cell::Text).unwrap_or(Cell::Empty) }
+}
+
+impl From<Option<f64>> for Cell {
+    fn from(x: Option<f64>) -> Self { x.map(Cell::Num).unwrap_or(Cell::Empty) }
+}
+
+pub struct Sheet {
+    pub name: String,
+    header: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Sheet {
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+}
+
+#[derive(Default)]
+pub struct Workbook {
+    sheets: Vec<Sheet>,
+}
+
+/* Excel's rules: ≤ 31 chars, none of []:*?/\, not blank, unique (case-insensitive) */
+fn sheet_name(wanted: &str, taken: &[Sheet]) -> String {
+    let clean: String = wanted.chars().map(|c| if "[]:*?/\\".contains(c) { '_' } else { c }).collect();
+    let clean = clean.trim().trim_matches('\'');
+    let base = if clean.is_empty() { "Sheet" } else { clean };
+    let is_taken = |n: &str| taken.iter().any(|s| s.name.to_lowercase() == n.to_lowercase());
+    let mut n = 1;
+    loop {
+        let suffix = if n == 1 { String::new() } else { format!(" ({n})") };
+        let keep = MAX_SHEET_NAME - suffix.chars().count();
+        let name = format!("{}{suffix}", base.chars().take(keep).collect::<String>().trim_end());
+        if !is_taken(&name) { return name; }
+        n += 1;
+    }
+}
+
+impl Workbook {
+    pub fn add_sheet(&mut self, name: &str, header: &[&str]) -> &mut Sheet {
+        let name = sheet_name(name, &self.sheets);
+        self.sheets.push(Sheet { name, header: header.iter().map(|h| h.to_string()).collect(), rows: Vec::new() });
+        self.sheets.last_mut().unwrap()
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.iter().map(|s| s.name.clone()).collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut zip = Zip::default();
+        zip.add("[Content_Types].xml", &content_types(self.sheets.len()));
+        zip.add("_rels/.rels", ROOT_RELS);
+        zip.add("xl/workbook.xml", &workbook_xml(&self.sheets));
+        zip.add("xl/_rels/workbook.xml.rels", &workbook_rels(self.sheets.len()));
+        zip.add("xl/styles.xml", STYLES);
+        for (i, s) in self.sheets.iter().enumerate() {
+            zip.add(&format!("xl/worksheets/sheet{}.xml", i + 1), &sheet_xml(s));
+        }
+        zip.finish()
+    }
+}
+
+/* ─────────────────────────── SpreadsheetML ─────────────────────────── */
+
+const XML_HEAD: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+const NS_MAIN: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const NS_REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+/* cellXfs: 0 default, 1 header (bold, grey fill), 2 integer, 3 two decimals, 4 one decimal */
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="0.0"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/><family val="2"/></font><font><b/><sz val="11"/><name val="Calibri"/><family val="2"/></font></fonts><fills count="3"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill><fill><patternFill patternType="solid"><fgColor rgb="FFEDEDED"/><bgColor indexed="64"/></patternFill></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="5"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="2" borderId="0" xfId="0" applyFont="1" applyFill="1"/><xf numFmtId="1" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="2" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+fn esc(s: &str) -> String {
+    s.chars()
+        .filter(|c| matches!(c, '\t' | '\n' | '\r') || *c >= ' ')
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn content_types(n: usize) -> String {
+    let mut x = format!(
+        r#"{XML_HEAD}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#
+    );
+    for i in 1..=n {
+        let _ = write!(
+            x,
+            r#"<Override PartName="/xl/worksheets/sheet{i}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+        );
+    }
+    x.push_str("</Types>");
+    x
+}
+
+fn workbook_xml(sheets: &[Sheet]) -> String {
+    let mut x = format!(r#"{XML_HEAD}<workbook xmlns="{NS_MAIN}" xmlns:r="{NS_REL}"><sheets>"#);
+    for (i, s) in sheets.iter().enumerate() {
+        let _ = write!(x, r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#, esc(&s.name), i + 1, i + 1);
+    }
+    x.push_str("</sheets></workbook>");
+    x
+}
+
+fn workbook_rels(n: usize) -> String {
+    let mut x = format!(r#"{XML_HEAD}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#);
+    for i in 1..=n {
+        let _ = write!(x, r#"<Relationship Id="rId{i}" Type="{NS_REL}/worksheet" Target="worksheets/sheet{i}.xml"/>"#);
+    }
+    let _ = write!(x, r#"<Relationship Id="rId{}" Type="{NS_REL}/styles" Target="styles.xml"/></Relationships>"#, n + 1);
+    x
+}
+
+/* A, B, …, Z, AA, … for a 0-based column */
+fn column(mut i: usize) -> String {
+    let mut s = Vec::new();
+    loop {
+        s.push(b'A' + (i % 26) as u8);
+        if i < 26 { break; }
+        i = i / 26 - 1;
+    }
+    s.reverse();
+    String::from_utf8(s).unwrap_or_default()
+}
+
+/* display width in Excel character units; wide (CJK) characters count double */
+fn text_width(s: &str) -> usize {
+    s.chars().map(|c| if (c as u32) >= 0x1100 { 2 } else { 1 }).sum()
+}
+
+fn cell_xml(x: &mut String, r: usize, c: usize, cell: &Cell, header: bool) {
+    let at = format!("{}{}", column(c), r);
+    let number = |x: &mut String, v: f64, style: u8| {
+        if v.is_finite() { let _ = write!(x, r#"<c r="{at}" s="{style}"><v>{v}</v></c>"#); }
+    };
+    match cell {
+        Cell::Empty => {}
+        Cell::Text(s) => {
+            let style = if header { r#" s="1""# } else { "" };
+            let _ = write!(x, r#"<c r="{at}" t="inlineStr"{style}><is><t xml:space="preserve">{}</t></is></c>"#, esc(s));
+        }
+        Cell::Int(v) => { let _ = write!(x, r#"<c r="{at}" s="2"><v>{v}</v></c>"#); }
+        Cell::Num(v) => number(x, *v, 3),
+        Cell::Pct(v) => number(x, *v, 4),
+    }
+}
+
+fn sheet_xml(sheet: &Sheet) -> String {
+    let header: Vec<Cell> = sheet.header.iter().map(|h| Cell::Text(h.clone())).collect();
+    let n_cols = sheet.rows.iter().map(Vec::len).chain([header.len()]).max().unwrap_or(0);
+    let mut widths = vec![8usize; n_cols];
+    for row in std::iter::once(&header).chain(&sheet.rows) {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            let len = match cell {
+                Cell::Empty => 0,
+                Cell::Text(s) => text_width(s),
+                Cell::Int(v) => v.to_string().len(),
+                Cell::Num(_) | Cell::Pct(_) => 10,
+            };
+            *w = (*w).max(len + 2).min(60);
+        }
+    }
+
+    let mut x = format!(r#"{XML_HEAD}<worksheet xmlns="{NS_MAIN}" xmlns:r="{NS_REL}">"#);
+    if !header.is_empty() {
+        x.push_str(r#"<sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#);
+    }
+    if n_cols > 0 {
+        x.push_str("<cols>");
+        for (i, w) in widths.iter().enumerate() {
+            let _ = write!(x, r#"<col min="{0}" max="{0}" width="{w}" customWidth="1"/>"#, i + 1);
+        }
+        x.push_str("</cols>");
+    }
+    x.push_str("<sheetData>");
+    let offset = usize::from(!header.is_empty());
+    for (r, row) in std::iter::once(&header).filter(|h| !h.is_empty()).chain(&sheet.rows).enumerate() {
+        let _ = write!(x, r#"<row r="{}">"#, r + 1);
+        for (c, cell) in row.iter().enumerate() { cell_xml(&mut x, r + 1, c, cell, r < offset); }
+        x.push_str("</row>");
+    }
+    x.push_str("</sheetData></worksheet>");
+    x
+}
+
+/* ─────────────────────────── Stored zip ─────────────────────────── */
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, t) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 { c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 }; }
+        *t = c;
+    }
+    !data.iter().fold(!0u32, |c, &b| table[((c ^ b as u32) & 0xFF) as usize] ^ (c >> 8))
+}
+
+#[derive(Default)]
+struct Zip {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl Zip {
+    fn add(&mut self, name: &str, data: &str) {
+        let (name, data) = (name.as_bytes(), data.as_bytes());
+        let (crc, size, offset) = (crc32(data), data.len() as u32, self.out.len() as u32);
+        // version 2.0, UTF-8 names, stored, 1980-01-01 00:00
+        let common = |v: &mut Vec<u8>| {
+            v.extend_from_slice(&20u16.to_le_bytes());
+            v.extend_from_slice(&0x0800u16.to_le_bytes());
+            v.extend_from_slice(&0u16.to_le_bytes());
+            v.extend_from_slice(&0u16.to_le_bytes());
+            v.extend_from_slice(&0x21u16.to_le_bytes());
+            v.extend_from_slice(&crc.to_le_bytes());
+            v.extend_from_slice(&size.to_le_bytes());
+            v.extend_from_slice(&size.to_le_bytes());
+            v.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            v.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        self.out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        common(&mut self.out);
+        self.out.extend_from_slice(name);
+        self.out.extend_from_slice(data);
+
+        self.central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        common(&mut self.central);
+        // comment length, disk number, internal and external attributes
+        self.central.extend_from_slice(&[0u8; 10]);
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name);
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let (cd_offset, cd_size) = (self.out.len() as u32, self.central.len() as u32);
+        self.out.append(&mut self.central);
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&[0u8; 4]);
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&cd_size.to_le_bytes());
+        self.out.extend_from_slice(&cd_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes());
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(hay: &[u8], needle: &[u8]) -> Option<usize> {
+        hay.windows(needle.len()).position(|w| w == needle)
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn column_letters() {
+        assert_eq!([0, 25, 26, 701, 702].map(column), ["A", "Z", "AA", "ZZ", "AAA"]);
+    }
+
+    #[test]
+    fn sheet_names_follow_excel_rules() {
+        let mut wb = Workbook::default();
+        wb.add_sheet("牛仔/淨係[1]:*?", &[]);
+        wb.add_sheet("  ", &[]);
+        let long = "淨係牛仔拎咗枝鉛筆".repeat(4);
+        wb.add_sheet(&long, &[]);
+        wb.add_sheet(&long, &[]);
+        wb.add_sheet("Items", &[]);
+        wb.add_sheet("ITEMS", &[]);
+        let names = wb.sheet_names();
+        assert_eq!(names[0], "牛仔_淨係_1____");
+        assert_eq!(names[1], "Sheet");
+        assert_eq!(names[2], long.chars().take(31).collect::<String>());
+        assert_eq!(names[3], format!("{} (2)", long.chars().take(27).collect::<String>()));
+        assert_eq!(names[3].chars().count(), MAX_SHEET_NAME);
+        assert_eq!(names[5], "ITEMS (2)");
+    }
+
+    #[test]
+    fn workbook_is_a_stored_zip_with_cjk_text() {
+        let mut wb = Workbook::default();
+        let sheet = wb.add_sheet("結果", &["參與者", "n"]);
+        sheet.push(vec!["牛仔 & 豬仔".into(), Cell::Int(3)]);
+        wb.add_sheet("notes", &["note"]);
+        let bytes = wb.to_bytes();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+
+        let eocd = find(&bytes, b"PK\x05\x06").expect("end of central directory");
+        let entries = u16::from_le_bytes([bytes[eocd + 10], bytes[eocd + 11]]);
+        // content types, root rels, workbook, workbook rels, styles + one part per sheet
+        assert_eq!(entries, 7);
+
+        // local entry: name directly followed by the stored part
+        let start = find(&bytes, b"xl/worksheets/sheet1.xml<?xml").expect("sheet1 entry");
+        let end = find(&bytes, b"xl/worksheets/sheet2.xml<?xml").expect("sheet2 entry");
+        let sheet1 = String::from_utf8_lossy(&bytes[start..end]);
+        assert!(sheet1.contains("<t xml:space=\"preserve\">參與者</t>"));
+        assert!(sheet1.contains("牛仔 &amp; 豬仔"));
+        assert!(String::from_utf8_lossy(&bytes).contains(r#"<sheet name="結果" sheetId="1""#));
+    }
+}
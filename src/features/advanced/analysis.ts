@@ -1,4 +1,5 @@
 import type { BoxTypes, GazeData } from "@/features/gaze/types";
+import { timeMs } from "@/shared/time";
 
 export type BinSummary = { bluePct: number; redPct: number; validPct: number; blueN: number; redN: number; validN: number };
 
@@ -13,7 +14,7 @@ export function buildBins(
 ): BinSummary[] {
   const bins: { total: number; invalid: number; blue: number; red: number }[] = Array.from({ length: numBins }, () => ({ total: 0, invalid: 0, blue: 0, red: 0 }));
   for (const g of gaze) {
-    const ts = +new Date(g.timestamp);
+    const ts = timeMs(g);
     const rel = ts - anchorAbsMs;
     if (rel < 0) continue;
     const idx = Math.floor(rel / Math.max(1, binMs));
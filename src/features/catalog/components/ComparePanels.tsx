@@ -7,6 +7,7 @@ import { LineChart } from "@/components/ui/charts";
 import JsonViewer from "@/components/ui/json-viewer";
 import { Skeleton } from "@/components/ui/skeleton";
 import { isLoading } from "@/shared/loading";
+import { timeMs } from "@/shared/time";
 import type { BoxTypes, TimelineRecording, WordWindow } from "../types";
 import { getTestImage, getTimelineRecordings, getWordWindows } from "../services/catalogApi";
 import { getStatic } from "@/shared/tauriClient";
@@ -286,7 +287,7 @@ export default function ComparePanels(p: Props) {
   const imgUrl2 = () => img2B64() ? `data:image/png;base64,${img2B64()}` : null;
 
   // Minimal local type for gaze points used here
-  type GD = { gaze_x: number | null; gaze_y: number | null; box_name: string; timestamp: string };
+  type GD = { gaze_x: number | null; gaze_y: number | null; box_name: string; time_us: number | null };
 
   const leftGaze  = () => series1()?.gaze ?? [];
   const leftBase  = () => series1()?.baseMs ?? 0;
@@ -295,10 +296,10 @@ export default function ComparePanels(p: Props) {
 
   const replayPts1 = () => leftGaze()
     .filter((g: GD) => g.gaze_x !== null && g.gaze_y !== null && g.box_name !== "missing" && g.box_name !== "out_of_screen")
-    .map((g: GD) => ({ t: (+new Date(g.timestamp) - leftBase()) / 1000, x: g.gaze_x as number, y: g.gaze_y as number }));
+    .map((g: GD) => ({ t: (timeMs(g) - leftBase()) / 1000, x: g.gaze_x as number, y: g.gaze_y as number }));
   const replayPts2 = () => rightGaze()
     .filter((g: GD) => g.gaze_x !== null && g.gaze_y !== null && g.box_name !== "missing" && g.box_name !== "out_of_screen")
-    .map((g: GD) => ({ t: (+new Date(g.timestamp) - rightBase()) / 1000, x: g.gaze_x as number, y: g.gaze_y as number }));
+    .map((g: GD) => ({ t: (timeMs(g) - rightBase()) / 1000, x: g.gaze_x as number, y: g.gaze_y as number }));
 
   let canvas1El: HTMLCanvasElement | null = null;
   let canvas2El: HTMLCanvasElement | null = null;
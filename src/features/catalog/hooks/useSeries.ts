@@ -1,6 +1,7 @@
 import { createEffect, createSignal } from "solid-js";
 import type { BoxTypes, GazeData } from "../types";
 import { getGazeData } from "../services/catalogApi";
+import { timeMs, timeSpanMs } from "@/shared/time";
 
 type Out = { datasets: any[]; xMax: number; xMaxBinned: number; gaze: GazeData[]; baseMs: number };
 
@@ -33,7 +34,7 @@ export function useSeries(params: () => {
 
     if (!gaze.length) { setSeries({ datasets: [], xMax: 0, xMaxBinned: 0, gaze: [], baseMs: 0 }); return; }
 
-    const baseMs = +new Date(gaze[0].timestamp);
+    const baseMs = timeSpanMs(gaze)[0];
     const ms = Math.max(1, p.binMs);
 
     type Acc = { blue: number; red: number; tot: number; invalid: number };
@@ -42,7 +43,7 @@ export function useSeries(params: () => {
 
     for (const g of gaze) {
       const b = g.box_name as BoxTypes;
-      const t = +new Date(g.timestamp) - baseMs;
+      const t = timeMs(g) - baseMs;
       const key = Math.floor(t / ms) * ms;
 
       const rec = bins.get(key) ?? { blue: 0, red: 0, tot: 0, invalid: 0 };
@@ -64,6 +64,8 @@ const GazeSchema: z.ZodType<GazeData> = z
     participant: z.string(),
     recording: z.string(),
     timestamp: z.string(),
+    time_us: z.number().nullable(),
+    rel_ms: z.number().nullable(),
     test_name: z.string(),
   })
   .catchall(z.any());
@@ -34,6 +34,8 @@ export type GazeData = {
   participant: string;
   recording: string;
   timestamp: string;
+  time_us: number | null;
+  rel_ms: number | null;
   test_name: string;
 };
 
@@ -10,14 +10,14 @@ type Sets = Record<
 >;
 
 type Props = {
-  rows: { timestamp: string } & Record<string, number>[];
+  rows: ({ timestamp: string; time_ms: number } & Record<string, number>)[];
   baseMs: number;
   viewSec: number;
   sets: Sets;
 };
 
 export default function AoiSetChart(p: Props) {
-  const dat = () => p.rows.map(r => ({ t: (+new Date(r.timestamp) - p.baseMs) / 1000, ...r })) as RowPoint[];
+  const dat = () => p.rows.map(r => ({ t: (r.time_ms - p.baseMs) / 1000, ...r })) as RowPoint[];
 
   const mk = (label: string, boxes: Set<BoxTypes> | undefined, color: string, dash: number[] = []) => {
     if (!boxes || boxes.size === 0) return null;
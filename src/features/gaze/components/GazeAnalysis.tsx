@@ -2,6 +2,7 @@ import { createEffect, createMemo, createSignal, Show } from "solid-js";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Skeleton } from "@/components/ui/skeleton";
 import { isLoading } from "@/shared/loading";
+import { timeMs } from "@/shared/time";
 
 import ControlsBar from "./ControlsBar";
 import TimelineChart from "./TimelineChart";
@@ -154,7 +155,7 @@ export default function GazeAnalysis() {
 
   // Build a copy of the Timeline dataset for JSON viewing (mirrors TimelineChart)
   const timelineDataset = createMemo(() => {
-    const dat = Q.rows().map(r => ({ t: (+new Date(r.timestamp) - Q.baseMs()) / 1000, ...r }));
+    const dat = Q.rows().map(r => ({ t: (r.time_ms - Q.baseMs()) / 1000, ...r }));
     const keys = Object.keys(DEFAULT_COLORS) as BoxTypes[];
     const ds = keys.map((b: BoxTypes) => {
       const sel = Q.selectedBoxes();
@@ -330,7 +331,7 @@ export default function GazeAnalysis() {
                   let t0 = 0;
                   for (const d of g) {
                     if (d.box_name !== 'missing' && d.box_name !== 'out_of_screen' && d.gaze_x !== null && d.gaze_y !== null) {
-                      t0 = Math.max(0, (+new Date(d.timestamp) - base) / 1000);
+                      t0 = Math.max(0, (timeMs(d) - base) / 1000);
                       break;
                     }
                   }
@@ -4,7 +4,7 @@ import type { MetaKey, WordWindow } from "../types";
 import { DEFAULT_COLORS } from "../constants";
 import { parseAOISet } from "../utils";
 
-type RowPoint = { timestamp: string } & Record<string, number>;
+type RowPoint = { timestamp: string; time_ms: number } & Record<string, number>;
 type Props = {
   rows: RowPoint[];
   baseMs: number;
@@ -22,7 +22,7 @@ type Props = {
 const META_KEYS: MetaKey[] = ["self_AOIs","correct_AOIs","potentially_correct_AOIs","incorrect_AOIs","correct_NULL","potentially_correct_NULL","incorrect_NULL"];
 
 export default function TimelineChart(p: Props) {
-  const dat = () => p.rows.map(r => ({ t: (+new Date(r.timestamp) - p.baseMs) / 1000, ...r }));
+  const dat = () => p.rows.map(r => ({ t: (r.time_ms - p.baseMs) / 1000, ...r }));
 
   const ds = () => {
     const keys = Object.keys(DEFAULT_COLORS);
@@ -7,6 +7,7 @@ import {
   getParticipants, getTestImage, getTestNames, getTimelineRecordings, getWordWindows,
 } from "../services/gazeApi";
 import { getStatic } from "@/shared/tauriClient";
+import { timeMs, timeSpanMs } from "@/shared/time";
 import { DEFAULT_COLORS } from "../constants";
 import { calcWholeStats, parseAOISet, parsePercent } from "../utils";
 
@@ -110,7 +111,7 @@ export function useGazeQuery() {
   /* rows (time bins) */
   const [intervalMs, setIntervalMs] = createSignal(100);
   const [rows, setRows] = createSignal<any[]>([]);
-  const baseMs = () => (gaze().length ? +new Date(gaze()[0].timestamp) : 0);
+  const baseMs = () => timeSpanMs(gaze())[0];
 
   /* view config */
   const [pxPerSec, setPxPerSec] = createSignal(40);
@@ -121,17 +122,14 @@ export function useGazeQuery() {
 
   /* durations */
   const rawDurationSec = createMemo(() => {
-    const g = gaze();
-    if (!g.length) return 0;
-    const first = +new Date(g[0].timestamp);
-    const last = +new Date(g[g.length - 1].timestamp);
+    const [first, last] = timeSpanMs(gaze());
     return Math.max(0, (last - first) / 1000);
   });
   const binnedDurationSec = createMemo(() => {
     const b = baseMs();
     const r = rows();
     if (!r.length || !b) return 0;
-    const last = +new Date(r[r.length - 1].timestamp);
+    const last = r[r.length - 1].time_ms;
     return Math.max(0, (last - b) / 1000);
   });
 
@@ -316,17 +314,18 @@ export function useGazeQuery() {
     if (!gaze().length) { setRows([]); return; }
     const ms = Math.max(1, intervalMs());
     const bins: Record<number, any> = {};
-    [...gaze()].sort((a, b) => +new Date(a.timestamp) - +new Date(b.timestamp))
+    // rows arrive sorted by parsed time (malformed, NaN, times last and left out here)
+    gaze().filter(pt => pt.time_us != null)
       .forEach(pt => {
-        const k = Math.floor(+new Date(pt.timestamp) / ms) * ms;
+        const k = Math.floor(timeMs(pt) / ms) * ms;
         bins[k] ??= { timestamp: k, total: 0, ...Object.fromEntries(Object.keys(DEFAULT_COLORS).map(b => [b, 0])) };
         bins[k][pt.box_name]++; bins[k].total++;
       });
     const r = Object.values(bins).map(g => {
-      const o: Record<string, any> = { timestamp: new Date(g.timestamp).toISOString() };
+      const o: Record<string, any> = { timestamp: new Date(g.timestamp).toISOString(), time_ms: g.timestamp };
       Object.keys(DEFAULT_COLORS).forEach(b => o[b] = g.total ? ((g[b] || 0) / g.total) * 100 : 0);
       return o;
-    }).sort((a, b) => +new Date(a.timestamp) - +new Date(b.timestamp));
+    }).sort((a, b) => a.time_ms - b.time_ms);
     setRows(r);
   });
   // participants allowed by selected test (sync from static map) ??no auto-clears
@@ -1,5 +1,6 @@
 import { createMemo, createSignal } from "solid-js";
 import { usePlayback } from "@/shared/hooks/usePlayback";
+import { timeMs, timeSpanMs } from "@/shared/time";
 import { timeColor } from "../utils";
 import type { GazeData, WordWindow } from "../types";
 
@@ -21,7 +22,7 @@ export function useReplay(params: () => {
       const g = params().gaze;
       const base = params().baseMs;
       const ptsInWin = g.filter(p => {
-        const t = (+new Date(p.timestamp) - base) / 1000;
+        const t = (timeMs(p) - base) / 1000;
         return t >= cur.start_sec && t <= cur.end_sec;
       });
       const valid = ptsInWin.filter(p => p.box_name !== "missing").length;
@@ -65,12 +66,11 @@ export function useReplay(params: () => {
     // Build the points (still filtering for drawing)
     const pts = g
       .filter(d => d.gaze_x !== null && d.gaze_y !== null && d.box_name !== "missing" && d.box_name !== "out_of_screen")
-      .map(d => ({ t: (+new Date(d.timestamp) - base) / 1000, x: d.gaze_x!, y: d.gaze_y! }));
+      .map(d => ({ t: (timeMs(d) - base) / 1000, x: d.gaze_x!, y: d.gaze_y! }));
 
     // Compute duration from the *full* time range, not just the filtered list
     if (g.length) {
-      const first = +new Date(g[0].timestamp);
-      const last  = +new Date(g[g.length - 1].timestamp);
+      const [first, last] = timeSpanMs(g);
       setDuration(Math.max(0, (last - first) / 1000));
       setCurTime(0);
       setReady(true);
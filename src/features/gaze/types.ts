@@ -9,6 +9,8 @@ export interface GazeData {
   gaze_y: number | null;
   box_name: string;
   timestamp: string;
+  time_us: number | null;
+  rel_ms: number | null;
   participant: string;
   test_name: string;
   recording?: string;
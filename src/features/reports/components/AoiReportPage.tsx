@@ -9,6 +9,7 @@ import JsonViewer from "@/components/ui/json-viewer";
 import { getAllCatalog, getGazeData, getParticipants, getTimelineRecordings, getWordWindows } from "@/features/gaze/services/gazeApi";
 import type { BoxTypes } from "@/features/gaze/types";
 import { parseAOISet } from "@/features/gaze/utils";
+import { timeMs } from "@/shared/time";
 
 // Catalog helpers
 import { ALL_AOI_KEYS, AOI_KEY_LABEL, AOI_CODE_TO_BOX } from "@/features/catalog/constants";
@@ -103,7 +104,7 @@ export default function AoiReportPage() {
 
     const data = await getGazeData({ testName: t, participants: [p], timeline: tl, recording: rc }).catch(() => []);
     if (!data.length) { setRows([]); return; }
-    const baseMs = +new Date(data[0].timestamp);
+    const baseMs = timeMs(data[0]);
 
     // anchor absolute milliseconds
     let anchorAbs = baseMs + (analysisStartMs() || 0);
@@ -117,7 +118,7 @@ export default function AoiReportPage() {
     const bins: { box: string }[][] = Array.from({ length: numBins() }, () => []);
     const inv = new Set<string>(invalidCats());
     for (const g of data) {
-      const ts = +new Date(g.timestamp);
+      const ts = timeMs(g);
       const rel = ts - anchorAbs; // ms
       if (rel <= 0) continue;
       const idx = Math.floor(rel / Math.max(1, binMs()));
@@ -1,7 +1,7 @@
 import { createEffect, createSignal, Show } from "solid-js";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
-import { getDisabledSlicesRaw, setDisabledSlicesRaw } from "@/shared/tauriClient";
+import { getApiServerRaw, getDisabledSlicesRaw, setDisabledSlicesRaw, startApiServerRaw, stopApiServerRaw, type ApiInfo } from "@/shared/tauriClient";
 import { saveXaiApiKey, getXaiApiKey } from "@/shared/ai";
 import { TextField, TextFieldInput } from "@/components/ui/text-field";
 import JsonViewer from "@/components/ui/json-viewer";
@@ -15,6 +15,9 @@ export default function SettingsPage() {
   const [saved, setSaved] = createSignal(false);
   const [storeDump, setStoreDump] = createSignal<Record<string, unknown> | null>(null);
   const [disabledJson, setDisabledJson] = createSignal<DisabledSlice[]>([]);
+  const [api, setApi] = createSignal<ApiInfo | null>(null);
+  const [apiPort, setApiPort] = createSignal("8765");
+  const [apiError, setApiError] = createSignal<string | null>(null);
   const envKey = (import.meta as any).env?.VITE_XAI_API_KEY as string | undefined;
   const usingEnv = !!(envKey && envKey.trim());
 
@@ -24,6 +27,7 @@ export default function SettingsPage() {
     setDisabledJson(rows as DisabledSlice[]);
     const dump = await loadStoreDump().catch(() => ({}));
     setStoreDump(dump);
+    setApi(await getApiServerRaw().catch(() => null));
   }
 
   createEffect(() => { refresh(); (async () => setApiKey((await getXaiApiKey()) ?? ""))(); });
@@ -38,6 +42,16 @@ export default function SettingsPage() {
     }
   }
 
+  async function toggleApi() {
+    setApiError(null);
+    try {
+      if (api()) { await stopApiServerRaw(); setApi(null); }
+      else setApi(await startApiServerRaw({ port: Number(apiPort()) || undefined }));
+    } catch (e) {
+      setApiError(String(e));
+    }
+  }
+
   return (
     <div class="space-y-6">
       <Card>
@@ -99,6 +113,33 @@ export default function SettingsPage() {
               <div class="text-xs text-muted-foreground">To change the key, edit your .env.local and restart the app.</div>
             </Show>
           </div>
+          <div class="rounded border p-3 space-y-2">
+            <div class="flex items-center justify-between">
+              <div class="font-medium">Local HTTP API</div>
+              <Show when={api()}>
+                <span class="text-[11px] px-2 py-0.5 rounded bg-emerald-100 text-emerald-800 border border-emerald-300">Running</span>
+              </Show>
+            </div>
+            <div class="text-xs text-muted-foreground">Serves gaze data, box stats, test/slice search and disabled slices as JSON on 127.0.0.1 for notebooks (Python, R). Requests need the token below.</div>
+            <div class="flex items-end gap-2">
+              <TextField value={apiPort()} onChange={setApiPort}>
+                <TextFieldInput type="number" class="w-[120px]" disabled={!!api()} />
+              </TextField>
+              <Button variant="outline" onClick={toggleApi}>{api() ? "Stop" : "Start"}</Button>
+              <Show when={apiError()}>
+                <span class="text-xs text-destructive">{apiError()}</span>
+              </Show>
+            </div>
+            <Show when={api()}>
+              <div class="text-xs space-y-1">
+                <div>URL: <code>{api()!.url}</code></div>
+                <div class="flex items-center gap-2">
+                  <span>Token: <code>{api()!.token}</code></span>
+                  <Button size="sm" variant="outline" onClick={() => navigator.clipboard.writeText(api()!.token).catch(() => {})}>Copy</Button>
+                </div>
+              </div>
+            </Show>
+          </div>
           <div class="rounded border p-3 space-y-2">
             <div class="flex items-center justify-between">
               <div class="font-medium">Disabled Slices</div>
@@ -1,16 +1,45 @@
-import { onMount } from "solid-js";
+import { For, Show, createSignal, onCleanup, onMount } from "solid-js";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Button } from "@/components/ui/button";
+
+type HealthIssue = { severity: "info" | "warning" | "fatal"; message: string };
+type HealthReport = { ok: boolean; db_path: string; issues: HealthIssue[] };
 
 export default function Splashscreen() {
+  const [report, setReport] = createSignal<HealthReport | null>(null);
+
   onMount(() => {
-    // Main window will signal frontend readiness; nothing to do here.
+    // Main window signals frontend readiness; the splash only shows fatal DB problems.
+    const unlisten = listen<HealthReport>("database-health", (e) => setReport(e.payload));
+    invoke<HealthReport | null>("get_database_health").then((r) => { if (r) setReport(r); }).catch(() => {});
+    onCleanup(() => { unlisten.then((f) => f()); });
   });
 
+  const fatal = () => (report()?.issues ?? []).filter((i) => i.severity === "fatal");
+
   return (
     <div class="min-h-screen flex items-center justify-center bg-background">
-      <div class="flex flex-col items-center gap-4">
-        <div class="h-12 w-12 rounded-full border-2 border-primary border-t-transparent animate-spin" />
-        <div class="text-sm text-muted-foreground">Starting up…</div>
-      </div>
+      <Show
+        when={report() && !report()!.ok}
+        fallback={
+          <div class="flex flex-col items-center gap-4">
+            <div class="h-12 w-12 rounded-full border-2 border-primary border-t-transparent animate-spin" />
+            <div class="text-sm text-muted-foreground">Starting up…</div>
+          </div>
+        }
+      >
+        <div class="flex max-w-md flex-col gap-3 p-6">
+          <div class="text-sm font-medium text-destructive">The database failed validation</div>
+          <div class="break-all text-xs text-muted-foreground">{report()!.db_path}</div>
+          <ul class="list-disc pl-5 text-xs">
+            <For each={fatal()}>{(i) => <li>{i.message}</li>}</For>
+          </ul>
+          <Button size="sm" variant="outline" onClick={() => invoke("set_complete", { task: "acknowledge" }).catch(() => {})}>
+            Continue anyway
+          </Button>
+        </div>
+      </Show>
     </div>
   );
 }
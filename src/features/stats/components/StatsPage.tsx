@@ -7,6 +7,7 @@ import { LineChart } from "@/components/ui/charts";
 import { getAllCatalog, getGazeData, getParticipants, getTimelineRecordings } from "@/features/gaze/services/gazeApi";
 import JsonViewer from "@/components/ui/json-viewer";
 import { parseAOISet } from "@/features/gaze/utils";
+import { timeMs } from "@/shared/time";
 
 type SeriesPoint = { x: number; y: number };
 
@@ -75,10 +76,10 @@ export default function StatsPage() {
 
     // bin
     const ms = Math.max(1, binMs());
-    const base = +new Date(data[0].timestamp);
+    const base = timeMs(data[0]);
     const bins: Record<number, { total: number; blue: number; red: number }> = {};
     for (const g of data) {
-      const k = Math.floor(+new Date(g.timestamp) / ms) * ms;
+      const k = Math.floor(timeMs(g) / ms) * ms;
       const b = (bins[k] ||= { total: 0, blue: 0, red: 0 });
       b.total++;
       const box = g.box_name;
@@ -1,4 +1,5 @@
-import { createMemo, createResource, createSignal } from "solid-js";
+import { createMemo, createResource, createSignal, onCleanup } from "solid-js";
+import { listen } from "@tauri-apps/api/event";
 import type { DisabledSlice } from "@/shared/type";
 import { getDisabledSlices, listGazeSlices, toggleDisabledSlice } from "@/features/toggles/services/togglesApi";
 
@@ -7,6 +8,9 @@ export function useDisabledSlices() {
   const [participants, setParticipants] = createSignal<string[]>([]);
 
   const [disabled, { mutate: mutateDisabled, refetch: refetchDisabled }] = createResource(async () => getDisabledSlices());
+  // the local HTTP API can change disabled slices behind the UI's back
+  const unlisten = listen("disabled-slices-changed", () => { refetchDisabled(); });
+  onCleanup(() => { unlisten.then((f) => f()); });
   const [candidates] = createResource(() => ({ t: testName(), ps: participants() }), async (p) => listGazeSlices({ testName: p.t, participants: p.ps }));
 
   const isDisabled = (s: DisabledSlice) => {
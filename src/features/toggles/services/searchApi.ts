@@ -13,8 +13,8 @@ const SearchTestRowSchema: z.ZodType<SearchTestRow> = z.object({
   png_triples: z.number().nullable().optional(),
 });
 
-export async function searchTests(): Promise<SearchTestRow[]> {
-  const raw = await searchTestsRaw();
+export async function searchTests(params: { cohort?: string | null } = {}): Promise<SearchTestRow[]> {
+  const raw = await searchTestsRaw(params);
   return z.array(SearchTestRowSchema).parse(raw);
 }
 
@@ -83,6 +83,7 @@ export async function getWordWindows(params: {
 export async function getTestImage(params: {
   testName: string;
   timeline?: string | null;
+  cohort?: string | null;
 }): Promise<string | null> {
   const raw = await getTestImageRaw(params);
   return raw as string | null;
@@ -6,7 +6,9 @@ import type { DisabledSlice, SearchTestRow, SearchSliceRow } from "@/shared/type
 
 /** cache StaticData once per app run */
 let _staticData: Promise<StaticData> | null = null;
-export async function getStatic(): Promise<StaticData> {
+export async function getStatic(cohort?: string | null): Promise<StaticData> {
+  // cohort-filtered bootstrap data is not cached
+  if (cohort) return withLoading(invoke<StaticData>("get_static_data", { cohort }));
   if (!_staticData) {
     _staticData = withLoading(invoke<StaticData>("get_static_data")).catch((err) => {
       _staticData = null; // allow retry
@@ -16,6 +18,11 @@ export async function getStatic(): Promise<StaticData> {
   return _staticData;
 }
 
+/** drop the cached StaticData (the active database changed) */
+export function resetStaticCache() {
+  _staticData = null;
+}
+
 /** always provide BOTH keys expected by the Rust side */
 export function bothTestNames(name?: string | null) {
   const v = (name ?? "").toString();
@@ -30,13 +37,25 @@ export function bothTestNames(name?: string | null) {
 export async function getTimelineRecordingsRaw(params: {
   testName: string;
   participants: string[];
+  cohort?: string | null;
 }): Promise<unknown> {
   return withLoading(invoke("get_timeline_recordings", {
     ...bothTestNames(params.testName),
     participants: params.participants,
+    cohort: params.cohort ?? null,
   }));
 }
 
+/** fixed-grid resampling: Box from the nearest (or held) sample, coordinates interpolated */
+export type ResampleParam = { rate_hz?: number; box_rule?: "nearest" | "hold"; max_gap_ms?: number };
+
+/** coordinate filters, applied in order per recording (Box untouched) */
+export type SmoothFilterParam =
+  | { kind: "median"; window?: number }
+  | { kind: "savitzky_golay"; window?: number; order?: number }
+  | { kind: "one_euro"; min_cutoff?: number; beta?: number; d_cutoff?: number };
+export type SmoothingParam = { filters: SmoothFilterParam[]; max_gap_ms?: number };
+
 export async function getGazeDataRaw(params: {
   testName: string;
   participants: string[];
@@ -44,6 +63,9 @@ export async function getGazeDataRaw(params: {
   recording?: string | null;
   limit?: number | null;
   offset?: number | null;
+  cohort?: string | null;
+  resample?: ResampleParam | null;
+  smoothing?: SmoothingParam | null;
 }): Promise<unknown> {
   return withLoading(invoke("get_gaze_data", {
     ...bothTestNames(params.testName),
@@ -52,6 +74,36 @@ export async function getGazeDataRaw(params: {
     recording: params.recording ?? null,
     limit: params.limit ?? null,
     offset: params.offset ?? null,
+    cohort: params.cohort ?? null,
+    resample: params.resample ?? null,
+    smoothing: params.smoothing ?? null,
+  }));
+}
+
+/** gaze rows plus `meta` (resample / smoothing as applied, row and recording counts, app version) */
+export async function getGazeStreamRaw(params: {
+  testName: string;
+  participants: string[];
+  timeline?: string | null;
+  recording?: string | null;
+  limit?: number | null;
+  offset?: number | null;
+  cohort?: string | null;
+  resample?: ResampleParam | null;
+  smoothing?: SmoothingParam | null;
+}): Promise<unknown> {
+  return withLoading(invoke("get_gaze_stream", {
+    query: {
+      test_name: params.testName,
+      participants: params.participants,
+      timeline: params.timeline ?? null,
+      recording: params.recording ?? null,
+      limit: params.limit ?? null,
+      offset: params.offset ?? null,
+      cohort: params.cohort ?? null,
+      resample: params.resample ?? null,
+      smoothing: params.smoothing ?? null,
+    },
   }));
 }
 
@@ -60,40 +112,51 @@ export async function getBoxStatsRaw(params: {
   participants: string[];
   timeline?: string | null;
   recording?: string | null;
+  transforms?: boolean | null;
+  cohort?: string | null;
+  resample?: ResampleParam | null;
+  manifest?: boolean;
 }): Promise<unknown> {
   return withLoading(invoke("get_box_stats", {
     ...bothTestNames(params.testName),
     participants: params.participants,
     timeline: params.timeline ?? null,
     recording: params.recording ?? null,
+    transforms: params.transforms ?? null,
+    cohort: params.cohort ?? null,
+    resample: params.resample ?? null,
+    manifest: params.manifest ?? null,
   }));
 }
 
 export async function getTestImageRaw(params: {
   testName: string;
   timeline?: string | null;
+  cohort?: string | null;
 }): Promise<unknown> {
   return withLoading(invoke("get_test_image", {
     ...bothTestNames(params.testName),
     timeline: params.timeline ?? null,
+    cohort: params.cohort ?? null,
   }));
 }
 
-export async function getParticipantsForTestRaw(params: { testName: string }): Promise<unknown> {
-  return withLoading(invoke("get_participants_for_test", bothTestNames(params.testName)));
+export async function getParticipantsForTestRaw(params: { testName: string; cohort?: string | null }): Promise<unknown> {
+  return withLoading(invoke("get_participants_for_test", { ...bothTestNames(params.testName), cohort: params.cohort ?? null }));
 }
 
-export async function getTestsForParticipantRaw(params: { participant: string }): Promise<unknown> {
-  return withLoading(invoke("get_tests_for_participant", { participant: params.participant }));
+export async function getTestsForParticipantRaw(params: { participant: string; cohort?: string | null }): Promise<unknown> {
+  return withLoading(invoke("get_tests_for_participant", { participant: params.participant, cohort: params.cohort ?? null }));
 }
 
 // Disabled slices + listing helpers
-export async function listGazeSlicesRaw(params: { testName?: string; participants?: string[] } = {}): Promise<unknown> {
+export async function listGazeSlicesRaw(params: { testName?: string; participants?: string[]; cohort?: string | null } = {}): Promise<unknown> {
   const t = params.testName ?? null;
   return withLoading(invoke("list_gaze_slices", {
     test_name: t,
     testName: t,
     participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
   }));
 }
 
@@ -109,28 +172,691 @@ export async function toggleDisabledSliceRaw(slice: DisabledSlice, disabled: boo
   return withLoading(invoke("toggle_disabled_slice", { slice, disabled }));
 }
 
-export async function searchTestsRaw(): Promise<SearchTestRow[]> {
-  return withLoading(invoke("search_tests"));
+export async function searchTestsRaw(params: { cohort?: string | null } = {}): Promise<SearchTestRow[]> {
+  return withLoading(invoke("search_tests", { cohort: params.cohort ?? null }));
 }
 
-export async function searchSlicesRaw(params: { testName?: string; participants?: string[] } = {}): Promise<SearchSliceRow[]> {
+export async function searchSlicesRaw(params: { testName?: string; participants?: string[]; cohort?: string | null } = {}): Promise<SearchSliceRow[]> {
   const t = params.testName ?? null;
-  return withLoading(invoke("search_slices", { test_name: t, testName: t, participants: params.participants ?? [] }));
+  return withLoading(invoke("search_slices", { test_name: t, testName: t, participants: params.participants ?? [], cohort: params.cohort ?? null }));
 }
 
 // participants table (full rows)
-export async function getParticipantsTableRaw(): Promise<RowMap[]> {
-  return withLoading(invoke("get_participants"));
+export async function getParticipantsTableRaw(params: { cohort?: string | null } = {}): Promise<RowMap[]> {
+  return withLoading(invoke("get_participants", { cohort: params.cohort ?? null }));
 }
 
 export async function getAllParticipantSessionsRaw(params: {
   tests: string[];
   participants: string[];
+  cohort?: string | null;
 }): Promise<unknown> {
   return withLoading(invoke("get_all_participant_sessions", {
     tests: params.tests,
     participants: params.participants,
+    cohort: params.cohort ?? null,
   }));
 }
 
 // (dedupe guard) — function defined once
+
+/* ──────────────────────────────────────────────────────────────
+   Server-side binning + analyses
+   ────────────────────────────────────────────────────────────── */
+
+export type AoiSetsParam = { blue_keys?: string[]; red_keys?: string[]; invalid?: string[] };
+export type BinParamsParam = { bin_ms?: number; num_bins?: number; start_ms?: number; anchor_word?: string | null; shift_ms?: number; resample?: ResampleParam | null };
+export type ConditionParam = { name: string; tests: string[] };
+export type ProportionScale = "proportion" | "elog" | "arcsine";
+export type Correction = "none" | "bonferroni" | "holm" | "fdr";
+export type TfceParam = { e?: number; h?: number; steps?: number };
+
+export async function getAoiBinsRaw(params: {
+  tests: string[];
+  participants: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  bins?: BinParamsParam | null;
+  transforms?: boolean | null;
+  manifest?: boolean;
+}): Promise<unknown> {
+  return withLoading(invoke("get_aoi_bins", {
+    tests: params.tests,
+    participants: params.participants,
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    bins: params.bins ?? null,
+    transforms: params.transforms ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+export async function fitGrowthCurveRaw(params: {
+  conditions: ConditionParam[];
+  participants: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  bins?: BinParamsParam | null;
+  options?: { degree?: number | null; scale?: ProportionScale; correction?: Correction } | null;
+  manifest?: boolean;
+}): Promise<unknown> {
+  return withLoading(invoke("fit_growth_curve", {
+    conditions: params.conditions,
+    participants: params.participants,
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    bins: params.bins ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+export type DivergenceOptionsParam = {
+  n_boot?: number;
+  alpha?: number;
+  sustain_ms?: number;
+  ci_level?: number;
+  seed?: number | null;
+  scale?: ProportionScale;
+  correction?: Correction;
+  tfce?: TfceParam | null;
+  n_perm?: number;
+};
+
+export async function getDivergencePointRaw(params: {
+  conditions: ConditionParam[];
+  participants: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  bins?: BinParamsParam | null;
+  options?: DivergenceOptionsParam | null;
+  manifest?: boolean;
+}): Promise<unknown> {
+  return withLoading(invoke("get_divergence_point", {
+    conditions: params.conditions,
+    participants: params.participants,
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    bins: params.bins ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+export type WindowParamsParam = { start_ms?: number; end_ms?: number; anchor_word?: string | null; resample?: ResampleParam | null };
+export type LmmOptionsParam = { fixed?: string[]; reml?: boolean; scale?: ProportionScale; correction?: Correction };
+
+export async function fitLmmRaw(params: {
+  tests: string[];
+  participants: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  window?: WindowParamsParam | null;
+  options?: LmmOptionsParam | null;
+  manifest?: boolean;
+}): Promise<unknown> {
+  return withLoading(invoke("fit_lmm", {
+    tests: params.tests,
+    participants: params.participants,
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    window: params.window ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+export async function adjustPValuesRaw(params: { pValues: (number | null)[]; method: Correction }): Promise<unknown> {
+  return withLoading(invoke("adjust_p_values", { p_values: params.pValues, pValues: params.pValues, method: params.method }));
+}
+
+export async function tfceScoresRaw(params: { statistics: (number | null)[]; options?: TfceParam | null }): Promise<unknown> {
+  return withLoading(invoke("tfce_scores", { statistics: params.statistics, options: params.options ?? null }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Aggregate cache (per-slice Box counts, rebuilt when the DB changes)
+   ────────────────────────────────────────────────────────────── */
+
+export async function getAggregateCacheStatusRaw(): Promise<unknown> {
+  return withLoading(invoke("get_aggregate_cache_status"));
+}
+
+export async function rebuildAggregateCacheRaw(params: { force?: boolean } = {}): Promise<unknown> {
+  return withLoading(invoke("rebuild_aggregate_cache", { force: params.force ?? null }));
+}
+
+/* Index diagnostics (EXPLAIN QUERY PLAN of built-in queries; optional index creation on project DBs) */
+export async function diagnoseIndexesRaw(params: { create?: boolean } = {}): Promise<unknown> {
+  return withLoading(invoke("diagnose_indexes", { create: params.create ?? null }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Study databases (bundled + project DBs, recent list)
+   ────────────────────────────────────────────────────────────── */
+
+export async function listDatabasesRaw(): Promise<unknown> {
+  return withLoading(invoke("list_databases"));
+}
+
+export async function openDatabaseRaw(params: { path: string; activate?: boolean }): Promise<unknown> {
+  const out = await withLoading(invoke("open_database", { path: params.path, activate: params.activate ?? null }));
+  if (params.activate ?? true) resetStaticCache();
+  return out;
+}
+
+export async function switchDatabaseRaw(params: { path: string }): Promise<unknown> {
+  const out = await withLoading(invoke("switch_database", { path: params.path }));
+  resetStaticCache();
+  return out;
+}
+
+export async function closeDatabaseRaw(params: { path?: string | null } = {}): Promise<unknown> {
+  const out = await withLoading(invoke("close_database", { path: params.path ?? null }));
+  resetStaticCache();
+  return out;
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Cohorts (other study DBs attached to the active one)
+   ────────────────────────────────────────────────────────────── */
+
+export type CohortSpecParam = { path: string; name: string };
+
+export async function attachCohortsRaw(params: { cohorts: CohortSpecParam[]; mainName?: string | null }): Promise<unknown> {
+  const out = await withLoading(invoke("attach_cohorts", { cohorts: params.cohorts, main_name: params.mainName ?? null, mainName: params.mainName ?? null }));
+  resetStaticCache();
+  return out;
+}
+
+export async function detachCohortsRaw(): Promise<void> {
+  await withLoading(invoke("detach_cohorts"));
+  resetStaticCache();
+}
+
+export async function getCohortsRaw(): Promise<unknown> {
+  return withLoading(invoke("get_cohorts"));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Schema validation / health check
+   ────────────────────────────────────────────────────────────── */
+
+export async function validateDatabaseRaw(params: { path?: string | null; full?: boolean } = {}): Promise<unknown> {
+  return withLoading(invoke("validate_database", { path: params.path ?? null, full: params.full ?? null }));
+}
+
+export async function getDatabaseHealthRaw(): Promise<unknown> {
+  return withLoading(invoke("get_database_health"));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Saved analyses (stored per database)
+   ────────────────────────────────────────────────────────────── */
+
+export type AnalysisKind = "bins" | "growth" | "divergence" | "lmm" | "workbook" | "items" | "measures" | "transitions" | "participants" | "precision" | "boxstats";
+export type AnalysisSpecParam = {
+  kind: AnalysisKind;
+  conditions: ConditionParam[];
+  participants?: string[];
+  recordings?: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam;
+  bins?: BinParamsParam;
+  window?: WindowParamsParam;
+  exclusions?: { use_disabled?: boolean; slices?: DisabledSlice[]; quality?: QualityRulesParam | null };
+  transforms?: boolean;
+  items?: { condition_by?: string | null; resample?: ResampleParam | null };
+  measures?: { window?: MeasureWindowParam; categories?: string[]; options?: MeasureOptionsParam };
+  transitions?: TransitionOptionsParam;
+  precision?: PrecisionOptionsParam;
+  growth?: { degree?: number | null; scale?: ProportionScale; correction?: Correction };
+  divergence?: DivergenceOptionsParam;
+  lmm?: LmmOptionsParam;
+};
+
+export async function listAnalysesRaw(): Promise<unknown> {
+  return withLoading(invoke("list_analyses"));
+}
+
+export async function loadAnalysisRaw(params: { id: string }): Promise<unknown> {
+  return withLoading(invoke("load_analysis", { id: params.id }));
+}
+
+export async function saveAnalysisRaw(params: { id?: string | null; name: string; description?: string | null; spec: AnalysisSpecParam }): Promise<unknown> {
+  return withLoading(invoke("save_analysis", {
+    id: params.id ?? null,
+    name: params.name,
+    description: params.description ?? null,
+    spec: params.spec,
+  }));
+}
+
+export async function duplicateAnalysisRaw(params: { id: string; name?: string | null }): Promise<unknown> {
+  return withLoading(invoke("duplicate_analysis", { id: params.id, name: params.name ?? null }));
+}
+
+export async function deleteAnalysisRaw(params: { id: string }): Promise<void> {
+  return withLoading(invoke("delete_analysis", { id: params.id }));
+}
+
+export async function runAnalysisRaw(params: { id: string; manifest?: boolean }): Promise<unknown> {
+  return withLoading(invoke("run_analysis", { id: params.id, manifest: params.manifest ?? null }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Reproducibility manifests
+   ────────────────────────────────────────────────────────────── */
+
+/** manifest for a result computed on the client (e.g. before exporting it) */
+export async function buildManifestRaw(params: { command: string; spec: AnalysisSpecParam; seed?: number | null; result: unknown }): Promise<unknown> {
+  return withLoading(invoke("build_manifest", {
+    command: params.command,
+    spec: params.spec,
+    seed: params.seed ?? null,
+    result: params.result,
+  }));
+}
+
+/** re-run from a manifest; with `original`, the report lists differing values */
+export async function rerunManifestRaw(params: { manifest: unknown; original?: unknown }): Promise<unknown> {
+  return withLoading(invoke("rerun_manifest", { manifest: params.manifest, original: params.original ?? null }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Reports and exports
+   ────────────────────────────────────────────────────────────── */
+
+export type ReportOutput = { html_path: string; pdf_path: string | null; pdf_error: string | null; manifest: unknown };
+
+/** self-contained HTML (and optionally PDF) report of a saved analysis */
+export async function generateReportRaw(params: { id: string; path?: string | null; pdf?: boolean }): Promise<ReportOutput> {
+  return withLoading(invoke("generate_report", { id: params.id, path: params.path ?? null, pdf: params.pdf ?? null }));
+}
+
+export type WorkbookExport = { path: string; sheets: string[]; tests: number; participants: number; sha256: string; manifest?: unknown };
+
+/** .xlsx with a Summary sheet, a per-test pivot, one sheet per test and the disabled slices */
+export async function exportWorkbookRaw(params: {
+  path?: string | null;
+  tests?: string[];
+  participants?: string[];
+  cohort?: string | null;
+  manifest?: boolean;
+}): Promise<WorkbookExport> {
+  return withLoading(invoke("export_workbook", {
+    path: params.path ?? null,
+    tests: params.tests ?? [],
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Local HTTP API
+   ────────────────────────────────────────────────────────────── */
+
+export type ApiInfo = { url: string; port: number; token: string; endpoints: string[] };
+
+export async function getApiServerRaw(): Promise<ApiInfo | null> {
+  return withLoading(invoke("get_api_server"));
+}
+
+/** serve the query commands on 127.0.0.1; a token is generated when none is given */
+export async function startApiServerRaw(params: { port?: number; token?: string | null } = {}): Promise<ApiInfo> {
+  return withLoading(invoke("start_api_server", { port: params.port ?? null, token: params.token ?? null }));
+}
+
+export async function stopApiServerRaw(): Promise<void> {
+  return withLoading(invoke("stop_api_server"));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Synthetic data
+   ────────────────────────────────────────────────────────────── */
+
+export type SynthCondition = {
+  name: string;
+  only_position: "subject" | "verb";
+  morpheme: string;
+  truth_value?: string;
+  baseline: number;
+  target_share: number;
+  onset_ms: number;
+  rise_ms: number;
+};
+
+export type SynthConfig = Partial<{
+  participants: number;
+  items_per_condition: number;
+  conditions: SynthCondition[];
+  seed: number | null;
+  sample_rate_hz: number;
+  video_ms: number;
+  picture_ms: number;
+  participant_sd: number;
+  item_sd: number;
+  blink_rate_hz: number;
+  track_loss_prob: number;
+  non_qac: number;
+  participant_prefix: string;
+}>;
+
+export type SynthSummary = {
+  path: string;
+  seed: number;
+  participants: number;
+  tests: number;
+  recordings: number;
+  gaze_rows: number;
+  valid_pct: number;
+};
+
+/** write a synthetic study DB (same schema, planted effect in synthetic_truth); open it with openDatabaseRaw */
+export async function generateSyntheticDbRaw(params: { path: string; config?: SynthConfig; overwrite?: boolean }): Promise<SynthSummary> {
+  return withLoading(invoke("generate_synthetic_db", {
+    path: params.path,
+    config: params.config ?? null,
+    overwrite: params.overwrite ?? false,
+  }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Catalog derivation from sentences
+   ────────────────────────────────────────────────────────────── */
+
+export type SentenceToken = { kind: "only" | "subject" | "verb" | "measure" | "object" | "unknown"; text: string };
+
+export type ParsedSentence = {
+  tokens: SentenceToken[];
+  subject: string | null;
+  verb: string | null;
+  measure: string | null;
+  object: string | null;
+  only_word: string | null;
+  only_position: "subject" | "verb" | null;
+  group: "Szinghai" | "Vzinghai" | null;
+  problems: string[];
+};
+
+export type CatalogFieldCheck = {
+  column: "group" | "only_position" | "morpheme";
+  curated: string | null;
+  derived: string | null;
+  status: "ok" | "fill" | "mismatch" | "unknown";
+};
+
+export type CatalogDerivation = {
+  rows: { test_name: string; sentence: string | null; parsed: ParsedSentence | null; fields: CatalogFieldCheck[] }[];
+  mismatches: number;
+  problem_rows: number;
+  fillable: number;
+  filled: number;
+  writable: boolean;
+  note: string | null;
+};
+
+export async function parseSentenceRaw(sentence: string): Promise<ParsedSentence> {
+  return withLoading(invoke("parse_sentence", { sentence }));
+}
+
+/** compare group / only_position / morpheme with the parsed sentences; `apply` fills blank cells */
+export async function deriveTestCatalogRaw(params: { apply?: boolean } = {}): Promise<CatalogDerivation> {
+  return withLoading(invoke("derive_test_catalog", { apply: params.apply ?? false }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Item- and participant-level summaries
+   ────────────────────────────────────────────────────────────── */
+
+export type ProportionSummary = { mean: number; sd: number | null; se: number | null; n: number };
+
+export type ItemStats = {
+  test_name: string;
+  factors: Record<string, string | null>;
+  n_participants: number;
+  n_samples: number;
+  boxes: Record<string, ProportionSummary>;
+  target: ProportionSummary | null;
+  condition_z: number | null;
+};
+
+/** per-test proportions as the mean of per-participant proportions (each child counts once) */
+export async function getItemStatsRaw(params: {
+  tests?: string[];
+  participants?: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  conditionBy?: string | null;
+  resample?: ResampleParam | null;
+  manifest?: boolean;
+} = {}): Promise<{ condition_by: string; items: ItemStats[]; manifest?: unknown }> {
+  return withLoading(invoke("get_item_stats", {
+    tests: params.tests ?? [],
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    options: { condition_by: params.conditionBy ?? null, resample: params.resample ?? null },
+    manifest: params.manifest ?? null,
+  }));
+}
+
+export type ParticipantSummary = {
+  participant: string;
+  is_qac: boolean | null;
+  tests_completed: number;
+  tests: string[];
+  samples: number;
+  mean_valid_pct: number | null;
+  correct: ProportionSummary | null;
+  conditions: { truth_value: string | null; only_position: string | null; tests: number; correct: ProportionSummary | null }[];
+  never_reaches_aoi: boolean | null;
+};
+
+/** per-child summary across their non-disabled tests; correct = blue samples / valid samples */
+export async function getParticipantSummaryRaw(params: {
+  participants?: string[];
+  cohort?: string | null;
+  aoi?: AoiSetsParam | null;
+  resample?: ResampleParam | null;
+  manifest?: boolean;
+} = {}): Promise<{ participants: ParticipantSummary[]; manifest?: unknown }> {
+  return withLoading(invoke("get_participant_summary", {
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    aoi: params.aoi ?? null,
+    resample: params.resample ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Window measures
+   ────────────────────────────────────────────────────────────── */
+
+export type MeasureWindowParam = WindowParamsParam & { word_window?: string | null; word_shift_ms?: number };
+export type MeasureOptionsParam = { min_fixation_ms?: number; max_gap_ms?: number; invalid?: string[] };
+
+export type TrialMeasure = {
+  cohort?: string;
+  test_name: string;
+  participant: string;
+  timeline: string;
+  recording: string;
+  category: string;
+  factors: Record<string, string | null>;
+  window_start_ms: number;
+  window_ms: number;
+  valid_ms: number;
+  time_to_first_fixation_ms: number | null;
+  first_fixation_ms: number | null;
+  dwell_ms: number;
+  visits: number;
+  window_prop: number;
+  valid_prop: number | null;
+};
+
+/** trial × AOI category table (TTFF, first fixation, dwell, visits) for one window */
+export async function getWindowMeasuresRaw(params: {
+  tests: string[];
+  participants?: string[];
+  cohort?: string | null;
+  window?: MeasureWindowParam | null;
+  categories?: string[] | null;
+  options?: MeasureOptionsParam | null;
+  manifest?: boolean;
+}): Promise<{ rows: TrialMeasure[]; skipped: string[]; manifest?: unknown }> {
+  return withLoading(invoke("get_window_measures", {
+    tests: params.tests,
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    window: params.window ?? null,
+    categories: params.categories ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   AOI transitions
+   ────────────────────────────────────────────────────────────── */
+
+export type TransitionOptionsParam = { categories?: string[]; include_other?: boolean; invalid?: string[]; condition_by?: string; resample?: ResampleParam | null };
+
+export type TransitionMatrix = {
+  counts: number[][];
+  probabilities: number[][];
+  visits: number[];
+  transitions: number;
+  entropy: { stationary: number; transition: number; stationary_norm: number; transition_norm: number } | null;
+};
+
+export type TransitionResult = {
+  states: string[];
+  condition_by: string;
+  recordings: { cohort?: string; test_name: string; participant: string; timeline: string; recording: string; condition: string | null; matrix: TransitionMatrix }[];
+  conditions: {
+    condition: string | null;
+    recordings: number;
+    matrix: TransitionMatrix;
+    stationary_entropy: ProportionSummary | null;
+    transition_entropy: ProportionSummary | null;
+  }[];
+};
+
+/** first-order AOI-category transitions per recording, pooled per condition, with entropies (bits) */
+export async function getAoiTransitionsRaw(params: {
+  tests: string[];
+  participants?: string[];
+  cohort?: string | null;
+  options?: TransitionOptionsParam | null;
+  manifest?: boolean;
+}): Promise<TransitionResult & { manifest?: unknown }> {
+  return withLoading(invoke("get_aoi_transitions", {
+    tests: params.tests,
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+/* ──────────────────────────────────────────────────────────────
+   Calibration / precision quality
+   ────────────────────────────────────────────────────────────── */
+
+export type EyeValues = { left: number | null; right: number | null; average: number | null };
+export type QualityValues = { accuracy: EyeValues; precision: EyeValues; precision_sd: EyeValues };
+export type CalibrationRecord = {
+  recording: string;
+  participant: string | null;
+  stage: "calibration" | "validation";
+  deg: QualityValues;
+  px: QualityValues;
+};
+export type CalibrationImport = {
+  path: string;
+  rows: number;
+  records: number;
+  skipped_rows: number;
+  columns_used: string[];
+  ignored_columns: string[];
+  unmatched_recordings: string[];
+};
+
+export type PrecisionOptionsParam = { velocity_threshold_deg_s?: number; px_per_deg?: number; min_fixation_ms?: number; max_gap_ms?: number };
+export type QualityRulesParam = {
+  max_accuracy_deg?: number | null;
+  max_precision_deg?: number | null;
+  max_accuracy_px?: number | null;
+  max_precision_px?: number | null;
+  worse_eye?: boolean;
+  exclude_uncalibrated?: boolean;
+  max_rms_s2s_px?: number | null;
+  max_rms_s2s_deg?: number | null;
+  precision?: PrecisionOptionsParam;
+};
+
+export type SlicePrecision = {
+  cohort?: string;
+  test_name: string;
+  participant: string;
+  recording: string;
+  samples: number;
+  fixations: number;
+  pairs: number;
+  rms_s2s_px: number | null;
+  rms_s2s_deg: number | null;
+};
+export type ParticipantQuality = {
+  cohort?: string;
+  participant: string;
+  slices: number;
+  recordings: number;
+  rms_s2s_px: ProportionSummary | null;
+  rms_s2s_deg: ProportionSummary | null;
+  calibrated_recordings: number;
+  accuracy_deg: number | null;
+  precision_deg: number | null;
+  accuracy_px: number | null;
+  precision_px: number | null;
+};
+export type PrecisionResult = { options: Required<PrecisionOptionsParam>; slices: SlicePrecision[]; participants: ParticipantQuality[] };
+export type QualityExclusion = { slice: DisabledSlice; reasons: string[] };
+
+/** import a Tobii calibration / validation export (CSV/TSV); `replace` defaults to true */
+export async function importCalibrationRaw(params: { path: string; replace?: boolean }): Promise<CalibrationImport> {
+  return withLoading(invoke("import_calibration", { path: params.path, replace: params.replace ?? null }));
+}
+
+export async function getCalibrationRaw(): Promise<CalibrationRecord[]> {
+  return withLoading(invoke("get_calibration"));
+}
+
+/** RMS sample-to-sample precision inside fixations; empty `tests` = every test */
+export async function getPrecisionRaw(params: {
+  tests?: string[];
+  participants?: string[];
+  cohort?: string | null;
+  options?: PrecisionOptionsParam | null;
+  manifest?: boolean;
+}): Promise<PrecisionResult & { manifest?: unknown }> {
+  return withLoading(invoke("get_precision", {
+    tests: params.tests ?? [],
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+    options: params.options ?? null,
+    manifest: params.manifest ?? null,
+  }));
+}
+
+/** slices a set of quality rules would exclude */
+export async function previewQualityExclusionsRaw(params: { rules: QualityRulesParam; tests?: string[]; participants?: string[]; cohort?: string | null }): Promise<QualityExclusion[]> {
+  return withLoading(invoke("preview_quality_exclusions", {
+    rules: params.rules,
+    tests: params.tests ?? [],
+    participants: params.participants ?? [],
+    cohort: params.cohort ?? null,
+  }));
+}
@@ -0,0 +1,16 @@
+/** Epoch ms of a gaze row, from the backend-parsed "Exact time" (NaN when it was malformed). */
+export function timeMs(g: { time_us?: number | null }): number {
+  return g.time_us == null ? NaN : g.time_us / 1000;
+}
+
+/** [first, last] epoch ms over rows with a parsed time ([0, 0] when there are none); rows need not be sorted. */
+export function timeSpanMs(rows: { time_us?: number | null }[]): [number, number] {
+  let first = Infinity, last = -Infinity;
+  for (const r of rows) {
+    const t = timeMs(r);
+    if (Number.isNaN(t)) continue;
+    if (t < first) first = t;
+    if (t > last) last = t;
+  }
+  return first <= last ? [first, last] : [0, 0];
+}
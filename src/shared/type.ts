@@ -19,12 +19,26 @@ export interface GazeData {
   participant: string;
   recording: string;
   timestamp: string;
+  /** parsed "Exact time" (epoch µs) and ms since the recording's first sample; null when malformed */
+  time_us: number | null;
+  rel_ms: number | null;
   test_name: string;
 }
 
+export interface ProportionTransforms {
+  count: number;
+  n: number;
+  proportion: number;
+  elog: number;
+  elog_weight: number;
+  arcsine: number;
+}
+
 export interface GazeStats {
   box_percentages: Record<string, number>;
   total_points: number;
+  box_counts?: Record<string, number>;
+  transforms?: Record<string, ProportionTransforms> | null;
 }
 
 export interface DisabledSlice {